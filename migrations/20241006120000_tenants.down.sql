@@ -0,0 +1,21 @@
+-- Serial numbers are only unique per tenant since this migration, so the
+-- global constraint cannot come back once two tenants share one. Which token
+-- to keep is not ours to decide: the rollback is refused before any change.
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT serial_number FROM refresh_tokens
+        GROUP BY serial_number HAVING COUNT(*) > 1
+    ) THEN
+        RAISE EXCEPTION 'Cannot revert the tenants migration: several tenants own a token for the same serial number. Delete the duplicates first.';
+    END IF;
+END
+$$;
+
+ALTER TABLE refresh_tokens
+    DROP CONSTRAINT refresh_tokens_tenant_id_serial_number_key,
+    DROP COLUMN tenant_id,
+    ADD CONSTRAINT refresh_tokens_serial_number_key UNIQUE (serial_number);
+
+DROP TABLE IF EXISTS api_keys;
+DROP TABLE IF EXISTS tenants;
@@ -0,0 +1,28 @@
+CREATE TABLE tenants (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY,
+    tenant_id UUID NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+-- Tokens registered before tenants existed are moved to a "default" tenant.
+INSERT INTO tenants (id, name, created_at, updated_at)
+SELECT '00000000-0000-0000-0000-000000000000', 'default', NOW(), NOW()
+WHERE EXISTS (SELECT 1 FROM refresh_tokens);
+
+ALTER TABLE refresh_tokens
+    ADD COLUMN tenant_id UUID REFERENCES tenants (id) ON DELETE CASCADE;
+
+UPDATE refresh_tokens SET tenant_id = '00000000-0000-0000-0000-000000000000';
+
+ALTER TABLE refresh_tokens
+    ALTER COLUMN tenant_id SET NOT NULL,
+    DROP CONSTRAINT refresh_tokens_serial_number_key,
+    ADD CONSTRAINT refresh_tokens_tenant_id_serial_number_key UNIQUE (tenant_id, serial_number);
@@ -0,0 +1,6 @@
+ALTER TABLE refresh_tokens
+    DROP COLUMN expires_at,
+    DROP COLUMN provider;
+
+ALTER TABLE api_keys
+    DROP COLUMN scopes;
@@ -0,0 +1,9 @@
+ALTER TABLE api_keys
+    ADD COLUMN scopes TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE refresh_tokens
+    ADD COLUMN provider VARCHAR(32) NOT NULL DEFAULT 'bambulab',
+    ADD COLUMN expires_at TIMESTAMPTZ;
+
+ALTER TABLE refresh_tokens
+    ALTER COLUMN provider DROP DEFAULT;
@@ -0,0 +1,2 @@
+ALTER TABLE refresh_tokens
+    ADD COLUMN idempotency_key VARCHAR(255);
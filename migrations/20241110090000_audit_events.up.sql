@@ -0,0 +1,29 @@
+-- Not tied to the tenants, so that the trail of a tenant outlives it.
+CREATE TABLE audit_events (
+    id UUID PRIMARY KEY,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    tenant_id UUID NOT NULL,
+    api_key_id UUID NOT NULL,
+    action VARCHAR(64) NOT NULL,
+    outcome VARCHAR(16) NOT NULL,
+    serial_number VARCHAR(255),
+    token_id UUID,
+    detail TEXT,
+    request_id VARCHAR(128),
+    client_ip TEXT
+);
+
+CREATE INDEX audit_events_tenant_id_occurred_at_idx ON audit_events (tenant_id, occurred_at DESC);
+CREATE INDEX audit_events_occurred_at_idx ON audit_events (occurred_at);
+
+-- Events are appended, and deleted once past the retention period, but never
+-- changed.
+CREATE FUNCTION audit_events_reject_update() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit events are append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_events_append_only
+    BEFORE UPDATE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION audit_events_reject_update();
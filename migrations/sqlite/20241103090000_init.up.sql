@@ -0,0 +1,32 @@
+-- The schema the Postgres migrations arrive at, in a single step since SQLite
+-- databases have no earlier history to migrate. Identifiers are UUID blobs and
+-- timestamps RFC 3339 strings.
+CREATE TABLE tenants (
+    id BLOB PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE api_keys (
+    id BLOB PRIMARY KEY NOT NULL,
+    tenant_id BLOB NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+    key_hash TEXT NOT NULL UNIQUE,
+    -- JSON array of the scopes.
+    scopes TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE refresh_tokens (
+    id BLOB PRIMARY KEY NOT NULL,
+    tenant_id BLOB NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+    provider TEXT NOT NULL,
+    serial_number TEXT NOT NULL,
+    token TEXT NOT NULL,
+    access_token TEXT,
+    idempotency_key TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    expires_at TEXT,
+    UNIQUE (tenant_id, serial_number)
+);
@@ -0,0 +1,25 @@
+-- Not tied to the tenants, so that the trail of a tenant outlives it.
+CREATE TABLE audit_events (
+    id BLOB PRIMARY KEY NOT NULL,
+    occurred_at TEXT NOT NULL,
+    tenant_id BLOB NOT NULL,
+    api_key_id BLOB NOT NULL,
+    action TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    serial_number TEXT,
+    token_id BLOB,
+    detail TEXT,
+    request_id TEXT,
+    client_ip TEXT
+);
+
+CREATE INDEX audit_events_tenant_id_occurred_at_idx ON audit_events (tenant_id, occurred_at DESC);
+CREATE INDEX audit_events_occurred_at_idx ON audit_events (occurred_at);
+
+-- Events are appended, and deleted once past the retention period, but never
+-- changed.
+CREATE TRIGGER audit_events_append_only
+    BEFORE UPDATE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit events are append-only');
+END;
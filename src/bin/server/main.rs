@@ -1,53 +1,375 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use ferrisprinter::{
     application::{
         http::{HttpServer, HttpServerConfig},
-        providers::token_provider_manager::{self, TokenProviderManager},
+        providers::{config::ProviderConfig, token_provider_manager::TokenProviderManager},
+        reload, shutdown,
+        telemetry::{self, TracingGuard},
     },
-    domain::token::{
-        ports::provider_token_service::ProviderType, service::RefreshTokenServiceImpl,
+    config::Config,
+    domain::{
+        audit::{
+            ports::audit::{AuditLog, AuditService},
+            service::AuditServiceImpl,
+        },
+        health::service::HealthServiceImpl,
+        proxy::service::ProxyServiceImpl,
+        tenant::{
+            models::tenant::{CreateTenantError, CreateTenantRequest, TenantName},
+            ports::tenant::{TenantRepository, TenantService},
+            service::TenantServiceImpl,
+        },
+        token::{
+            ports::{
+                provider_token_service::{ProviderTokenService, ProviderType},
+                refresh_token::RefreshTokenRepository,
+            },
+            service::RefreshTokenServiceImpl,
+        },
     },
-    env::Env,
+    env::{Command, Env, MigrateCommand},
     infrastructure::{
-        db::postgres::Postgres,
+        audit::{
+            memory::audit_log::InMemoryAuditLog, postgres::audit_log::PostgresAuditLog,
+            sqlite::audit_log::SqliteAuditLog,
+        },
+        db::Database,
+        proxy::bambulab_api_client::BambuLabApiClient,
+        resilience::ResilientClient,
+        tenant::{
+            memory::tenant_repository::InMemoryTenantRepository,
+            postgres::tenant_repository::PostgresTenantRepository,
+            sqlite::tenant_repository::SqliteTenantRepository,
+        },
         token::{
+            memory::refresh_token_repository::InMemoryRefreshTokenRepository,
             postgres::refresh_token_repository::PostgresRefreshTokenRepository,
-            providers::bambulab_provider::BambuLabProviderTokenService,
+            providers::{
+                bambulab_provider::BambuLabProviderTokenService,
+                fake_provider::FakeProviderTokenService,
+            },
+            sqlite::refresh_token_repository::SqliteRefreshTokenRepository,
         },
     },
 };
+use tokio_util::sync::CancellationToken;
+
+const POOL_METRICS_INTERVAL: Duration = Duration::from_secs(15);
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const AUDIT_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv::dotenv().ok();
-    tracing_subscriber::fmt::init();
-
     let env = Arc::new(Env::parse());
 
-    let postgres = Postgres::new(Arc::clone(&env)).await?;
+    if let Some(Command::Migrate { action }) = &env.command {
+        let config = Config::load_migration(&env)?;
+        let _tracing_guard = telemetry::init_tracing(&config.logging)?;
+        let database = Database::connect(&config.database).await?;
+        let result = migrate(&database, action).await;
+        database.close().await;
+        return result;
+    }
 
-    let postgres = Arc::new(postgres);
-    let server_config = HttpServerConfig { port: &env.port };
-    let mut token_provider_manager = TokenProviderManager::new();
-    let bambulab_provider = BambuLabProviderTokenService::new(
-        "".to_string(),
-        "https://bambulab.com/api/sign-in/form".to_string(),
-    );
+    let config = Config::load(&env)?;
+
+    let tracing_guard = telemetry::init_tracing(&config.logging)?;
+    telemetry::prometheus_handle();
+
+    let database = Database::connect(&config.database).await?;
+    if config.database.run_migrations {
+        database.migrate().await?;
+    }
+
+    let shutdown = shutdown::on_signal();
+
+    let pool_metrics = database.clone();
+    let pool_metrics_shutdown = shutdown.child_token();
+    let pool_metrics_task = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POOL_METRICS_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => pool_metrics.record_pool_metrics(),
+                _ = pool_metrics_shutdown.cancelled() => break,
+            }
+        }
+    });
+
+    let result = match &database {
+        Database::Postgres(postgres) => {
+            serve_providers(
+                &env,
+                &config,
+                &tracing_guard,
+                &shutdown,
+                database.clone(),
+                PostgresTenantRepository::new(Arc::clone(postgres)),
+                PostgresRefreshTokenRepository::new(Arc::clone(postgres)),
+                PostgresAuditLog::new(Arc::clone(postgres)),
+            )
+            .await
+        }
+        Database::Sqlite(sqlite) => {
+            serve_providers(
+                &env,
+                &config,
+                &tracing_guard,
+                &shutdown,
+                database.clone(),
+                SqliteTenantRepository::new(Arc::clone(sqlite)),
+                SqliteRefreshTokenRepository::new(Arc::clone(sqlite)),
+                SqliteAuditLog::new(Arc::clone(sqlite)),
+            )
+            .await
+        }
+        Database::Memory(memory) => {
+            serve_providers(
+                &env,
+                &config,
+                &tracing_guard,
+                &shutdown,
+                database.clone(),
+                InMemoryTenantRepository::new(Arc::clone(memory)),
+                InMemoryRefreshTokenRepository::new(Arc::clone(memory)),
+                InMemoryAuditLog::new(Arc::clone(memory)),
+            )
+            .await
+        }
+    };
+
+    // The server may also stop on an error, in which case the background
+    // tasks are told to stop here.
+    shutdown.cancel();
+    if let Err(e) = pool_metrics_task.await {
+        tracing::warn!("pool metrics task failed: {}", e);
+    }
+    database.close().await;
+
+    result
+}
+
+/// Serves the API with the real providers, or with fake ones in demo mode.
+#[allow(clippy::too_many_arguments)]
+async fn serve_providers<T, R, L>(
+    env: &Arc<Env>,
+    config: &Config,
+    tracing_guard: &TracingGuard,
+    shutdown: &CancellationToken,
+    database: Database,
+    tenant_repository: T,
+    refresh_token_repository: R,
+    audit_log: L,
+) -> Result<()>
+where
+    T: TenantRepository,
+    R: RefreshTokenRepository,
+    L: AuditLog,
+{
+    if env.demo {
+        tracing::warn!("demo mode: the providers are fake and accept any credentials");
+        return serve(
+            env,
+            config,
+            tracing_guard,
+            shutdown,
+            database,
+            tenant_repository,
+            refresh_token_repository,
+            audit_log,
+            |_, _| Ok(FakeProviderTokenService::new()),
+        )
+        .await;
+    }
+
+    serve(
+        env,
+        config,
+        tracing_guard,
+        shutdown,
+        database,
+        tenant_repository,
+        refresh_token_repository,
+        audit_log,
+        |provider, http_client| match provider.provider_type {
+            ProviderType::BambuLab => Ok(BambuLabProviderTokenService::new(
+                provider.api_url.clone(),
+                provider.login_url.clone(),
+            )?
+            .with_http_client(http_client)),
+        },
+    )
+    .await
+}
 
-    token_provider_manager.register_provider(ProviderType::BambuLab, bambulab_provider);
-    let token_provider_manager = Arc::new(token_provider_manager);
+/// Serves the API until `shutdown` is cancelled, storing the tenants, the
+/// tokens and the audit events in the configured database and logging in
+/// through the providers built by `build_provider`.
+#[allow(clippy::too_many_arguments)]
+async fn serve<T, R, L, P>(
+    env: &Arc<Env>,
+    config: &Config,
+    tracing_guard: &TracingGuard,
+    shutdown: &CancellationToken,
+    database: Database,
+    tenant_repository: T,
+    refresh_token_repository: R,
+    audit_log: L,
+    build_provider: impl Fn(&ProviderConfig, ResilientClient) -> Result<P>,
+) -> Result<()>
+where
+    T: TenantRepository,
+    R: RefreshTokenRepository,
+    L: AuditLog,
+    P: ProviderTokenService,
+{
+    // The proxy only supports Bambu Lab so far.
+    let bambulab_config = config
+        .providers
+        .get(&ProviderType::BambuLab)
+        .context("the bambulab provider must be enabled")?;
+    let bambulab_http_client = bambulab_config.http_client()?;
 
-    let refresh_token_repository = PostgresRefreshTokenRepository::new(Arc::clone(&postgres));
+    let server_config = HttpServerConfig {
+        address: config.server.address.clone(),
+        tls: config.server.tls.clone(),
+        admin_api_key: config.auth.admin_api_key.as_ref().map(|key| key.expose()),
+        shutdown_timeout: config.server.shutdown_timeout,
+        login_rate_limit: config.auth.login_rate_limit,
+    };
+    // The token service and the API client of a provider share its client,
+    // and thus its circuit breaker.
+    let token_provider_manager = Arc::new(TokenProviderManager::from_config(
+        &config.providers,
+        |provider| match provider.provider_type {
+            ProviderType::BambuLab => build_provider(provider, bambulab_http_client.clone()),
+        },
+    )?);
 
-    let refresh_token_service = RefreshTokenServiceImpl::new(
+    let refresh_token_service = Arc::new(RefreshTokenServiceImpl::new(
         refresh_token_repository,
         Arc::clone(&token_provider_manager),
+        config.auth.lockout,
+    ));
+
+    let proxy_service = ProxyServiceImpl::new(
+        ProviderType::BambuLab,
+        Arc::clone(&refresh_token_service),
+        BambuLabApiClient::new(bambulab_config.api_url.clone())?
+            .with_http_client(bambulab_http_client),
+        BambuLabApiClient::default_allowlist(),
+    );
+
+    let tenant_service = TenantServiceImpl::new(tenant_repository);
+    if env.demo {
+        create_demo_tenant(&tenant_service).await?;
+    }
+
+    let health_service = HealthServiceImpl::new(
+        database,
+        Arc::clone(&token_provider_manager),
+        config.server.health_check_timeout,
     );
 
-    let http_server = HttpServer::new(Arc::new(refresh_token_service), server_config).await?;
+    let audit_service = Arc::new(AuditServiceImpl::new(audit_log, config.audit.retention));
+
+    let http_server = HttpServer::new(
+        refresh_token_service,
+        Arc::new(tenant_service),
+        Arc::new(proxy_service),
+        Arc::new(health_service),
+        Arc::clone(&audit_service),
+        server_config,
+    )
+    .await?;
+
+    let log_level = tracing_guard.log_level();
+    let login_rate_limiter = http_server.login_rate_limiter();
+    let reload_task = tokio::spawn(reload::watch(
+        Arc::clone(env),
+        CONFIG_POLL_INTERVAL,
+        shutdown.child_token(),
+        move |config| {
+            if let Err(e) = log_level.set(&config.logging.level) {
+                tracing::warn!("{:#}", e);
+            }
+            login_rate_limiter.set_config(config.auth.login_rate_limit);
+            tracing::info!(
+                "applied the log level {:?} and the login rate limits, other settings apply on restart",
+                config.logging.level
+            );
+        },
+    ));
+
+    let audit_purge_shutdown = shutdown.child_token();
+    let audit_purge_task = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(AUDIT_PURGE_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = audit_service.purge_expired().await {
+                        tracing::warn!("failed to purge the expired audit events: {}", e);
+                    }
+                }
+                _ = audit_purge_shutdown.cancelled() => break,
+            }
+        }
+    });
+
+    let result = http_server.run(shutdown.clone()).await;
+
+    shutdown.cancel();
+    if let Err(e) = reload_task.await {
+        tracing::warn!("configuration reload task failed: {}", e);
+    }
+    if let Err(e) = audit_purge_task.await {
+        tracing::warn!("audit purge task failed: {}", e);
+    }
+
+    result
+}
+
+/// Creates the `demo` tenant, printing its API key so that the API can be
+/// tried right away. A database kept across runs already holds it.
+///
+/// The key is printed once on stderr rather than logged, so that it stays out
+/// of the logs, which are written to stdout to be shipped and retained.
+async fn create_demo_tenant(tenant_service: &impl TenantService) -> Result<()> {
+    let request = CreateTenantRequest::new(TenantName::new("demo")?);
+    match tenant_service.create_tenant(&request).await {
+        Ok((tenant, api_key)) => {
+            tracing::info!("demo mode: created the tenant {}", tenant.id);
+            eprintln!("API key of the demo tenant: {}", api_key.expose());
+            Ok(())
+        }
+        Err(CreateTenantError::Duplicate { .. }) => {
+            tracing::info!("demo mode: the demo tenant already exists");
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
 
-    http_server.run().await
+async fn migrate(database: &Database, action: &MigrateCommand) -> Result<()> {
+    match action {
+        MigrateCommand::Up => database.migrate().await,
+        MigrateCommand::Down { target } => database.revert_migrations(*target).await,
+        MigrateCommand::Status => {
+            for migration in database.migration_status().await? {
+                let state = match (migration.applied, migration.modified) {
+                    (true, true) => "modified",
+                    (true, false) => "applied",
+                    (false, _) => "pending",
+                };
+                println!(
+                    "{:>14}  {:<8}  {}",
+                    migration.version, state, migration.description
+                );
+            }
+            Ok(())
+        }
+    }
 }
@@ -1,2 +1,5 @@
 pub mod http;
 pub mod providers;
+pub mod reload;
+pub mod shutdown;
+pub mod telemetry;
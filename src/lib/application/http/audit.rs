@@ -0,0 +1,75 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
+
+use axum::{async_trait, extract::ConnectInfo, extract::FromRequestParts, http::request::Parts};
+
+use crate::domain::audit::models::audit::{
+    AuditAction, AuditEvent, AuditOutcome, RecordAuditEventError,
+};
+
+use super::{
+    auth::CurrentTenant,
+    handlers::{ApiError, ErrorCode},
+    problem::RequestId,
+};
+
+/// Only returned for actions revealing a secret, which are not carried out
+/// unless on record.
+impl From<RecordAuditEventError> for ApiError {
+    fn from(_: RecordAuditEventError) -> Self {
+        Self::ServiceUnavailable(
+            ErrorCode::AuditLogUnavailable,
+            "The action could not be recorded in the audit log".to_string(),
+        )
+    }
+}
+
+/// Where a request comes from, recorded along with the audit events it
+/// causes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestOrigin {
+    pub request_id: Option<String>,
+    /// Absent for requests received on a Unix socket.
+    pub client_ip: Option<IpAddr>,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for RequestOrigin
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self {
+            request_id: parts
+                .extensions
+                .get::<RequestId>()
+                .map(|RequestId(id)| id.clone()),
+            client_ip: parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address.ip()),
+        })
+    }
+}
+
+impl RequestOrigin {
+    /// An event of `action` performed by `caller` through this request.
+    pub fn event(&self, caller: &CurrentTenant, action: AuditAction) -> AuditEvent {
+        AuditEvent::new(caller.tenant.id, caller.api_key.id, action)
+            .with_request(self.request_id.clone(), self.client_ip)
+    }
+}
+
+/// The outcome of a handler: refusing the caller is a denial, anything else
+/// going wrong a failure.
+pub fn outcome<T>(result: &Result<T, ApiError>) -> AuditOutcome {
+    match result {
+        Ok(_) => AuditOutcome::Success,
+        Err(ApiError::Unauthorized(..) | ApiError::Forbidden(..)) => AuditOutcome::Denied,
+        Err(_) => AuditOutcome::Failure,
+    }
+}
@@ -0,0 +1,139 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts},
+};
+use sha2::{Digest, Sha256};
+
+use crate::domain::{
+    audit::ports::audit::AuditService,
+    health::ports::health::HealthService,
+    proxy::ports::proxy::ProxyService,
+    tenant::{
+        models::{
+            api_key::{ApiKey, ApiKeyInfo, AuthenticateTenantError, Scope},
+            tenant::Tenant,
+        },
+        ports::tenant::TenantService,
+    },
+    token::ports::refresh_token::RefreshTokenService,
+};
+
+use super::{
+    handlers::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// The tenant on whose behalf a request is made, resolved from the
+/// `Authorization: Bearer <api key>` header.
+#[derive(Debug, Clone)]
+pub struct CurrentTenant {
+    pub tenant: Tenant,
+    pub api_key: ApiKeyInfo,
+}
+
+impl CurrentTenant {
+    pub fn require_scope(&self, scope: Scope) -> Result<(), ApiError> {
+        if self.api_key.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden(
+                ErrorCode::InsufficientScope,
+                format!("API key is missing the {} scope", scope),
+            ))
+        }
+    }
+}
+
+/// Marker extractor for requests authenticated with the operator key
+/// configured through [crate::env::Env::admin_api_key].
+#[derive(Debug, Clone, Copy)]
+pub struct AdminAccess;
+
+pub(super) fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+fn bearer_token(parts: &Parts) -> Result<&str, ApiError> {
+    parts
+        .headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            ApiError::Unauthorized(
+                ErrorCode::MissingApiKey,
+                "Missing bearer API key".to_string(),
+            )
+        })
+}
+
+impl From<AuthenticateTenantError> for ApiError {
+    fn from(e: AuthenticateTenantError) -> Self {
+        match e {
+            AuthenticateTenantError::InvalidApiKey => {
+                Self::Unauthorized(ErrorCode::InvalidApiKey, "Invalid API key".to_string())
+            }
+            AuthenticateTenantError::DatabaseError(cause) => {
+                Self::InternalServerError(cause.to_string())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<R, T, P, H, A> FromRequestParts<AppState<R, T, P, H, A>> for CurrentTenant
+where
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState<R, T, P, H, A>,
+    ) -> Result<Self, Self::Rejection> {
+        // Already resolved by [super::rate_limit::limit_requests].
+        if let Some(caller) = parts.extensions.get::<CurrentTenant>() {
+            return Ok(caller.clone());
+        }
+
+        let api_key = ApiKey::new(bearer_token(parts)?).map_err(|_| {
+            ApiError::Unauthorized(ErrorCode::InvalidApiKey, "Invalid API key".to_string())
+        })?;
+
+        let (tenant, api_key) = state.tenant_service.authenticate(&api_key).await?;
+
+        Ok(CurrentTenant { tenant, api_key })
+    }
+}
+
+#[async_trait]
+impl<R, T, P, H, A> FromRequestParts<AppState<R, T, P, H, A>> for AdminAccess
+where
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState<R, T, P, H, A>,
+    ) -> Result<Self, Self::Rejection> {
+        let key = bearer_token(parts)?;
+
+        match state.admin_api_key_hash.as_deref() {
+            Some(expected) if expected == hash_key(key) => Ok(AdminAccess),
+            _ => Err(ApiError::Unauthorized(
+                ErrorCode::InvalidApiKey,
+                "Invalid admin API key".to_string(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use axum::{
+    extract::{OriginalUri, Request},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+pub static DEPRECATION_HEADER: HeaderName = HeaderName::from_static("deprecation");
+pub static SUNSET_HEADER: HeaderName = HeaderName::from_static("sunset");
+pub static LINK_HEADER: HeaderName = HeaderName::from_static("link");
+
+/// When the unversioned routes were deprecated, as an RFC 9745 structured date.
+const DEPRECATED_SINCE: &str = "@1792368000";
+/// When the unversioned routes will be removed, as an RFC 8594 HTTP-date.
+const SUNSET_AT: &str = "Fri, 30 Apr 2027 00:00:00 GMT";
+
+/// Marks responses of the unversioned `/api` alias as deprecated, pointing
+/// clients to the same route under `/api/v1`.
+pub async fn deprecated_alias(request: Request, next: Next) -> Response {
+    // Nested routers see the path without its prefix.
+    let successor = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path())
+        .unwrap_or_else(|| request.uri().path())
+        .strip_prefix("/api")
+        .map(|path| format!("</api/v1{}>; rel=\"successor-version\"", path));
+
+    let mut response = next.run(request).await;
+
+    let headers = response.headers_mut();
+    headers.insert(
+        DEPRECATION_HEADER.clone(),
+        HeaderValue::from_static(DEPRECATED_SINCE),
+    );
+    headers.insert(SUNSET_HEADER.clone(), HeaderValue::from_static(SUNSET_AT));
+    if let Some(value) = successor.and_then(|link| HeaderValue::from_str(&link).ok()) {
+        headers.insert(LINK_HEADER.clone(), value);
+    }
+
+    response
+}
@@ -0,0 +1,101 @@
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{header::CONTENT_TYPE, request::Parts, HeaderMap, StatusCode},
+};
+use serde::de::DeserializeOwned;
+use serde_json::error::Category;
+
+use super::handlers::{ApiError, ErrorCode};
+
+/// JSON request body whose rejections use the API error envelope: a body over
+/// the size limit is a `413`, malformed JSON a `400`, and a value of the wrong
+/// type a `422` naming the field.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonBody<T>(pub T);
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|mime| {
+            let mime = mime.trim();
+            mime.eq_ignore_ascii_case("application/json") || mime.ends_with("+json")
+        })
+        .unwrap_or(false)
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for JsonBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !is_json(request.headers()) {
+            return Err(ApiError::UnsupportedMediaType(
+                ErrorCode::InvalidRequest,
+                "Expected a request body with Content-Type: application/json".to_string(),
+            ));
+        }
+
+        let bytes = Bytes::from_request(request, state)
+            .await
+            .map_err(|e| match e.status() {
+                StatusCode::PAYLOAD_TOO_LARGE => {
+                    ApiError::PayloadTooLarge(ErrorCode::PayloadTooLarge, e.body_text())
+                }
+                _ => ApiError::BadRequest(ErrorCode::InvalidRequest, e.body_text()),
+            })?;
+
+        let deserializer = &mut serde_json::Deserializer::from_slice(&bytes);
+        serde_path_to_error::deserialize(deserializer)
+            .map(JsonBody)
+            .map_err(|e| match e.inner().classify() {
+                Category::Data => {
+                    let field = match e.path().to_string().as_str() {
+                        "." => "body".to_string(),
+                        path => path.to_string(),
+                    };
+                    ApiError::invalid_field(&field, e.inner())
+                }
+                Category::Syntax | Category::Eof | Category::Io => ApiError::BadRequest(
+                    ErrorCode::InvalidRequest,
+                    format!("Request body is not valid JSON: {}", e.inner()),
+                ),
+            })
+    }
+}
+
+/// Query string whose rejections use the API error envelope: a parameter
+/// with an invalid value is a `422` naming the parameter.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryParams<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for QueryParams<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query = parts.uri.query().unwrap_or_default();
+        let deserializer =
+            serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+        serde_path_to_error::deserialize(deserializer)
+            .map(QueryParams)
+            .map_err(|e| {
+                let field = match e.path().to_string().as_str() {
+                    "." => "query".to_string(),
+                    path => path.to_string(),
+                };
+                ApiError::invalid_field(&field, e.inner())
+            })
+    }
+}
@@ -1,9 +1,26 @@
+use std::time::Duration;
+
 use axum::{http::StatusCode, response::IntoResponse, Json};
-use create_refresh_token::ApiResponseBody;
+pub use create_refresh_token::{ApiErrorData, ApiResponseBody};
 use serde::Serialize;
+use utoipa::ToSchema;
 
+pub mod create_api_key;
 pub mod create_refresh_token;
+pub mod create_tenant;
+pub mod delete_refresh_token;
+pub mod delete_tenant;
+pub mod export_refresh_token;
+pub mod get_health;
+pub mod get_liveness;
+pub mod get_readiness;
 pub mod get_refresh_token;
+pub mod get_tenant;
+pub mod list_audit_events;
+pub mod list_refresh_tokens;
+pub mod list_tenants;
+pub mod proxy_bambu;
+pub mod update_refresh_token;
 
 pub struct ApiSuccess<T: Serialize + PartialEq>(StatusCode, Json<ApiResponseBody<T>>);
 
@@ -28,10 +45,197 @@ impl<T: Serialize + PartialEq> IntoResponse for ApiSuccess<T> {
     }
 }
 
+/// Stable, machine-readable identifier of an error, returned next to its
+/// human-readable message so clients can dispatch on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+#[schema(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InternalError,
+    InvalidRequest,
+    PayloadTooLarge,
+    ValidationFailed,
+    MissingApiKey,
+    InvalidApiKey,
+    InsufficientScope,
+    PathNotAllowed,
+    RateLimited,
+    LoginLockedOut,
+    TokenNotFound,
+    TenantNotFound,
+    DuplicateToken,
+    DuplicateTenant,
+    InvalidProviderCredentials,
+    ProviderVerificationRequired,
+    ProviderTokenRejected,
+    ProviderRateLimited,
+    ProviderUnavailable,
+    ProviderError,
+    AuditLogUnavailable,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InternalError => "internal_error",
+            ErrorCode::InvalidRequest => "invalid_request",
+            ErrorCode::PayloadTooLarge => "payload_too_large",
+            ErrorCode::ValidationFailed => "validation_failed",
+            ErrorCode::MissingApiKey => "missing_api_key",
+            ErrorCode::InvalidApiKey => "invalid_api_key",
+            ErrorCode::InsufficientScope => "insufficient_scope",
+            ErrorCode::PathNotAllowed => "path_not_allowed",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::LoginLockedOut => "login_locked_out",
+            ErrorCode::TokenNotFound => "token_not_found",
+            ErrorCode::TenantNotFound => "tenant_not_found",
+            ErrorCode::DuplicateToken => "duplicate_token",
+            ErrorCode::DuplicateTenant => "duplicate_tenant",
+            ErrorCode::InvalidProviderCredentials => "invalid_provider_credentials",
+            ErrorCode::ProviderVerificationRequired => "provider_verification_required",
+            ErrorCode::ProviderTokenRejected => "provider_token_rejected",
+            ErrorCode::ProviderRateLimited => "provider_rate_limited",
+            ErrorCode::ProviderUnavailable => "provider_unavailable",
+            ErrorCode::ProviderError => "provider_error",
+            ErrorCode::AuditLogUnavailable => "audit_log_unavailable",
+        }
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// A problem with a single field of a request body or query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ApiError {
     InternalServerError(String),
-    UnprocessableEntity(String),
+    /// `422 Unprocessable Entity` listing every invalid field.
+    InvalidFields(Vec<FieldError>),
+    BadRequest(ErrorCode, String),
+    Unauthorized(ErrorCode, String),
+    Forbidden(ErrorCode, String),
+    NotFound(ErrorCode, String),
+    Conflict(ErrorCode, String),
+    PayloadTooLarge(ErrorCode, String),
+    UnsupportedMediaType(ErrorCode, String),
+    UnprocessableEntity(ErrorCode, String),
+    /// `429 Too Many Requests`, telling the client when to retry if known.
+    TooManyRequests(ErrorCode, String, Option<Duration>),
+    BadGateway(ErrorCode, String),
+    ServiceUnavailable(ErrorCode, String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::InvalidFields(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::BadRequest(..) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(..) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(..) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(..) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(..) => StatusCode::CONFLICT,
+            ApiError::PayloadTooLarge(..) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::UnsupportedMediaType(..) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::UnprocessableEntity(..) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::TooManyRequests(..) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::BadGateway(..) => StatusCode::BAD_GATEWAY,
+            ApiError::ServiceUnavailable(..) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::InternalServerError(_) => ErrorCode::InternalError,
+            ApiError::InvalidFields(_) => ErrorCode::ValidationFailed,
+            ApiError::BadRequest(code, _)
+            | ApiError::Unauthorized(code, _)
+            | ApiError::Forbidden(code, _)
+            | ApiError::NotFound(code, _)
+            | ApiError::Conflict(code, _)
+            | ApiError::PayloadTooLarge(code, _)
+            | ApiError::UnsupportedMediaType(code, _)
+            | ApiError::UnprocessableEntity(code, _)
+            | ApiError::TooManyRequests(code, _, _)
+            | ApiError::BadGateway(code, _)
+            | ApiError::ServiceUnavailable(code, _) => *code,
+        }
+    }
+
+    /// The message sent to the client; internal errors never disclose their cause.
+    pub fn message(&self) -> &str {
+        match self {
+            ApiError::InternalServerError(_) => "Internal server error",
+            ApiError::InvalidFields(_) => "The request contains invalid fields",
+            ApiError::BadRequest(_, message)
+            | ApiError::Unauthorized(_, message)
+            | ApiError::Forbidden(_, message)
+            | ApiError::NotFound(_, message)
+            | ApiError::Conflict(_, message)
+            | ApiError::PayloadTooLarge(_, message)
+            | ApiError::UnsupportedMediaType(_, message)
+            | ApiError::UnprocessableEntity(_, message)
+            | ApiError::TooManyRequests(_, message, _)
+            | ApiError::BadGateway(_, message)
+            | ApiError::ServiceUnavailable(_, message) => message,
+        }
+    }
+}
+
+impl ApiError {
+    pub fn invalid_field(field: &str, message: impl ToString) -> Self {
+        Self::InvalidFields(vec![FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        }])
+    }
+
+    pub fn field_errors(&self) -> &[FieldError] {
+        match self {
+            ApiError::InvalidFields(errors) => errors,
+            _ => &[],
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ApiError::TooManyRequests(_, _, retry_after) => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+/// Collects the field errors of a request, so that all of them are reported at
+/// once rather than only the first.
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    /// Records the error of `result`, if any, against `field`.
+    pub fn check<T, E: ToString>(&mut self, field: &str, result: Result<T, E>) -> Option<T> {
+        result
+            .map_err(|e| {
+                self.0.push(FieldError {
+                    field: field.to_string(),
+                    message: e.to_string(),
+                })
+            })
+            .ok()
+    }
+}
+
+impl From<FieldErrors> for ApiError {
+    fn from(errors: FieldErrors) -> Self {
+        Self::InvalidFields(errors.0)
+    }
 }
 
 impl From<anyhow::Error> for ApiError {
@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    application::http::{auth::AdminAccess, extract::JsonBody, AppState},
+    domain::{
+        audit::ports::audit::AuditService,
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        secret::serialize_exposed,
+        tenant::{
+            models::api_key::{ApiKey, Scope, UnknownScopeError},
+            ports::tenant::TenantService,
+        },
+        token::ports::refresh_token::RefreshTokenService,
+    },
+};
+
+use super::{ApiError, ApiErrorData, ApiResponseBody, ApiSuccess};
+
+impl From<UnknownScopeError> for ApiError {
+    fn from(e: UnknownScopeError) -> Self {
+        Self::invalid_field("scopes", e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct CreateApiKeyHttpRequestBody {
+    #[serde(default)]
+    scopes: Vec<String>,
+}
+
+impl CreateApiKeyHttpRequestBody {
+    fn try_into_domain(self) -> Result<Vec<Scope>, UnknownScopeError> {
+        self.scopes
+            .iter()
+            .map(|scope| Scope::from_str(scope))
+            .collect()
+    }
+}
+
+/// The plaintext key is only ever returned by this response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct CreateApiKeyResponseData {
+    id: String,
+    tenant_id: String,
+    scopes: Vec<String>,
+    #[serde(serialize_with = "serialize_exposed")]
+    #[schema(value_type = String)]
+    api_key: ApiKey,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/api-keys",
+    tag = "tenants",
+    security(("admin_api_key" = [])),
+    params(("tenant_id" = uuid::Uuid, Path, description = "Id of the tenant")),
+    request_body = CreateApiKeyHttpRequestBody,
+    responses(
+        (status = 201, description = "API key created", body = ApiResponseBody<CreateApiKeyResponseData>),
+        (status = 401, description = "Missing or invalid admin API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 404, description = "No such tenant", body = ApiResponseBody<ApiErrorData>),
+        (status = 413, description = "Request body too large", body = ApiResponseBody<ApiErrorData>),
+        (status = 422, description = "Unknown scope", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn create_api_key<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    _: AdminAccess,
+    Path(tenant_id): Path<uuid::Uuid>,
+    JsonBody(body): JsonBody<CreateApiKeyHttpRequestBody>,
+) -> Result<ApiSuccess<CreateApiKeyResponseData>, ApiError> {
+    let scopes = body.try_into_domain()?;
+
+    let (info, api_key) = state
+        .tenant_service
+        .create_api_key(&tenant_id, &scopes)
+        .await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::CREATED,
+        CreateApiKeyResponseData {
+            id: info.id.to_string(),
+            tenant_id: info.tenant_id.to_string(),
+            scopes: info
+                .scopes
+                .iter()
+                .map(|scope| scope.as_str().to_string())
+                .collect(),
+            api_key,
+        },
+    ))
+}
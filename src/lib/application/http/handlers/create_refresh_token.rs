@@ -1,85 +1,148 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::State,
+    http::{
+        header::{RETRY_AFTER, WWW_AUTHENTICATE},
+        HeaderMap, HeaderName, HeaderValue, StatusCode,
+    },
+    response::IntoResponse,
+    Json,
+};
 
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
 use tracing::{error, info};
+use utoipa::{IntoParams, ToSchema};
 
-use crate::domain::token::models::refresh_token::CreateRefreshTokenRequest;
+use crate::domain::token::models::refresh_token::{
+    CreateMode, CreateRefreshTokenOptions, CreateRefreshTokenRequest, CreatedRefreshToken,
+    IdempotencyKey, InvalidIdempotencyKeyError,
+};
 use crate::domain::token::ports::provider_token_service::ProviderType;
 use crate::{
-    application::http::AppState,
-    domain::token::{
-        models::{
-            refresh_token::{CreateRefreshTokenError, RefreshToken},
-            token::{SerialNumber, SerialNumberEmptyError, Token, TokenEmptyError},
+    application::http::{
+        audit::{outcome, RequestOrigin},
+        auth::CurrentTenant,
+        extract::{JsonBody, QueryParams},
+        AppState,
+    },
+    domain::{
+        audit::{models::audit::AuditAction, ports::audit::AuditService},
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        secret::Secret,
+        tenant::ports::tenant::TenantService,
+        token::{
+            models::{
+                login_attempts::LoginLockedOutError,
+                refresh_token::CreateRefreshTokenError,
+                token::{CreateTokensError, Password, SerialNumber, Username},
+            },
+            ports::refresh_token::RefreshTokenService,
         },
-        ports::refresh_token::RefreshTokenService,
     },
 };
 
-use super::{ApiError, ApiSuccess};
+use super::{
+    get_refresh_token::RefreshTokenResponseData, ApiError, ApiSuccess, ErrorCode, FieldError,
+    FieldErrors,
+};
 
 impl From<CreateRefreshTokenError> for ApiError {
     fn from(e: CreateRefreshTokenError) -> Self {
-        info!("{:?}", e);
+        info!("Failed to create the refresh token: {}", e);
         match e {
-            CreateRefreshTokenError::Duplicate { name } => Self::UnprocessableEntity(format!(
-                "Refresh token with serial number {} already exists",
-                name
-            )),
-            CreateRefreshTokenError::Unknown(cause) => {
-                error!("{:?}\n{}", cause, cause.backtrace());
-                Self::InternalServerError("Internal server error".to_string())
-            }
+            CreateRefreshTokenError::Duplicate { name } => Self::Conflict(
+                ErrorCode::DuplicateToken,
+                format!("Refresh token with serial number {} already exists", name),
+            ),
+            CreateRefreshTokenError::LockedOut(e) => e.into(),
+            CreateRefreshTokenError::Provider(e) => e.into(),
+            CreateRefreshTokenError::ProviderNotFound => Self::InternalServerError(e.to_string()),
             CreateRefreshTokenError::DatabaseError(cause) => {
-                error!("{:?}", cause);
-                Self::InternalServerError("Internal server error".to_string())
+                Self::InternalServerError(cause.to_string())
             }
-            _ => Self::InternalServerError("Internal server error".to_string()),
         }
     }
 }
 
-impl From<ParseCreateRefreshTokenHttpRequestBodyError> for ApiError {
-    fn from(e: ParseCreateRefreshTokenHttpRequestBodyError) -> Self {
-        let message = match e {
-            ParseCreateRefreshTokenHttpRequestBodyError::SerialNumber(e) => e.to_string(),
-            ParseCreateRefreshTokenHttpRequestBodyError::Token(e) => e.to_string(),
-        };
+impl From<CreateTokensError> for ApiError {
+    fn from(e: CreateTokensError) -> Self {
+        match e {
+            CreateTokensError::InvalidCredentials => {
+                Self::UnprocessableEntity(ErrorCode::InvalidProviderCredentials, e.to_string())
+            }
+            CreateTokensError::VerificationRequired => {
+                Self::UnprocessableEntity(ErrorCode::ProviderVerificationRequired, e.to_string())
+            }
+            CreateTokensError::InvalidToken => Self::UnprocessableEntity(
+                ErrorCode::ProviderTokenRejected,
+                "The provider no longer accepts the stored token, update its credentials"
+                    .to_string(),
+            ),
+            CreateTokensError::RateLimited => {
+                Self::TooManyRequests(ErrorCode::ProviderRateLimited, e.to_string(), None)
+            }
+            CreateTokensError::Unavailable(_) => {
+                error!("{}", e);
+                Self::ServiceUnavailable(ErrorCode::ProviderUnavailable, e.to_string())
+            }
+            CreateTokensError::ProviderError(_) => {
+                error!("{}", e);
+                Self::BadGateway(ErrorCode::ProviderError, e.to_string())
+            }
+        }
+    }
+}
+
+impl From<LoginLockedOutError> for ApiError {
+    fn from(e: LoginLockedOutError) -> Self {
+        Self::TooManyRequests(
+            ErrorCode::LoginLockedOut,
+            e.to_string(),
+            Some(e.retry_after),
+        )
+    }
+}
 
-        Self::UnprocessableEntity(message)
+impl From<InvalidIdempotencyKeyError> for ApiError {
+    fn from(e: InvalidIdempotencyKeyError) -> Self {
+        Self::BadRequest(ErrorCode::InvalidRequest, e.to_string())
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> axum::response::Response {
-        use ApiError::*;
+        if let ApiError::InternalServerError(cause) = &self {
+            error!("{}", cause);
+        }
 
-        match self {
-            InternalServerError(e) => {
-                error!("{}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponseBody::new_error(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        "Internal server error".to_string(),
-                    )),
-                )
-                    .into_response()
+        let status = self.status();
+        let body = Json(
+            ApiResponseBody::new_error(status, self.code(), self.message().to_string())
+                .with_field_errors(self.field_errors().to_vec()),
+        );
+
+        let mut response = match self {
+            ApiError::Unauthorized(..) => {
+                (status, [(WWW_AUTHENTICATE, "Bearer")], body).into_response()
             }
-            UnprocessableEntity(message) => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(ApiResponseBody::new_error(
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    message,
-                )),
-            )
-                .into_response(),
+            _ => (status, body).into_response(),
+        };
+        if let Some(retry_after) = self.retry_after() {
+            // Whole seconds, rounded up so that a retry is never too early.
+            let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(seconds.max(1)));
         }
+        // Kept so the response can be rendered as problem details instead,
+        // see [crate::application::http::problem].
+        response.extensions_mut().insert(self);
+
+        response
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 pub struct ApiResponseBody<T: Serialize + PartialEq> {
     status_code: u16,
     data: T,
@@ -95,82 +158,178 @@ impl<T: Serialize + PartialEq> ApiResponseBody<T> {
 }
 
 impl ApiResponseBody<ApiErrorData> {
-    pub fn new_error(status_code: StatusCode, message: String) -> Self {
+    pub fn new_error(status_code: StatusCode, code: ErrorCode, message: String) -> Self {
         Self {
             status_code: status_code.as_u16(),
-            data: ApiErrorData { message },
+            data: ApiErrorData {
+                code,
+                message,
+                errors: Vec::new(),
+            },
         }
     }
+
+    pub fn with_field_errors(mut self, errors: Vec<FieldError>) -> Self {
+        self.data.errors = errors;
+        self
+    }
 }
 
 /// The response data format for all error responses.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 pub struct ApiErrorData {
+    pub code: ErrorCode,
     pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-pub struct CreateRefreshTokenRequestBody {
-    token: String,
+/// Missing fields deserialize as empty so they are reported along with the
+/// other invalid fields.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct CreateRefreshTokenHttpRequestBody {
+    /// Email address of the Bambu Lab account.
+    #[serde(default)]
+    username: String,
+    #[serde(default)]
+    #[schema(value_type = String)]
+    password: Secret,
+    /// Serial number of the printer, such as `01S00C123456789`.
+    #[serde(default)]
+    serial_number: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-pub struct CreateRefreshTokenResponseData {
-    id: String,
-}
+impl CreateRefreshTokenHttpRequestBody {
+    fn try_into_domain(self) -> Result<CreateRefreshTokenRequest, FieldErrors> {
+        let mut errors = FieldErrors::default();
+        let username = errors.check("username", Username::new(&self.username));
+        let password = errors.check("password", Password::new(self.password.expose()));
+        let serial_number = errors.check("serial_number", SerialNumber::new(&self.serial_number));
 
-impl From<&RefreshToken> for CreateRefreshTokenResponseData {
-    fn from(refresh_token: &RefreshToken) -> Self {
-        Self {
-            id: refresh_token.id.to_string(),
+        match (username, password, serial_number) {
+            (Some(username), Some(password), Some(serial_number)) => Ok(
+                CreateRefreshTokenRequest::new(username, password, serial_number),
+            ),
+            _ => Err(errors),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
-pub struct CreateRefreshTokenHttpRequestBody {
-    username: String,
-    password: String,
-    serial_number: String,
-}
+/// Lets a client retry a creation safely: a token created with a key is
+/// replaced, instead of rejected as a duplicate, when the key is sent again.
+pub static IDEMPOTENCY_KEY_HEADER: HeaderName = HeaderName::from_static("idempotency-key");
 
-#[derive(Debug, Clone, Error)]
-enum ParseCreateRefreshTokenHttpRequestBodyError {
-    #[error(transparent)]
-    SerialNumber(#[from] SerialNumberEmptyError),
-    #[error(transparent)]
-    Token(#[from] TokenEmptyError),
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CreateRefreshTokenHttpMode {
+    #[default]
+    Create,
+    Replace,
 }
 
-impl CreateRefreshTokenHttpRequestBody {
-    fn try_into_domain(
-        self,
-    ) -> Result<CreateRefreshTokenRequest, ParseCreateRefreshTokenHttpRequestBodyError> {
-        let serial_number = SerialNumber::new(&self.serial_number)?;
-
-        Ok(CreateRefreshTokenRequest::new(
-            self.username,
-            self.password,
-            serial_number,
-        ))
-    }
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CreateRefreshTokenHttpQuery {
+    /// `replace` overwrites an existing token for the same serial number.
+    #[serde(default)]
+    #[param(inline, required = false)]
+    mode: CreateRefreshTokenHttpMode,
 }
 
-pub async fn create_refresh_token<R: RefreshTokenService>(
-    State(state): State<AppState<R>>,
-    Json(body): Json<CreateRefreshTokenHttpRequestBody>,
-) -> Result<ApiSuccess<CreateRefreshTokenResponseData>, ApiError> {
+/// Registers the printer's account. With `?mode=replace`, an existing token for
+/// the same serial number is replaced in place (`200 OK`) rather than rejected
+/// with `409 Conflict`; so is a token created with the same
+/// `Idempotency-Key` header.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tokens",
+    tag = "tokens",
+    security(("api_key" = [])),
+    params(
+        CreateRefreshTokenHttpQuery,
+        ("idempotency-key" = Option<String>, Header, description = "Makes retries of the creation safe"),
+    ),
+    request_body = CreateRefreshTokenHttpRequestBody,
+    responses(
+        (status = 201, description = "Token created", body = ApiResponseBody<RefreshTokenResponseData>),
+        (status = 200, description = "Existing token replaced", body = ApiResponseBody<RefreshTokenResponseData>),
+        (status = 401, description = "Missing or invalid API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 409, description = "A token already exists for the serial number", body = ApiResponseBody<ApiErrorData>),
+        (status = 413, description = "Request body too large", body = ApiResponseBody<ApiErrorData>),
+        (status = 422, description = "Invalid body or mode, or credentials rejected by the provider", body = ApiResponseBody<ApiErrorData>),
+        (status = 429, description = "Too many logins from the client or to the account, or the provider is rate limiting requests", body = ApiResponseBody<ApiErrorData>,
+            headers(("retry-after" = u64, description = "Seconds to wait before retrying, unless the provider is rate limiting"))),
+        (status = 502, description = "The provider returned an unexpected response", body = ApiResponseBody<ApiErrorData>),
+        (status = 503, description = "The provider is unavailable", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn create_refresh_token<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    caller: CurrentTenant,
+    origin: RequestOrigin,
+    QueryParams(query): QueryParams<CreateRefreshTokenHttpQuery>,
+    headers: HeaderMap,
+    JsonBody(body): JsonBody<CreateRefreshTokenHttpRequestBody>,
+) -> Result<ApiSuccess<RefreshTokenResponseData>, ApiError> {
     let domain_request = body.try_into_domain()?;
+    let options = CreateRefreshTokenOptions {
+        mode: match query.mode {
+            CreateRefreshTokenHttpMode::Create => CreateMode::Reject,
+            CreateRefreshTokenHttpMode::Replace => CreateMode::Replace,
+        },
+        idempotency_key: headers
+            .get(&IDEMPOTENCY_KEY_HEADER)
+            .map(|value| {
+                value
+                    .to_str()
+                    .map_err(|_| InvalidIdempotencyKeyError)
+                    .and_then(IdempotencyKey::new)
+            })
+            .transpose()?,
+    };
 
-    state
+    let result = state
         .refresh_token_service
         .create_refresh_token(
+            &caller.tenant.id,
             domain_request.username().to_string(),
-            domain_request.password().to_string(),
-            domain_request.serial_number().as_str(),
+            domain_request.password().clone(),
+            domain_request.serial_number(),
             ProviderType::BambuLab,
+            &options,
         )
         .await
-        .map_err(ApiError::from)
-        .map(|ref refresh_token| ApiSuccess::new(StatusCode::CREATED, refresh_token.into()))
+        .map_err(ApiError::from);
+
+    let mut event = origin
+        .event(&caller, AuditAction::TokenCreate)
+        .with_outcome(outcome(&result))
+        .with_serial_number(domain_request.serial_number().as_str());
+    match &result {
+        Ok(CreatedRefreshToken::Created(refresh_token)) => {
+            event = event.with_token_id(Some(refresh_token.id));
+        }
+        Ok(CreatedRefreshToken::Replaced(refresh_token)) => {
+            event = event
+                .with_token_id(Some(refresh_token.id))
+                .with_detail("replaced".to_string());
+        }
+        Err(_) => {}
+    }
+    state.audit_service.record(event).await?;
+
+    result.map(|created| match created {
+        CreatedRefreshToken::Created(ref refresh_token) => {
+            ApiSuccess::new(StatusCode::CREATED, refresh_token.into())
+        }
+        CreatedRefreshToken::Replaced(ref refresh_token) => {
+            ApiSuccess::new(StatusCode::OK, refresh_token.into())
+        }
+    })
 }
@@ -0,0 +1,103 @@
+use axum::{extract::State, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    application::http::{auth::AdminAccess, extract::JsonBody, AppState},
+    domain::{
+        audit::ports::audit::AuditService,
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        secret::serialize_exposed,
+        tenant::{
+            models::{
+                api_key::ApiKey,
+                tenant::{
+                    CreateTenantError, CreateTenantRequest, TenantName, TenantNameEmptyError,
+                },
+            },
+            ports::tenant::TenantService,
+        },
+        token::ports::refresh_token::RefreshTokenService,
+    },
+};
+
+use super::{
+    get_tenant::TenantResponseData, ApiError, ApiErrorData, ApiResponseBody, ApiSuccess, ErrorCode,
+};
+
+impl From<CreateTenantError> for ApiError {
+    fn from(e: CreateTenantError) -> Self {
+        match e {
+            CreateTenantError::Duplicate { .. } => {
+                Self::Conflict(ErrorCode::DuplicateTenant, e.to_string())
+            }
+            CreateTenantError::DatabaseError(cause) => Self::InternalServerError(cause.to_string()),
+        }
+    }
+}
+
+impl From<TenantNameEmptyError> for ApiError {
+    fn from(e: TenantNameEmptyError) -> Self {
+        Self::invalid_field("name", e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct CreateTenantHttpRequestBody {
+    name: String,
+}
+
+impl CreateTenantHttpRequestBody {
+    fn try_into_domain(self) -> Result<CreateTenantRequest, TenantNameEmptyError> {
+        Ok(CreateTenantRequest::new(TenantName::new(&self.name)?))
+    }
+}
+
+/// The plaintext API key is only ever returned by this response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct CreateTenantResponseData {
+    #[serde(flatten)]
+    tenant: TenantResponseData,
+    #[serde(serialize_with = "serialize_exposed")]
+    #[schema(value_type = String)]
+    api_key: ApiKey,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants",
+    tag = "tenants",
+    security(("admin_api_key" = [])),
+    request_body = CreateTenantHttpRequestBody,
+    responses(
+        (status = 201, description = "Tenant created with its first API key", body = ApiResponseBody<CreateTenantResponseData>),
+        (status = 401, description = "Missing or invalid admin API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 409, description = "A tenant with the same name exists", body = ApiResponseBody<ApiErrorData>),
+        (status = 413, description = "Request body too large", body = ApiResponseBody<ApiErrorData>),
+        (status = 422, description = "Invalid body", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn create_tenant<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    _: AdminAccess,
+    JsonBody(body): JsonBody<CreateTenantHttpRequestBody>,
+) -> Result<ApiSuccess<CreateTenantResponseData>, ApiError> {
+    let domain_request = body.try_into_domain()?;
+
+    let (tenant, api_key) = state.tenant_service.create_tenant(&domain_request).await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::CREATED,
+        CreateTenantResponseData {
+            tenant: (&tenant).into(),
+            api_key,
+        },
+    ))
+}
@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+use crate::{
+    application::http::{
+        audit::{outcome, RequestOrigin},
+        auth::CurrentTenant,
+        AppState,
+    },
+    domain::{
+        audit::{models::audit::AuditAction, ports::audit::AuditService},
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        tenant::ports::tenant::TenantService,
+        token::{
+            models::refresh_token::DeleteRefreshTokenError,
+            ports::refresh_token::RefreshTokenService,
+        },
+    },
+};
+
+use super::{
+    get_refresh_token::RefreshTokenResponseData, ApiError, ApiErrorData, ApiResponseBody,
+    ApiSuccess,
+};
+
+impl From<DeleteRefreshTokenError> for ApiError {
+    fn from(e: DeleteRefreshTokenError) -> Self {
+        match e {
+            DeleteRefreshTokenError::NotFound(e) => e.into(),
+            DeleteRefreshTokenError::DatabaseError(cause) => {
+                Self::InternalServerError(cause.to_string())
+            }
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tokens/{token_id}",
+    tag = "tokens",
+    security(("api_key" = [])),
+    params(("token_id" = String, Path, description = "Serial number of the printer")),
+    responses(
+        (status = 200, description = "Token deleted", body = ApiResponseBody<RefreshTokenResponseData>),
+        (status = 401, description = "Missing or invalid API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 404, description = "No such token for the tenant", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn delete_refresh_token<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    caller: CurrentTenant,
+    origin: RequestOrigin,
+    Path(token_id): Path<String>,
+) -> Result<ApiSuccess<RefreshTokenResponseData>, ApiError> {
+    let result = state
+        .refresh_token_service
+        .delete_refresh_token(&caller.tenant.id, &token_id)
+        .await
+        .map_err(ApiError::from);
+
+    state
+        .audit_service
+        .record(
+            origin
+                .event(&caller, AuditAction::TokenDelete)
+                .with_outcome(outcome(&result))
+                .with_serial_number(&token_id)
+                .with_token_id(result.as_ref().ok().map(|refresh_token| refresh_token.id)),
+        )
+        .await?;
+
+    result.map(|ref refresh_token| ApiSuccess::new(StatusCode::OK, refresh_token.into()))
+}
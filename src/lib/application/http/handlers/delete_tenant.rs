@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    application::http::{auth::AdminAccess, AppState},
+    domain::{
+        audit::ports::audit::AuditService, health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService, tenant::ports::tenant::TenantService,
+        token::ports::refresh_token::RefreshTokenService,
+    },
+};
+
+use super::{ApiError, ApiErrorData, ApiResponseBody, ApiSuccess};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DeleteTenantResponseData {
+    id: String,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}",
+    tag = "tenants",
+    security(("admin_api_key" = [])),
+    params(("tenant_id" = uuid::Uuid, Path, description = "Id of the tenant")),
+    responses(
+        (status = 200, description = "Tenant deleted along with its keys and tokens", body = ApiResponseBody<DeleteTenantResponseData>),
+        (status = 401, description = "Missing or invalid admin API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 404, description = "No such tenant", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn delete_tenant<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    _: AdminAccess,
+    Path(tenant_id): Path<uuid::Uuid>,
+) -> Result<ApiSuccess<DeleteTenantResponseData>, ApiError> {
+    state.tenant_service.delete_tenant(&tenant_id).await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        DeleteTenantResponseData {
+            id: tenant_id.to_string(),
+        },
+    ))
+}
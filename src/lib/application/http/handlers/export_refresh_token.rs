@@ -0,0 +1,96 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    application::http::{
+        audit::{outcome, RequestOrigin},
+        auth::CurrentTenant,
+        AppState,
+    },
+    domain::{
+        audit::{models::audit::AuditAction, ports::audit::AuditService},
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        secret::serialize_exposed,
+        tenant::{models::api_key::Scope, ports::tenant::TenantService},
+        token::{models::token::Token, ports::refresh_token::RefreshTokenService},
+    },
+};
+
+use super::{
+    get_refresh_token::RefreshTokenResponseData, ApiError, ApiErrorData, ApiResponseBody,
+    ApiSuccess,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ExportRefreshTokenResponseData {
+    #[serde(flatten)]
+    metadata: RefreshTokenResponseData,
+    #[serde(serialize_with = "serialize_exposed")]
+    #[schema(value_type = String)]
+    refresh_token: Token,
+}
+
+/// Returns the raw provider token. Requires an API key with the
+/// [Scope::Admin] scope, and every attempt is recorded in the audit log: the
+/// token is not returned unless its export is on record.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tokens/{token_id}/export",
+    tag = "tokens",
+    security(("api_key" = ["admin"])),
+    params(("token_id" = String, Path, description = "Serial number of the printer")),
+    responses(
+        (status = 200, description = "Token including its secret", body = ApiResponseBody<ExportRefreshTokenResponseData>),
+        (status = 401, description = "Missing or invalid API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 403, description = "The API key lacks the admin scope", body = ApiResponseBody<ApiErrorData>),
+        (status = 404, description = "No such token for the tenant", body = ApiResponseBody<ApiErrorData>),
+        (status = 503, description = "The export could not be recorded in the audit log", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn export_refresh_token<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    caller: CurrentTenant,
+    origin: RequestOrigin,
+    Path(token_id): Path<String>,
+) -> Result<ApiSuccess<ExportRefreshTokenResponseData>, ApiError> {
+    let result = match caller.require_scope(Scope::Admin) {
+        Ok(()) => state
+            .refresh_token_service
+            .find_by_serial_number(&caller.tenant.id, &token_id)
+            .await
+            .map_err(ApiError::from),
+        Err(e) => Err(e),
+    };
+
+    state
+        .audit_service
+        .record(
+            origin
+                .event(&caller, AuditAction::TokenExport)
+                .with_outcome(outcome(&result))
+                .with_serial_number(&token_id)
+                .with_token_id(result.as_ref().ok().map(|refresh_token| refresh_token.id)),
+        )
+        .await?;
+
+    result.map(|ref refresh_token| {
+        ApiSuccess::new(
+            StatusCode::OK,
+            ExportRefreshTokenResponseData {
+                metadata: refresh_token.into(),
+                refresh_token: refresh_token.token.clone(),
+            },
+        )
+    })
+}
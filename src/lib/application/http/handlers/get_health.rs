@@ -0,0 +1,109 @@
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use crate::{
+    application::http::{auth::AdminAccess, AppState},
+    domain::{
+        audit::ports::audit::AuditService,
+        health::{
+            models::health::{DependencyError, DependencyHealth},
+            ports::health::HealthService,
+        },
+        proxy::ports::proxy::ProxyService,
+        tenant::ports::tenant::TenantService,
+        token::ports::refresh_token::RefreshTokenService,
+    },
+};
+
+use super::{ApiError, ApiErrorData, ApiResponseBody, ApiSuccess};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct HealthResponseData {
+    status: String,
+    dependencies: Vec<DependencyHealthData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DependencyHealthData {
+    name: String,
+    kind: String,
+    /// Whether the dependency being down makes the service unready.
+    critical: bool,
+    status: String,
+    latency_ms: u64,
+    last_error: Option<DependencyErrorData>,
+    /// State of the circuit breaker, for dependencies called through one:
+    /// `closed`, `open` or `half_open`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    circuit: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DependencyErrorData {
+    message: String,
+    #[serde(with = "time::serde::rfc3339")]
+    occurred_at: OffsetDateTime,
+}
+
+impl From<&DependencyError> for DependencyErrorData {
+    fn from(error: &DependencyError) -> Self {
+        Self {
+            message: error.message.clone(),
+            occurred_at: error.occurred_at,
+        }
+    }
+}
+
+impl From<&DependencyHealth> for DependencyHealthData {
+    fn from(dependency: &DependencyHealth) -> Self {
+        Self {
+            name: dependency.name.clone(),
+            kind: dependency.kind.to_string(),
+            critical: dependency.critical,
+            status: dependency.status.to_string(),
+            latency_ms: dependency.latency.as_millis() as u64,
+            last_error: dependency
+                .last_error
+                .as_ref()
+                .map(DependencyErrorData::from),
+            circuit: dependency.circuit.map(|circuit| circuit.to_string()),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "Status, latency and last error of each dependency", body = ApiResponseBody<HealthResponseData>),
+        (status = 401, description = "Missing or invalid admin API key", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn get_health<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    _: AdminAccess,
+) -> Result<ApiSuccess<HealthResponseData>, ApiError> {
+    let report = state.health_service.check().await;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        HealthResponseData {
+            status: report.status().to_string(),
+            dependencies: report
+                .dependencies
+                .iter()
+                .map(DependencyHealthData::from)
+                .collect(),
+        },
+    ))
+}
@@ -0,0 +1,31 @@
+use axum::http::StatusCode;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::health::models::health::HealthStatus;
+
+use super::{ApiResponseBody, ApiSuccess};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct LivenessResponseData {
+    status: String,
+}
+
+/// Answers as long as the process serves requests. No dependency is checked,
+/// so that an outage of one does not get the process restarted.
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses(
+        (status = 200, description = "The process is alive", body = ApiResponseBody<LivenessResponseData>),
+    )
+)]
+pub async fn get_liveness() -> ApiSuccess<LivenessResponseData> {
+    ApiSuccess::new(
+        StatusCode::OK,
+        LivenessResponseData {
+            status: HealthStatus::Up.to_string(),
+        },
+    )
+}
@@ -0,0 +1,84 @@
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    application::http::AppState,
+    domain::{
+        audit::ports::audit::AuditService,
+        health::{models::health::DependencyHealth, ports::health::HealthService},
+        proxy::ports::proxy::ProxyService,
+        tenant::ports::tenant::TenantService,
+        token::ports::refresh_token::RefreshTokenService,
+    },
+};
+
+use super::{ApiResponseBody, ApiSuccess};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ReadinessResponseData {
+    status: String,
+    dependencies: Vec<DependencyStatusData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DependencyStatusData {
+    name: String,
+    kind: String,
+    /// Whether the status of the dependency decides readiness.
+    critical: bool,
+    status: String,
+}
+
+impl From<&DependencyHealth> for DependencyStatusData {
+    fn from(dependency: &DependencyHealth) -> Self {
+        Self {
+            name: dependency.name.clone(),
+            kind: dependency.kind.to_string(),
+            critical: dependency.critical,
+            status: dependency.status.to_string(),
+        }
+    }
+}
+
+/// Ready when the database answers. The reachability of each provider is
+/// reported but does not affect the status code, and is checked again at most
+/// every 30 seconds. Errors are only detailed by `/health`.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "The service can take traffic", body = ApiResponseBody<ReadinessResponseData>),
+        (status = 503, description = "A critical dependency is down", body = ApiResponseBody<ReadinessResponseData>),
+    )
+)]
+pub async fn get_readiness<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+) -> ApiSuccess<ReadinessResponseData> {
+    let report = state.health_service.check_readiness().await;
+
+    let status_code = if report.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    ApiSuccess::new(
+        status_code,
+        ReadinessResponseData {
+            status: report.status().to_string(),
+            dependencies: report
+                .dependencies
+                .iter()
+                .map(DependencyStatusData::from)
+                .collect(),
+        },
+    )
+}
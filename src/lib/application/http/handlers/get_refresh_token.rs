@@ -3,37 +3,117 @@ use axum::{
     http::StatusCode,
 };
 use serde::Serialize;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
 
 use crate::{
-    application::http::AppState, domain::token::ports::refresh_token::RefreshTokenService,
+    application::http::{
+        audit::{outcome, RequestOrigin},
+        auth::CurrentTenant,
+        AppState,
+    },
+    domain::{
+        audit::{models::audit::AuditAction, ports::audit::AuditService},
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        tenant::ports::tenant::TenantService,
+        token::{
+            models::refresh_token::{FindRefreshTokenError, RefreshToken},
+            ports::refresh_token::RefreshTokenService,
+        },
+    },
 };
 
-use super::{ApiError, ApiSuccess};
+use super::{ApiError, ApiErrorData, ApiResponseBody, ApiSuccess, ErrorCode};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-pub struct GetRefreshTokenResponseData {
-    pub refresh_token: String,
+/// Public representation of a stored token.
+///
+/// The secret itself is deliberately absent: it can only be retrieved through
+/// the privileged export operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct RefreshTokenResponseData {
+    id: String,
+    serial_number: String,
+    provider: String,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    expires_at: Option<OffsetDateTime>,
+    health: String,
 }
 
-pub async fn get_refresh_token<R: RefreshTokenService>(
-    State(state): State<AppState<R>>,
+impl From<&RefreshToken> for RefreshTokenResponseData {
+    fn from(refresh_token: &RefreshToken) -> Self {
+        Self {
+            id: refresh_token.id.to_string(),
+            serial_number: refresh_token.serial_number.to_string(),
+            provider: refresh_token.provider.to_string(),
+            created_at: refresh_token.created_at,
+            updated_at: refresh_token.updated_at,
+            expires_at: refresh_token.expires_at,
+            health: refresh_token
+                .health(OffsetDateTime::now_utc())
+                .as_str()
+                .to_string(),
+        }
+    }
+}
+
+impl From<FindRefreshTokenError> for ApiError {
+    fn from(e: FindRefreshTokenError) -> Self {
+        match e {
+            FindRefreshTokenError::NotFound { .. } => {
+                Self::NotFound(ErrorCode::TokenNotFound, e.to_string())
+            }
+            FindRefreshTokenError::DatabaseError(cause) => {
+                Self::InternalServerError(cause.to_string())
+            }
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{token_id}",
+    tag = "tokens",
+    security(("api_key" = [])),
+    params(("token_id" = String, Path, description = "Serial number of the printer")),
+    responses(
+        (status = 200, description = "Token metadata", body = ApiResponseBody<RefreshTokenResponseData>),
+        (status = 401, description = "Missing or invalid API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 404, description = "No such token for the tenant", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn get_refresh_token<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    caller: CurrentTenant,
+    origin: RequestOrigin,
     Path(token_id): Path<String>,
-) -> Result<ApiSuccess<GetRefreshTokenResponseData>, ApiError> {
-    let refresh_token = state
+) -> Result<ApiSuccess<RefreshTokenResponseData>, ApiError> {
+    let result = state
         .refresh_token_service
-        .find_by_serial_number(&token_id)
-        .await;
+        .find_by_serial_number(&caller.tenant.id, &token_id)
+        .await
+        .map_err(ApiError::from);
 
-    match refresh_token {
-        Ok(refresh_token) => {
-            let response_data = GetRefreshTokenResponseData {
-                refresh_token: refresh_token.token.as_str().to_string(),
-            };
+    state
+        .audit_service
+        .record(
+            origin
+                .event(&caller, AuditAction::TokenRead)
+                .with_outcome(outcome(&result))
+                .with_serial_number(&token_id)
+                .with_token_id(result.as_ref().ok().map(|refresh_token| refresh_token.id)),
+        )
+        .await?;
 
-            Ok(ApiSuccess::new(StatusCode::ACCEPTED, response_data))
-        }
-        Err(_) => Err(ApiError::InternalServerError(
-            "Internal Server Error".to_string(),
-        )),
-    }
+    result.map(|ref refresh_token| ApiSuccess::new(StatusCode::OK, refresh_token.into()))
 }
@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Serialize;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use crate::{
+    application::http::{auth::AdminAccess, AppState},
+    domain::{
+        audit::ports::audit::AuditService,
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        tenant::{
+            models::tenant::{FindTenantError, Tenant},
+            ports::tenant::TenantService,
+        },
+        token::ports::refresh_token::RefreshTokenService,
+    },
+};
+
+use super::{ApiError, ApiErrorData, ApiResponseBody, ApiSuccess, ErrorCode};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TenantResponseData {
+    id: String,
+    name: String,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+}
+
+impl From<&Tenant> for TenantResponseData {
+    fn from(tenant: &Tenant) -> Self {
+        Self {
+            id: tenant.id.to_string(),
+            name: tenant.name.to_string(),
+            created_at: tenant.created_at,
+            updated_at: tenant.updated_at,
+        }
+    }
+}
+
+impl From<FindTenantError> for ApiError {
+    fn from(e: FindTenantError) -> Self {
+        match e {
+            FindTenantError::NotFound { .. } => {
+                Self::NotFound(ErrorCode::TenantNotFound, e.to_string())
+            }
+            FindTenantError::DatabaseError(cause) => Self::InternalServerError(cause.to_string()),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}",
+    tag = "tenants",
+    security(("admin_api_key" = [])),
+    params(("tenant_id" = uuid::Uuid, Path, description = "Id of the tenant")),
+    responses(
+        (status = 200, description = "The tenant", body = ApiResponseBody<TenantResponseData>),
+        (status = 401, description = "Missing or invalid admin API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 404, description = "No such tenant", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn get_tenant<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    _: AdminAccess,
+    Path(tenant_id): Path<uuid::Uuid>,
+) -> Result<ApiSuccess<TenantResponseData>, ApiError> {
+    state
+        .tenant_service
+        .find_tenant(&tenant_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref tenant| ApiSuccess::new(StatusCode::OK, tenant.into()))
+}
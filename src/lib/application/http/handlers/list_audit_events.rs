@@ -0,0 +1,207 @@
+use std::str::FromStr;
+
+use axum::{extract::State, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    application::http::{auth::CurrentTenant, extract::QueryParams, AppState},
+    domain::{
+        audit::{
+            models::audit::{
+                AuditAction, AuditEvent, AuditOutcome, ListAuditEventsError, ListAuditEventsQuery,
+                UnknownAuditActionError, UnknownAuditOutcomeError,
+            },
+            ports::audit::AuditService,
+        },
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        tenant::{models::api_key::Scope, ports::tenant::TenantService},
+        token::ports::refresh_token::RefreshTokenService,
+    },
+};
+
+use super::{ApiError, ApiErrorData, ApiResponseBody, ApiSuccess};
+
+impl From<ListAuditEventsError> for ApiError {
+    fn from(e: ListAuditEventsError) -> Self {
+        match e {
+            ListAuditEventsError::DatabaseError(cause) => {
+                Self::InternalServerError(cause.to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseListAuditEventsHttpQueryError> for ApiError {
+    fn from(e: ParseListAuditEventsHttpQueryError) -> Self {
+        match e {
+            ParseListAuditEventsHttpQueryError::Action(e) => Self::invalid_field("action", e),
+            ParseListAuditEventsHttpQueryError::Outcome(e) => Self::invalid_field("outcome", e),
+            ParseListAuditEventsHttpQueryError::ApiKeyId(e) => Self::invalid_field("api_key_id", e),
+            ParseListAuditEventsHttpQueryError::Since(e) => Self::invalid_field("since", e),
+            ParseListAuditEventsHttpQueryError::Until(e) => Self::invalid_field("until", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListAuditEventsHttpQuery {
+    /// Such as `token.create`, `token.read` or `printer.command`.
+    action: Option<String>,
+    /// One of `success`, `denied` or `failure`.
+    outcome: Option<String>,
+    serial_number: Option<String>,
+    /// Id of the API key the actions were performed with.
+    api_key_id: Option<String>,
+    /// RFC 3339 timestamp of the oldest events to return, inclusive.
+    since: Option<String>,
+    /// RFC 3339 timestamp of the newest events to return, exclusive.
+    until: Option<String>,
+    /// Page size, at most 500.
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseListAuditEventsHttpQueryError {
+    #[error(transparent)]
+    Action(#[from] UnknownAuditActionError),
+    #[error(transparent)]
+    Outcome(#[from] UnknownAuditOutcomeError),
+    #[error(transparent)]
+    ApiKeyId(#[from] uuid::Error),
+    #[error("Expected an RFC 3339 timestamp: {0}")]
+    Since(time::error::Parse),
+    #[error("Expected an RFC 3339 timestamp: {0}")]
+    Until(time::error::Parse),
+}
+
+impl ListAuditEventsHttpQuery {
+    fn try_into_domain(self) -> Result<ListAuditEventsQuery, ParseListAuditEventsHttpQueryError> {
+        let mut query = ListAuditEventsQuery::new(self.limit, self.offset);
+        query.action = self
+            .action
+            .as_deref()
+            .map(AuditAction::from_str)
+            .transpose()?;
+        query.outcome = self
+            .outcome
+            .as_deref()
+            .map(AuditOutcome::from_str)
+            .transpose()?;
+        query.serial_number = self.serial_number;
+        query.api_key_id = self
+            .api_key_id
+            .as_deref()
+            .map(uuid::Uuid::parse_str)
+            .transpose()?;
+        query.since = self
+            .since
+            .as_deref()
+            .map(|since| OffsetDateTime::parse(since, &Rfc3339))
+            .transpose()
+            .map_err(ParseListAuditEventsHttpQueryError::Since)?;
+        query.until = self
+            .until
+            .as_deref()
+            .map(|until| OffsetDateTime::parse(until, &Rfc3339))
+            .transpose()
+            .map_err(ParseListAuditEventsHttpQueryError::Until)?;
+
+        Ok(query)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct AuditEventResponseData {
+    id: String,
+    #[serde(with = "time::serde::rfc3339")]
+    occurred_at: OffsetDateTime,
+    action: String,
+    outcome: String,
+    /// The API key the action was performed with.
+    api_key_id: String,
+    serial_number: Option<String>,
+    token_id: Option<String>,
+    detail: Option<String>,
+    request_id: Option<String>,
+    client_ip: Option<String>,
+}
+
+impl From<&AuditEvent> for AuditEventResponseData {
+    fn from(event: &AuditEvent) -> Self {
+        Self {
+            id: event.id.to_string(),
+            occurred_at: event.occurred_at,
+            action: event.action.to_string(),
+            outcome: event.outcome.to_string(),
+            api_key_id: event.api_key_id.to_string(),
+            serial_number: event.serial_number.clone(),
+            token_id: event.token_id.map(|id| id.to_string()),
+            detail: event.detail.clone(),
+            request_id: event.request_id.clone(),
+            client_ip: event.client_ip.map(|ip| ip.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ListAuditEventsResponseData {
+    events: Vec<AuditEventResponseData>,
+    total: u64,
+    limit: u32,
+    offset: u32,
+}
+
+/// Lists the audit log of the tenant, newest events first. Requires an API
+/// key with the [Scope::Admin] scope.
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit",
+    tag = "audit",
+    security(("api_key" = ["admin"])),
+    params(ListAuditEventsHttpQuery),
+    responses(
+        (status = 200, description = "A page of the tenant's audit events", body = ApiResponseBody<ListAuditEventsResponseData>),
+        (status = 401, description = "Missing or invalid API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 403, description = "The API key lacks the admin scope", body = ApiResponseBody<ApiErrorData>),
+        (status = 422, description = "Invalid filters", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn list_audit_events<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    caller: CurrentTenant,
+    QueryParams(query): QueryParams<ListAuditEventsHttpQuery>,
+) -> Result<ApiSuccess<ListAuditEventsResponseData>, ApiError> {
+    caller.require_scope(Scope::Admin)?;
+    let query = query.try_into_domain()?;
+
+    let page = state
+        .audit_service
+        .list_events(&caller.tenant.id, &query)
+        .await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        ListAuditEventsResponseData {
+            events: page
+                .items
+                .iter()
+                .map(AuditEventResponseData::from)
+                .collect(),
+            total: page.total,
+            limit: query.limit,
+            offset: query.offset,
+        },
+    ))
+}
@@ -0,0 +1,147 @@
+use std::str::FromStr;
+
+use axum::{extract::State, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    application::http::{auth::CurrentTenant, extract::QueryParams, AppState},
+    domain::{
+        audit::ports::audit::AuditService,
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        tenant::ports::tenant::TenantService,
+        token::{
+            models::{
+                refresh_token::{ListRefreshTokensError, ListRefreshTokensQuery},
+                token::{InvalidSerialNumberError, SerialNumber},
+            },
+            ports::{
+                provider_token_service::{ProviderType, UnknownProviderError},
+                refresh_token::RefreshTokenService,
+            },
+        },
+    },
+};
+
+use super::{
+    get_refresh_token::RefreshTokenResponseData, ApiError, ApiErrorData, ApiResponseBody,
+    ApiSuccess,
+};
+
+impl From<ListRefreshTokensError> for ApiError {
+    fn from(e: ListRefreshTokensError) -> Self {
+        match e {
+            ListRefreshTokensError::DatabaseError(cause) => {
+                Self::InternalServerError(cause.to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseListRefreshTokensHttpQueryError> for ApiError {
+    fn from(e: ParseListRefreshTokensHttpQueryError) -> Self {
+        match e {
+            ParseListRefreshTokensHttpQueryError::Provider(e) => Self::invalid_field("provider", e),
+            ParseListRefreshTokensHttpQueryError::SerialNumber(e) => {
+                Self::invalid_field("serial_number", e)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListRefreshTokensHttpQuery {
+    provider: Option<String>,
+    serial_number: Option<String>,
+    /// Page size, at most 100.
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseListRefreshTokensHttpQueryError {
+    #[error(transparent)]
+    Provider(#[from] UnknownProviderError),
+    #[error(transparent)]
+    SerialNumber(#[from] InvalidSerialNumberError),
+}
+
+impl ListRefreshTokensHttpQuery {
+    fn try_into_domain(
+        self,
+    ) -> Result<ListRefreshTokensQuery, ParseListRefreshTokensHttpQueryError> {
+        let provider = self
+            .provider
+            .as_deref()
+            .map(ProviderType::from_str)
+            .transpose()?;
+        let serial_number = self
+            .serial_number
+            .as_deref()
+            .map(SerialNumber::new)
+            .transpose()?;
+
+        Ok(ListRefreshTokensQuery::new(
+            provider,
+            serial_number,
+            self.limit,
+            self.offset,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ListRefreshTokensResponseData {
+    tokens: Vec<RefreshTokenResponseData>,
+    total: u64,
+    limit: u32,
+    offset: u32,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens",
+    tag = "tokens",
+    security(("api_key" = [])),
+    params(ListRefreshTokensHttpQuery),
+    responses(
+        (status = 200, description = "A page of the tenant's tokens", body = ApiResponseBody<ListRefreshTokensResponseData>),
+        (status = 401, description = "Missing or invalid API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 422, description = "Invalid filters", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn list_refresh_tokens<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    CurrentTenant { tenant, .. }: CurrentTenant,
+    QueryParams(query): QueryParams<ListRefreshTokensHttpQuery>,
+) -> Result<ApiSuccess<ListRefreshTokensResponseData>, ApiError> {
+    let query = query.try_into_domain()?;
+
+    let page = state
+        .refresh_token_service
+        .list_refresh_tokens(&tenant.id, &query)
+        .await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        ListRefreshTokensResponseData {
+            tokens: page
+                .items
+                .iter()
+                .map(RefreshTokenResponseData::from)
+                .collect(),
+            total: page.total,
+            limit: query.limit,
+            offset: query.offset,
+        },
+    ))
+}
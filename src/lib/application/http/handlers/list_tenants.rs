@@ -0,0 +1,58 @@
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    application::http::{auth::AdminAccess, AppState},
+    domain::{
+        audit::ports::audit::AuditService,
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        tenant::{models::tenant::ListTenantsError, ports::tenant::TenantService},
+        token::ports::refresh_token::RefreshTokenService,
+    },
+};
+
+use super::{get_tenant::TenantResponseData, ApiError, ApiErrorData, ApiResponseBody, ApiSuccess};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ListTenantsResponseData {
+    tenants: Vec<TenantResponseData>,
+}
+
+impl From<ListTenantsError> for ApiError {
+    fn from(e: ListTenantsError) -> Self {
+        match e {
+            ListTenantsError::DatabaseError(cause) => Self::InternalServerError(cause.to_string()),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants",
+    tag = "tenants",
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "All tenants", body = ApiResponseBody<ListTenantsResponseData>),
+        (status = 401, description = "Missing or invalid admin API key", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn list_tenants<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    _: AdminAccess,
+) -> Result<ApiSuccess<ListTenantsResponseData>, ApiError> {
+    let tenants = state.tenant_service.list_tenants().await?;
+
+    let response_data = ListTenantsResponseData {
+        tenants: tenants.iter().map(TenantResponseData::from).collect(),
+    };
+
+    Ok(ApiSuccess::new(StatusCode::OK, response_data))
+}
@@ -0,0 +1,192 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, RawQuery, State},
+    http::{header::CONTENT_TYPE, HeaderMap, HeaderName, Method},
+    response::{IntoResponse, Response},
+};
+use tracing::error;
+
+use crate::{
+    application::http::{audit::RequestOrigin, auth::CurrentTenant, AppState},
+    domain::{
+        audit::{
+            models::audit::{AuditAction, AuditEvent, AuditOutcome},
+            ports::audit::AuditService,
+        },
+        health::ports::health::HealthService,
+        proxy::{
+            models::proxy::{
+                InvalidProxyPathError, ProxyError, ProxyPath, ProxyRequest, ProxyResponse,
+            },
+            ports::proxy::ProxyService,
+        },
+        tenant::ports::tenant::TenantService,
+        token::ports::refresh_token::RefreshTokenService,
+    },
+};
+
+use super::{ApiError, ApiErrorData, ApiResponseBody, ErrorCode};
+
+/// Selects which of the tenant's printers, and thus which linked account, the
+/// request is made for. It is never forwarded upstream.
+pub static SERIAL_NUMBER_HEADER: HeaderName = HeaderName::from_static("x-serial-number");
+
+impl From<ProxyError> for ApiError {
+    fn from(e: ProxyError) -> Self {
+        match e {
+            ProxyError::NotAllowed { .. } => {
+                Self::Forbidden(ErrorCode::PathNotAllowed, e.to_string())
+            }
+            ProxyError::TokenNotFound(e) => e.into(),
+            ProxyError::Renewal(e) => e.into(),
+            ProxyError::Upstream(_) => {
+                error!("{}", e);
+                Self::ServiceUnavailable(ErrorCode::ProviderUnavailable, e.to_string())
+            }
+        }
+    }
+}
+
+impl From<InvalidProxyPathError> for ApiError {
+    fn from(e: InvalidProxyPathError) -> Self {
+        Self::BadRequest(ErrorCode::InvalidRequest, e.to_string())
+    }
+}
+
+/// Whether a proxied request changes the state of a printer, and is thus
+/// recorded in the audit log.
+fn is_printer_command(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// The renewal of the access token and the printer command caused by a
+/// proxied request, whichever happened.
+fn proxied_events(
+    caller: &CurrentTenant,
+    origin: &RequestOrigin,
+    serial_number: &str,
+    command: Option<String>,
+    result: &Result<ProxyResponse, ProxyError>,
+) -> Vec<AuditEvent> {
+    let mut events = Vec::new();
+
+    let renewal = match result {
+        Ok(response) if response.token_renewed => Some(AuditOutcome::Success),
+        Err(ProxyError::Renewal(_)) => Some(AuditOutcome::Failure),
+        _ => None,
+    };
+    if let Some(renewal) = renewal {
+        events.push(
+            origin
+                .event(caller, AuditAction::TokenRenew)
+                .with_outcome(renewal)
+                .with_serial_number(serial_number),
+        );
+    }
+
+    if let Some(command) = command {
+        let (outcome, detail) = match result {
+            Ok(response) if response.status.is_success() => (
+                AuditOutcome::Success,
+                format!("{}: {}", command, response.status),
+            ),
+            Ok(response) => (
+                AuditOutcome::Failure,
+                format!("{}: {}", command, response.status),
+            ),
+            Err(ProxyError::NotAllowed { .. }) => (AuditOutcome::Denied, command),
+            Err(e) => (AuditOutcome::Failure, format!("{}: {}", command, e)),
+        };
+        events.push(
+            origin
+                .event(caller, AuditAction::PrinterCommand)
+                .with_outcome(outcome)
+                .with_serial_number(serial_number)
+                .with_detail(detail),
+        );
+    }
+
+    events
+}
+
+/// Forwards an allowlisted request to the Bambu Lab cloud API with the
+/// printer's access token, streaming the upstream response back as is.
+/// Requests of every method reach the allowlist, so that those other than
+/// `GET`, `HEAD` and `OPTIONS` are recorded in the audit log as printer
+/// commands, even when denied.
+#[utoipa::path(
+    method(get, head, options, post, put, patch, delete),
+    path = "/api/v1/proxy/bambu/{path}",
+    tag = "proxy",
+    security(("api_key" = [])),
+    params(
+        ("path" = String, Path, description = "Allowlisted Bambu Lab API path"),
+        ("x-serial-number" = String, Header, description = "Printer whose account the request is made for"),
+    ),
+    responses(
+        (status = 200, description = "The upstream response, streamed as is"),
+        (status = 400, description = "Missing serial number or invalid path", body = ApiResponseBody<ApiErrorData>),
+        (status = 401, description = "Missing or invalid API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 403, description = "The method and path are not allowlisted", body = ApiResponseBody<ApiErrorData>),
+        (status = 404, description = "No such token for the tenant", body = ApiResponseBody<ApiErrorData>),
+        (status = 503, description = "The provider is unavailable", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn proxy_bambu<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    caller: CurrentTenant,
+    origin: RequestOrigin,
+    Path(path): Path<String>,
+    RawQuery(query): RawQuery,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    let serial_number = headers
+        .get(&SERIAL_NUMBER_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| {
+            ApiError::BadRequest(
+                ErrorCode::InvalidRequest,
+                format!("Missing {} header", SERIAL_NUMBER_HEADER),
+            )
+        })?;
+
+    let command = is_printer_command(&method).then(|| format!("{} /{}", method, path));
+    let request = ProxyRequest {
+        method,
+        path: ProxyPath::new(&format!("/{}", path))?,
+        query,
+        content_type: headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        body,
+    };
+
+    let result = state
+        .proxy_service
+        .forward(&caller.tenant.id, serial_number, request)
+        .await;
+    // Built before awaiting, the response body not being shareable across
+    // threads.
+    let events = proxied_events(&caller, &origin, serial_number, command, &result);
+    for event in events {
+        state.audit_service.record(event).await?;
+    }
+    let response = result?;
+
+    let mut proxied = (response.status, Body::from_stream(response.body)).into_response();
+    if let Some(content_type) = response.content_type.and_then(|value| value.parse().ok()) {
+        proxied.headers_mut().insert(CONTENT_TYPE, content_type);
+    }
+
+    Ok(proxied)
+}
@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    application::http::{
+        audit::{outcome, RequestOrigin},
+        auth::CurrentTenant,
+        extract::JsonBody,
+        AppState,
+    },
+    domain::{
+        audit::{models::audit::AuditAction, ports::audit::AuditService},
+        health::ports::health::HealthService,
+        proxy::ports::proxy::ProxyService,
+        secret::Secret,
+        tenant::ports::tenant::TenantService,
+        token::{
+            models::{
+                refresh_token::UpdateRefreshTokenError,
+                token::{Password, Username},
+            },
+            ports::refresh_token::RefreshTokenService,
+        },
+    },
+};
+
+use super::{
+    get_refresh_token::RefreshTokenResponseData, ApiError, ApiErrorData, ApiResponseBody,
+    ApiSuccess, FieldErrors,
+};
+
+impl From<UpdateRefreshTokenError> for ApiError {
+    fn from(e: UpdateRefreshTokenError) -> Self {
+        match e {
+            UpdateRefreshTokenError::NotFound(e) => e.into(),
+            UpdateRefreshTokenError::LockedOut(e) => e.into(),
+            UpdateRefreshTokenError::Provider(e) => e.into(),
+            UpdateRefreshTokenError::ProviderNotFound => Self::InternalServerError(e.to_string()),
+            UpdateRefreshTokenError::DatabaseError(cause) => {
+                Self::InternalServerError(cause.to_string())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct UpdateRefreshTokenHttpRequestBody {
+    /// Email address of the Bambu Lab account.
+    #[serde(default)]
+    username: String,
+    #[serde(default)]
+    #[schema(value_type = String)]
+    password: Secret,
+}
+
+impl UpdateRefreshTokenHttpRequestBody {
+    fn try_into_domain(self) -> Result<(Username, Password), FieldErrors> {
+        let mut errors = FieldErrors::default();
+        let username = errors.check("username", Username::new(&self.username));
+        let password = errors.check("password", Password::new(self.password.expose()));
+
+        match (username, password) {
+            (Some(username), Some(password)) => Ok((username, password)),
+            _ => Err(errors),
+        }
+    }
+}
+
+/// Logs in again with new credentials, replacing the stored tokens in place.
+#[utoipa::path(
+    put,
+    path = "/api/v1/tokens/{token_id}",
+    tag = "tokens",
+    security(("api_key" = [])),
+    params(("token_id" = String, Path, description = "Serial number of the printer")),
+    request_body = UpdateRefreshTokenHttpRequestBody,
+    responses(
+        (status = 200, description = "Token replaced", body = ApiResponseBody<RefreshTokenResponseData>),
+        (status = 401, description = "Missing or invalid API key", body = ApiResponseBody<ApiErrorData>),
+        (status = 404, description = "No such token for the tenant", body = ApiResponseBody<ApiErrorData>),
+        (status = 413, description = "Request body too large", body = ApiResponseBody<ApiErrorData>),
+        (status = 422, description = "Credentials rejected by the provider", body = ApiResponseBody<ApiErrorData>),
+        (status = 429, description = "Too many logins from the client or to the account, or the provider is rate limiting requests", body = ApiResponseBody<ApiErrorData>,
+            headers(("retry-after" = u64, description = "Seconds to wait before retrying, unless the provider is rate limiting"))),
+        (status = 503, description = "The provider is unavailable", body = ApiResponseBody<ApiErrorData>),
+    )
+)]
+pub async fn update_refresh_token<
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    caller: CurrentTenant,
+    origin: RequestOrigin,
+    Path(token_id): Path<String>,
+    JsonBody(body): JsonBody<UpdateRefreshTokenHttpRequestBody>,
+) -> Result<ApiSuccess<RefreshTokenResponseData>, ApiError> {
+    let (username, password) = body.try_into_domain()?;
+
+    let result = state
+        .refresh_token_service
+        .update_credentials(
+            &caller.tenant.id,
+            &token_id,
+            username.as_str().to_string(),
+            password,
+        )
+        .await
+        .map_err(ApiError::from);
+
+    state
+        .audit_service
+        .record(
+            origin
+                .event(&caller, AuditAction::TokenUpdate)
+                .with_outcome(outcome(&result))
+                .with_serial_number(&token_id)
+                .with_token_id(result.as_ref().ok().map(|refresh_token| refresh_token.id)),
+        )
+        .await?;
+
+    result.map(|ref refresh_token| ApiSuccess::new(StatusCode::OK, refresh_token.into()))
+}
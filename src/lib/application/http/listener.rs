@@ -0,0 +1,91 @@
+use std::{
+    fmt::Display,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+};
+
+/// Where the server accepts connections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindAddress {
+    /// An IPv4 or IPv6 address, `[::]` accepting both on most systems.
+    Tcp(SocketAddr),
+    /// A Unix domain socket, for a proxy or sidecar on the same host. A stale
+    /// socket file left at the path is replaced.
+    Unix(PathBuf),
+}
+
+impl Display for BindAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BindAddress::Tcp(address) => write!(f, "{}", address),
+            BindAddress::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// A connection, over TCP or a Unix socket and with TLS or not.
+pub(super) trait Io: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + 'static> Io for T {}
+
+pub(super) enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener),
+}
+
+impl Listener {
+    pub async fn bind(address: &BindAddress) -> std::io::Result<Self> {
+        match address {
+            BindAddress::Tcp(address) => TcpListener::bind(address).await.map(Listener::Tcp),
+            #[cfg(unix)]
+            BindAddress::Unix(path) => {
+                use std::os::unix::fs::FileTypeExt;
+
+                if std::fs::symlink_metadata(path).is_ok_and(|file| file.file_type().is_socket()) {
+                    std::fs::remove_file(path)?;
+                }
+                tokio::net::UnixListener::bind(path).map(Listener::Unix)
+            }
+            #[cfg(not(unix))]
+            BindAddress::Unix(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Unix domain sockets are not supported on this platform",
+            )),
+        }
+    }
+
+    /// Returns the connection along with the address of the client, which
+    /// only TCP connections have.
+    pub async fn accept(&self) -> std::io::Result<(Box<dyn Io>, Option<SocketAddr>)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, address) = listener.accept().await?;
+                Ok((Box::new(stream), Some(address)))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let (stream, _) = listener.accept().await?;
+                Ok((Box::new(stream), None))
+            }
+        }
+    }
+
+    pub fn local_address(&self) -> std::io::Result<BindAddress> {
+        match self {
+            Listener::Tcp(listener) => listener.local_addr().map(BindAddress::Tcp),
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let address = listener.local_addr()?;
+                Ok(BindAddress::Unix(
+                    address.as_pathname().unwrap_or(Path::new("")).to_path_buf(),
+                ))
+            }
+        }
+    }
+}
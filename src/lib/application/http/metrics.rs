@@ -0,0 +1,47 @@
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::header::CONTENT_TYPE,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::application::telemetry;
+
+/// Label of requests that matched no route, so that probing random paths
+/// cannot create new series.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Counts requests and records their latency, labelled with the route
+/// template rather than the requested path to keep the cardinality bounded.
+pub async fn track_http_requests(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let started_at = Instant::now();
+
+    let response = next.run(request).await;
+
+    let labels = [
+        ("method", method),
+        ("route", route),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    metrics::counter!("http_requests_total", &labels).increment(1);
+    metrics::histogram!("http_request_duration_seconds", &labels)
+        .record(started_at.elapsed().as_secs_f64());
+
+    response
+}
+
+/// Serves the metrics in the Prometheus text exposition format.
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        telemetry::prometheus_handle().render(),
+    )
+}
@@ -0,0 +1,183 @@
+use axum::{response::Html, Json};
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+use utoipa_scalar::Scalar;
+
+use super::{
+    handlers::{
+        create_api_key, create_refresh_token, create_tenant, delete_refresh_token, delete_tenant,
+        export_refresh_token, get_health, get_liveness, get_readiness, get_refresh_token,
+        get_tenant, list_audit_events, list_refresh_tokens, list_tenants, proxy_bambu,
+        update_refresh_token, ErrorCode, FieldError,
+    },
+    problem::ProblemDetails,
+};
+
+/// Specification of the public API, derived from the handlers and their
+/// request and response types.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "FerrisPrinter",
+        license(name = "MIT"),
+        description = "Stores printer provider tokens on behalf of tenants and proxies calls to the provider APIs. \
+            Errors are returned in the `ApiResponseBody` envelope, or as RFC 7807 problem details \
+            when requested with `Accept: application/problem+json`. \
+            The same routes are served without the `/v1` segment as a deprecated alias."
+    ),
+    paths(
+        create_refresh_token::create_refresh_token,
+        list_refresh_tokens::list_refresh_tokens,
+        get_refresh_token::get_refresh_token,
+        update_refresh_token::update_refresh_token,
+        delete_refresh_token::delete_refresh_token,
+        export_refresh_token::export_refresh_token,
+        create_tenant::create_tenant,
+        list_tenants::list_tenants,
+        get_tenant::get_tenant,
+        delete_tenant::delete_tenant,
+        create_api_key::create_api_key,
+        proxy_bambu::proxy_bambu,
+        list_audit_events::list_audit_events,
+        get_liveness::get_liveness,
+        get_readiness::get_readiness,
+        get_health::get_health,
+    ),
+    components(schemas(ErrorCode, FieldError, ProblemDetails)),
+    modifiers(&SecuritySchemes, &UniqueOperationIds),
+    tags(
+        (name = "tokens", description = "Provider tokens of the tenant's printers"),
+        (name = "tenants", description = "Tenant management, restricted to the operator"),
+        (name = "proxy", description = "Allowlisted calls to the provider APIs"),
+        (name = "audit", description = "Record of the actions on the tenant's tokens and printers"),
+        (name = "health", description = "Probes and dependency status, served outside of `/api`"),
+    )
+)]
+pub struct ApiDoc;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("API key of a tenant"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "admin_api_key",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("Operator key configured through ADMIN_API_KEY"))
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Suffixes with their method the operation ids shared by several methods of
+/// a path, such as those of the proxy, operation ids having to be unique.
+struct UniqueOperationIds;
+
+impl Modify for UniqueOperationIds {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                ("get", &mut item.get),
+                ("put", &mut item.put),
+                ("post", &mut item.post),
+                ("delete", &mut item.delete),
+                ("options", &mut item.options),
+                ("head", &mut item.head),
+                ("patch", &mut item.patch),
+                ("trace", &mut item.trace),
+            ];
+            let ids: Vec<String> = operations
+                .iter()
+                .filter_map(|(_, operation)| operation.as_ref()?.operation_id.clone())
+                .collect();
+
+            for (method, operation) in operations {
+                let Some(id) = operation.as_mut().and_then(|o| o.operation_id.as_mut()) else {
+                    continue;
+                };
+                if ids.iter().filter(|other| *other == id).count() > 1 {
+                    *id = format!("{}_{}", id, method);
+                }
+            }
+        }
+    }
+}
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Interactive documentation rendering [ApiDoc].
+pub async fn docs() -> Html<String> {
+    Html(Scalar::new(ApiDoc::openapi()).to_html())
+}
+
+#[cfg(test)]
+mod tests {
+    use utoipa::OpenApi;
+
+    use super::ApiDoc;
+
+    const SNAPSHOT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+
+    #[test]
+    fn test_operation_ids_are_unique() {
+        let openapi = ApiDoc::openapi();
+        let mut ids: Vec<&str> = openapi
+            .paths
+            .paths
+            .values()
+            .flat_map(|item| {
+                [
+                    &item.get,
+                    &item.put,
+                    &item.post,
+                    &item.delete,
+                    &item.options,
+                    &item.head,
+                    &item.patch,
+                    &item.trace,
+                ]
+            })
+            .filter_map(|operation| operation.as_ref()?.operation_id.as_deref())
+            .collect();
+        let count = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+
+        assert_eq!(ids.len(), count);
+        assert!(ids.contains(&"proxy_bambu_post"));
+    }
+
+    /// Fails when the specification changes without the checked-in snapshot
+    /// being updated, which is done by running the test with
+    /// `UPDATE_OPENAPI_SNAPSHOT=1`.
+    #[test]
+    fn test_openapi_matches_snapshot() {
+        let generated = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+
+        if std::env::var_os("UPDATE_OPENAPI_SNAPSHOT").is_some() {
+            std::fs::write(SNAPSHOT_PATH, &generated).unwrap();
+        }
+
+        let snapshot = std::fs::read_to_string(SNAPSHOT_PATH).unwrap_or_default();
+        assert!(
+            snapshot == generated,
+            "openapi.json is out of date, run `UPDATE_OPENAPI_SNAPSHOT=1 cargo test` and commit the result"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use axum::{
+    extract::Request,
+    http::{
+        header::{ACCEPT, CONTENT_LENGTH, CONTENT_TYPE},
+        HeaderMap, HeaderName, HeaderValue,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::handlers::{ApiError, ErrorCode, FieldError};
+
+/// Identifies a request in logs and error responses. Taken from the client
+/// when provided, generated otherwise, and always echoed back.
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// The id of the request, in its extensions for the handlers to refer to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// An error rendered as RFC 7807 problem details, with the error code, the
+/// request id and any field errors as extension members.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    problem_type: String,
+    title: String,
+    status: u16,
+    detail: String,
+    instance: String,
+    code: ErrorCode,
+    request_id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<FieldError>,
+}
+
+impl ProblemDetails {
+    pub fn new(error: &ApiError, instance: &str, request_id: &str) -> Self {
+        let status = error.status();
+
+        Self {
+            problem_type: format!("urn:ferrisprinter:problem:{}", error.code().as_str()),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: error.message().to_string(),
+            instance: instance.to_string(),
+            code: error.code(),
+            request_id: request_id.to_string(),
+            errors: error.field_errors().to_vec(),
+        }
+    }
+}
+
+fn accepts_problem_json(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|media_range| media_range.split(';').next())
+        .any(|media_type| media_type.trim().eq_ignore_ascii_case(PROBLEM_JSON))
+}
+
+fn request_id(headers: &HeaderMap) -> String {
+    headers
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LENGTH)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Tags every response with its request id and, for clients sending
+/// `Accept: application/problem+json`, replaces the error envelope of
+/// [ApiError] responses with [ProblemDetails]. Other clients keep the envelope.
+pub async fn problem_details(mut request: Request, next: Next) -> Response {
+    let request_id = request_id(request.headers());
+    request
+        .extensions_mut()
+        .insert(RequestId(request_id.clone()));
+    let wants_problem = accepts_problem_json(request.headers());
+    let instance = request.uri().path().to_string();
+
+    let mut response = next.run(request).await;
+
+    if let Some(error) = response.extensions_mut().remove::<ApiError>() {
+        if wants_problem {
+            let problem = Json(ProblemDetails::new(&error, &instance, &request_id));
+            let (mut parts, _) = response.into_parts();
+            parts.headers.remove(CONTENT_LENGTH);
+            parts
+                .headers
+                .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+            response = Response::from_parts(parts, problem.into_response().into_body());
+        }
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER.clone(), value);
+    }
+
+    response
+}
@@ -0,0 +1,249 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::request::Parts,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::domain::{
+    audit::ports::audit::AuditService, health::ports::health::HealthService,
+    proxy::ports::proxy::ProxyService, tenant::ports::tenant::TenantService,
+    token::ports::refresh_token::RefreshTokenService,
+};
+
+use super::{
+    auth::CurrentTenant,
+    handlers::{ApiError, ErrorCode},
+    AppState,
+};
+
+/// Requests allowed per window, for each API key and each client IP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    pub per_api_key: u32,
+    pub per_client_ip: u32,
+    pub window: Duration,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_api_key: 10,
+            per_client_ip: 30,
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum RateLimitKey {
+    /// Id of the API key the request was authenticated with. Requests with
+    /// a key that does not resolve only count against their client IP, so
+    /// that a made-up key never earns a fresh quota.
+    ApiKey(uuid::Uuid),
+    ClientIp(IpAddr),
+}
+
+impl RateLimitKey {
+    fn kind(&self) -> &'static str {
+        match self {
+            RateLimitKey::ApiKey(_) => "api_key",
+            RateLimitKey::ClientIp(_) => "client_ip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    started_at: Instant,
+    count: u32,
+}
+
+/// Fixed-window counters of the requests made with each API key and from each
+/// client IP. They are kept in memory and thus per instance.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    config: RwLock<RateLimitConfig>,
+    windows: Mutex<HashMap<RateLimitKey, Window>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> RateLimitConfig {
+        *self.config.read().unwrap()
+    }
+
+    /// Applies new quotas to the requests counted from now on, keeping the
+    /// counts of the current windows.
+    pub fn set_config(&self, config: RateLimitConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    /// Counts a request against every key, unless one of them is exhausted in
+    /// which case nothing is counted and the wait until it resets is returned.
+    fn acquire(&self, keys: &[RateLimitKey], now: Instant) -> Result<(), (Duration, &'static str)> {
+        let config = self.config();
+        let window = config.window;
+        let limit = |key: &RateLimitKey| match key {
+            RateLimitKey::ApiKey(_) => config.per_api_key,
+            RateLimitKey::ClientIp(_) => config.per_client_ip,
+        };
+        let mut windows = self.windows.lock().unwrap();
+        windows.retain(|_, counter| now.duration_since(counter.started_at) < window);
+
+        for key in keys {
+            if let Some(counter) = windows.get(key) {
+                if counter.count >= limit(key) {
+                    let retry_after = window - now.duration_since(counter.started_at);
+                    return Err((retry_after, key.kind()));
+                }
+            }
+        }
+
+        for key in keys {
+            windows
+                .entry(key.clone())
+                .or_insert(Window {
+                    started_at: now,
+                    count: 0,
+                })
+                .count += 1;
+        }
+
+        Ok(())
+    }
+}
+
+fn rate_limit_keys(parts: &Parts, caller: Option<&CurrentTenant>) -> Vec<RateLimitKey> {
+    let api_key = caller.map(|caller| RateLimitKey::ApiKey(caller.api_key.id));
+    // Absent on Unix sockets, see [super::serve_connection].
+    let client_ip = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| RateLimitKey::ClientIp(address.ip()));
+
+    api_key.into_iter().chain(client_ip).collect()
+}
+
+/// Rejects with `429 Too Many Requests` the requests exceeding the quota of
+/// their API key or client IP. Applied to the routes forwarding credentials to
+/// a provider, where a misbehaving client could get the service throttled.
+///
+/// The caller is authenticated here, and handed to the handler through the
+/// request extensions so that it is not authenticated twice.
+pub async fn limit_requests<R, T, P, H, A>(
+    State(state): State<AppState<R, T, P, H, A>>,
+    request: Request,
+    next: Next,
+) -> Response
+where
+    R: RefreshTokenService,
+    T: TenantService,
+    P: ProxyService,
+    H: HealthService,
+    A: AuditService,
+{
+    let (mut parts, body) = request.into_parts();
+    // Rejected by the handler when unresolved.
+    let caller = CurrentTenant::from_request_parts(&mut parts, &state)
+        .await
+        .ok();
+    let keys = rate_limit_keys(&parts, caller.as_ref());
+    if let Some(caller) = caller {
+        parts.extensions.insert(caller);
+    }
+    let request = Request::from_parts(parts, body);
+
+    match state.login_rate_limiter.acquire(&keys, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err((retry_after, key)) => {
+            metrics::counter!("rate_limited_requests_total", "key" => key).increment(1);
+
+            ApiError::TooManyRequests(
+                ErrorCode::RateLimited,
+                "Too many requests, slow down".to_string(),
+                Some(retry_after),
+            )
+            .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::{Duration, Instant},
+    };
+
+    use super::{RateLimitConfig, RateLimitKey, RateLimiter};
+
+    #[test]
+    fn test_each_key_has_its_own_quota() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            per_api_key: 2,
+            per_client_ip: 3,
+            window: Duration::from_secs(60),
+        });
+        let now = Instant::now();
+        let ip = RateLimitKey::ClientIp(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let first_key = RateLimitKey::ApiKey(uuid::Uuid::new_v4());
+        let second_key = RateLimitKey::ApiKey(uuid::Uuid::new_v4());
+
+        for _ in 0..2 {
+            assert!(limiter
+                .acquire(&[first_key.clone(), ip.clone()], now)
+                .is_ok());
+        }
+        let (retry_after, key) = limiter
+            .acquire(
+                &[first_key.clone(), ip.clone()],
+                now + Duration::from_secs(15),
+            )
+            .unwrap_err();
+        assert_eq!((retry_after, key), (Duration::from_secs(45), "api_key"));
+
+        assert!(limiter
+            .acquire(&[second_key.clone(), ip.clone()], now)
+            .is_ok());
+        let (_, key) = limiter.acquire(&[second_key, ip.clone()], now).unwrap_err();
+        assert_eq!(key, "client_ip");
+
+        assert!(limiter
+            .acquire(&[first_key, ip], now + Duration::from_secs(60))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_new_quotas_apply_to_the_current_window() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            per_api_key: 1,
+            ..RateLimitConfig::default()
+        });
+        let now = Instant::now();
+        let keys = [RateLimitKey::ApiKey(uuid::Uuid::new_v4())];
+
+        assert!(limiter.acquire(&keys, now).is_ok());
+        assert!(limiter.acquire(&keys, now).is_err());
+
+        limiter.set_config(RateLimitConfig {
+            per_api_key: 2,
+            ..RateLimitConfig::default()
+        });
+        assert!(limiter.acquire(&keys, now).is_ok());
+        assert!(limiter.acquire(&keys, now).is_err());
+    }
+}
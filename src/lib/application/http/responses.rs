@@ -1,12 +0,0 @@
-use serde::Serialize;
-
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
-pub struct ResponseBody<T: Serialize> {
-    status_code: u16,
-    data: T,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-pub struct ErrorResponseData {
-    pub message: String,
-}
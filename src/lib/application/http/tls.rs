@@ -0,0 +1,199 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context};
+use clap::ValueEnum;
+use rustls::{
+    crypto::ring,
+    pki_types::{CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore, ServerConfig,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Whether clients must present a certificate issued by
+/// [TlsConfig::client_ca_file].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientAuth {
+    #[default]
+    Required,
+    /// Clients without a certificate are served too, those presenting one
+    /// are rejected unless it is issued by the CA.
+    Optional,
+}
+
+/// PEM files of the server certificate, and of the CA verifying the client
+/// certificates of the callers when mTLS is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// The server certificate followed by its intermediates.
+    pub cert_file: PathBuf,
+    pub key_file: PathBuf,
+    pub client_ca_file: Option<PathBuf>,
+    pub client_auth: ClientAuth,
+}
+
+impl TlsConfig {
+    fn files(&self) -> impl Iterator<Item = &Path> {
+        [
+            Some(&self.cert_file),
+            Some(&self.key_file),
+            self.client_ca_file.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(PathBuf::as_path)
+    }
+}
+
+fn read(path: &Path) -> anyhow::Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("could not read {}", path.display()))
+}
+
+fn read_certificates(path: &Path, pem: &[u8]) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let certificates = rustls_pemfile::certs(&mut &pem[..])
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("invalid PEM file {}", path.display()))?;
+    if certificates.is_empty() {
+        return Err(anyhow!("{} holds no PEM certificate", path.display()));
+    }
+
+    Ok(certificates)
+}
+
+fn read_private_key(path: &Path, pem: &[u8]) -> anyhow::Result<PrivateKeyDer<'static>> {
+    rustls_pemfile::private_key(&mut &pem[..])
+        .with_context(|| format!("invalid PEM file {}", path.display()))?
+        .ok_or_else(|| anyhow!("{} holds no PEM private key", path.display()))
+}
+
+/// Digest of the content of the files, telling when they change.
+fn fingerprint(config: &TlsConfig) -> anyhow::Result<Vec<u8>> {
+    let mut fingerprint = Sha256::new();
+    for path in config.files() {
+        fingerprint.update(read(path)?);
+    }
+
+    Ok(fingerprint.finalize().to_vec())
+}
+
+fn load(config: &TlsConfig) -> anyhow::Result<Arc<ServerConfig>> {
+    let certificates = read_certificates(&config.cert_file, &read(&config.cert_file)?)?;
+    let key = read_private_key(&config.key_file, &read(&config.key_file)?)?;
+
+    let provider = Arc::new(ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .context("no TLS version is supported")?;
+    let builder = match &config.client_ca_file {
+        Some(path) => {
+            let mut roots = RootCertStore::empty();
+            for certificate in read_certificates(path, &read(path)?)? {
+                roots
+                    .add(certificate)
+                    .with_context(|| format!("invalid CA certificate in {}", path.display()))?;
+            }
+
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+            let verifier = match config.client_auth {
+                ClientAuth::Required => verifier,
+                ClientAuth::Optional => verifier.allow_unauthenticated(),
+            };
+            builder.with_client_cert_verifier(
+                verifier
+                    .build()
+                    .with_context(|| format!("invalid client CA {}", path.display()))?,
+            )
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder
+        .with_single_cert(certificates, key)
+        .with_context(|| {
+            format!(
+                "{} does not match the certificate {}",
+                config.key_file.display(),
+                config.cert_file.display()
+            )
+        })?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(Arc::new(server_config))
+}
+
+/// Terminates TLS with the certificates of a [TlsConfig], reloading them when
+/// their files change so that renewed certificates are served without a
+/// restart. Connections already established keep the previous ones.
+pub(super) struct TlsAcceptor {
+    config: TlsConfig,
+    server_config: RwLock<Arc<ServerConfig>>,
+    /// Of the files last loaded, or that failed to load.
+    fingerprint: Mutex<Vec<u8>>,
+}
+
+impl TlsAcceptor {
+    pub fn new(config: TlsConfig) -> anyhow::Result<Self> {
+        let fingerprint = fingerprint(&config)?;
+        let server_config = load(&config)?;
+
+        Ok(Self {
+            config,
+            server_config: RwLock::new(server_config),
+            fingerprint: Mutex::new(fingerprint),
+        })
+    }
+
+    pub fn acceptor(&self) -> tokio_rustls::TlsAcceptor {
+        tokio_rustls::TlsAcceptor::from(Arc::clone(&self.server_config.read().unwrap()))
+    }
+
+    /// Reloads the certificates if one of the files changed, keeping the
+    /// current ones when the new files are invalid, such as when the
+    /// certificate is renewed before its key. Invalid files are reported once,
+    /// and retried when they change again.
+    pub fn reload_if_changed(&self) -> anyhow::Result<bool> {
+        let fingerprint = fingerprint(&self.config)?;
+        {
+            let mut last = self.fingerprint.lock().unwrap();
+            if *last == fingerprint {
+                return Ok(false);
+            }
+            *last = fingerprint;
+        }
+
+        *self.server_config.write().unwrap() = load(&self.config)?;
+
+        Ok(true)
+    }
+
+    /// Checks the files every `poll_interval` until `shutdown` is cancelled.
+    pub async fn watch(self: Arc<Self>, poll_interval: Duration, shutdown: CancellationToken) {
+        let mut interval = tokio::time::interval(poll_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.cancelled() => break,
+            }
+
+            match self.reload_if_changed() {
+                Ok(true) => info!(
+                    "reloaded the TLS certificate {}",
+                    self.config.cert_file.display()
+                ),
+                Ok(false) => {}
+                Err(e) => warn!("keeping the current TLS certificate: {:#}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,481 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use reqwest::{Certificate, Client, Proxy, Url};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    domain::token::ports::provider_token_service::ProviderType,
+    env::Env,
+    infrastructure::resilience::{ResiliencePolicy, ResilientClient},
+};
+
+#[derive(Debug, Error)]
+pub enum ProvidersConfigError {
+    #[error("{field}: unknown provider {value:?}")]
+    UnknownProvider { field: String, value: String },
+    #[error("At least one provider must be enabled")]
+    NoProviderEnabled,
+    #[error("{field}: {value:?} is not a valid URL: {reason}")]
+    InvalidUrl {
+        field: String,
+        value: String,
+        reason: String,
+    },
+    #[error("{field}: {reason}")]
+    InvalidValue { field: String, reason: String },
+    #[error("{field}: could not read the CA bundle {}: {source}", path.display())]
+    ReadCaBundle {
+        field: String,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{field}: {} holds no PEM certificate", path.display())]
+    InvalidCaBundle { field: String, path: PathBuf },
+    #[error("Could not build the HTTP client of {provider}: {source}")]
+    HttpClient {
+        provider: ProviderType,
+        source: reqwest::Error,
+    },
+}
+
+/// Settings of the HTTP client calling a provider.
+#[derive(Debug, Clone)]
+pub struct HttpClientConfig {
+    pub user_agent: String,
+    pub proxy: Option<Url>,
+    /// Trusted in addition to the system certificates.
+    pub ca_certificates: Vec<Certificate>,
+    pub connect_timeout: Duration,
+    pub read_timeout: Duration,
+    pub max_retries: u32,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        let policy = ResiliencePolicy::default();
+
+        Self {
+            user_agent: ResilientClient::DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+            ca_certificates: Vec::new(),
+            connect_timeout: policy.connect_timeout,
+            read_timeout: policy.read_timeout,
+            max_retries: policy.max_retries,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProviderConfig {
+    pub provider_type: ProviderType,
+    /// Base URL of the provider's API, without a trailing slash.
+    pub api_url: String,
+    pub login_url: String,
+    pub http: HttpClientConfig,
+}
+
+impl ProviderConfig {
+    /// Builds the client calling the provider. Each call builds a client with
+    /// its own circuit breaker.
+    pub fn http_client(&self) -> Result<ResilientClient, ProvidersConfigError> {
+        let mut builder = Client::builder().user_agent(&self.http.user_agent);
+        if let Some(proxy) = &self.http.proxy {
+            let proxy =
+                Proxy::all(proxy.clone()).map_err(|source| ProvidersConfigError::HttpClient {
+                    provider: self.provider_type.clone(),
+                    source,
+                })?;
+            builder = builder.proxy(proxy);
+        }
+        for certificate in &self.http.ca_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+
+        let policy = ResiliencePolicy {
+            connect_timeout: self.http.connect_timeout,
+            read_timeout: self.http.read_timeout,
+            max_retries: self.http.max_retries,
+            ..ResiliencePolicy::default()
+        };
+
+        ResilientClient::build(self.provider_type.as_str(), builder, policy).map_err(|source| {
+            ProvidersConfigError::HttpClient {
+                provider: self.provider_type.clone(),
+                source,
+            }
+        })
+    }
+}
+
+/// The enabled providers, from the `[http]` and `[providers.<name>]` tables of
+/// the configuration file overridden by the environment.
+///
+/// ```toml
+/// enabled_providers = ["bambulab"]
+///
+/// [http]
+/// proxy = "http://proxy.internal:3128"
+/// read_timeout_ms = 20000
+///
+/// [providers.bambulab]
+/// api_url = "https://api.bambulab.com"
+///
+/// [providers.bambulab.http]
+/// max_retries = 3
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProvidersConfig {
+    providers: Vec<ProviderConfig>,
+}
+
+/// The provider settings of the configuration file, see
+/// [crate::config::Config].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ProvidersFile {
+    pub enabled_providers: Option<Vec<String>>,
+    pub http: HttpFile,
+    pub providers: HashMap<String, ProviderFile>,
+}
+
+const ALL_PROVIDERS: [ProviderType; 1] = [ProviderType::BambuLab];
+
+fn default_urls(provider_type: &ProviderType) -> (&'static str, &'static str) {
+    match provider_type {
+        ProviderType::BambuLab => (
+            "https://api.bambulab.com",
+            "https://bambulab.com/api/sign-in/form",
+        ),
+    }
+}
+
+fn env_urls(env: &Env, provider_type: &ProviderType) -> (Option<String>, Option<String>) {
+    match provider_type {
+        ProviderType::BambuLab => (env.bambulab_api_url.clone(), env.bambulab_login_url.clone()),
+    }
+}
+
+fn parse_url(field: &str, value: &str) -> Result<Url, ProvidersConfigError> {
+    let invalid = |reason: String| ProvidersConfigError::InvalidUrl {
+        field: field.to_string(),
+        value: value.to_string(),
+        reason,
+    };
+
+    let url = Url::parse(value).map_err(|e| invalid(e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid("the scheme must be http or https".to_string()));
+    }
+    if url.host_str().is_none() {
+        return Err(invalid("a host is required".to_string()));
+    }
+
+    Ok(url)
+}
+
+fn positive_millis(field: &str, value: u64) -> Result<Duration, ProvidersConfigError> {
+    if value == 0 {
+        return Err(ProvidersConfigError::InvalidValue {
+            field: field.to_string(),
+            reason: "must be greater than 0".to_string(),
+        });
+    }
+
+    Ok(Duration::from_millis(value))
+}
+
+fn read_ca_bundle(field: &str, path: &Path) -> Result<Vec<Certificate>, ProvidersConfigError> {
+    let pem = std::fs::read(path).map_err(|source| ProvidersConfigError::ReadCaBundle {
+        field: field.to_string(),
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    match Certificate::from_pem_bundle(&pem) {
+        Ok(certificates) if !certificates.is_empty() => Ok(certificates),
+        _ => Err(ProvidersConfigError::InvalidCaBundle {
+            field: field.to_string(),
+            path: path.to_path_buf(),
+        }),
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct HttpFile {
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    ca_bundle: Option<PathBuf>,
+    connect_timeout_ms: Option<u64>,
+    read_timeout_ms: Option<u64>,
+    max_retries: Option<u32>,
+}
+
+impl HttpFile {
+    /// Values of `self` take precedence over those of `fallback`.
+    fn or(self, fallback: HttpFile) -> HttpFile {
+        HttpFile {
+            user_agent: self.user_agent.or(fallback.user_agent),
+            proxy: self.proxy.or(fallback.proxy),
+            ca_bundle: self.ca_bundle.or(fallback.ca_bundle),
+            connect_timeout_ms: self.connect_timeout_ms.or(fallback.connect_timeout_ms),
+            read_timeout_ms: self.read_timeout_ms.or(fallback.read_timeout_ms),
+            max_retries: self.max_retries.or(fallback.max_retries),
+        }
+    }
+
+    fn from_env(env: &Env) -> HttpFile {
+        HttpFile {
+            user_agent: env.provider_user_agent.clone(),
+            proxy: env.provider_http_proxy.clone(),
+            ca_bundle: env.provider_ca_bundle.clone(),
+            connect_timeout_ms: env.provider_connect_timeout_ms,
+            read_timeout_ms: env.provider_read_timeout_ms,
+            max_retries: env.provider_max_retries,
+        }
+    }
+
+    fn validate(self, field: &str) -> Result<HttpClientConfig, ProvidersConfigError> {
+        let defaults = HttpClientConfig::default();
+
+        let user_agent = self.user_agent.unwrap_or(defaults.user_agent);
+        if user_agent.trim().is_empty() {
+            return Err(ProvidersConfigError::InvalidValue {
+                field: format!("{}.user_agent", field),
+                reason: "cannot be empty".to_string(),
+            });
+        }
+
+        Ok(HttpClientConfig {
+            user_agent,
+            proxy: self
+                .proxy
+                .map(|proxy| parse_url(&format!("{}.proxy", field), &proxy))
+                .transpose()?,
+            ca_certificates: self
+                .ca_bundle
+                .map(|path| read_ca_bundle(&format!("{}.ca_bundle", field), &path))
+                .transpose()?
+                .unwrap_or_default(),
+            connect_timeout: self
+                .connect_timeout_ms
+                .map(|ms| positive_millis(&format!("{}.connect_timeout_ms", field), ms))
+                .transpose()?
+                .unwrap_or(defaults.connect_timeout),
+            read_timeout: self
+                .read_timeout_ms
+                .map(|ms| positive_millis(&format!("{}.read_timeout_ms", field), ms))
+                .transpose()?
+                .unwrap_or(defaults.read_timeout),
+            max_retries: self.max_retries.unwrap_or(defaults.max_retries),
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ProviderFile {
+    api_url: Option<String>,
+    login_url: Option<String>,
+    http: HttpFile,
+}
+
+impl ProvidersConfig {
+    /// Validates the configuration of the enabled providers.
+    pub(crate) fn from_file(
+        env: &Env,
+        mut file: ProvidersFile,
+    ) -> Result<Self, ProvidersConfigError> {
+        let mut configured = HashMap::new();
+        for (name, provider) in file.providers.drain() {
+            let provider_type = name.parse::<ProviderType>().map_err(|_| {
+                ProvidersConfigError::UnknownProvider {
+                    field: "providers".to_string(),
+                    value: name.clone(),
+                }
+            })?;
+            configured.insert(provider_type, provider);
+        }
+
+        let enabled = match (&env.enabled_providers, &file.enabled_providers) {
+            (Some(enabled), _) => enabled.clone(),
+            (None, Some(names)) => names
+                .iter()
+                .map(|name| {
+                    name.parse()
+                        .map_err(|_| ProvidersConfigError::UnknownProvider {
+                            field: "enabled_providers".to_string(),
+                            value: name.clone(),
+                        })
+                })
+                .collect::<Result<_, _>>()?,
+            (None, None) => ALL_PROVIDERS.to_vec(),
+        };
+        if enabled.is_empty() {
+            return Err(ProvidersConfigError::NoProviderEnabled);
+        }
+
+        let mut providers: Vec<ProviderConfig> = Vec::new();
+        for provider_type in enabled {
+            if providers
+                .iter()
+                .any(|provider| provider.provider_type == provider_type)
+            {
+                continue;
+            }
+
+            let field = format!("providers.{}", provider_type);
+            let provider = configured.remove(&provider_type).unwrap_or_default();
+            let (default_api_url, default_login_url) = default_urls(&provider_type);
+            let (env_api_url, env_login_url) = env_urls(env, &provider_type);
+
+            let api_url = env_api_url
+                .or(provider.api_url)
+                .unwrap_or_else(|| default_api_url.to_string());
+            parse_url(&format!("{}.api_url", field), &api_url)?;
+            let login_url = env_login_url
+                .or(provider.login_url)
+                .unwrap_or_else(|| default_login_url.to_string());
+            parse_url(&format!("{}.login_url", field), &login_url)?;
+
+            let http = HttpFile::from_env(env)
+                .or(provider.http)
+                .or(file.http.clone())
+                .validate(&format!("{}.http", field))?;
+
+            providers.push(ProviderConfig {
+                provider_type,
+                api_url: api_url.trim_end_matches('/').to_string(),
+                login_url,
+                http,
+            });
+        }
+
+        Ok(Self { providers })
+    }
+
+    pub fn providers(&self) -> impl Iterator<Item = &ProviderConfig> {
+        self.providers.iter()
+    }
+
+    pub fn get(&self, provider_type: &ProviderType) -> Option<&ProviderConfig> {
+        self.providers
+            .iter()
+            .find(|provider| &provider.provider_type == provider_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ProvidersConfig, ProvidersConfigError, ProvidersFile};
+    use crate::{domain::token::ports::provider_token_service::ProviderType, env::Env};
+
+    fn file(content: &str) -> ProvidersFile {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_defaults_enable_every_provider() {
+        let config = ProvidersConfig::from_file(&Env::default(), ProvidersFile::default()).unwrap();
+
+        let bambulab = config.get(&ProviderType::BambuLab).unwrap();
+        assert_eq!(bambulab.api_url, "https://api.bambulab.com");
+        assert_eq!(bambulab.http.user_agent, "ferris-printer");
+        assert!(bambulab.http.proxy.is_none());
+    }
+
+    #[test]
+    fn test_environment_overrides_the_file() {
+        let env = Env {
+            bambulab_api_url: Some("https://api.example.com/".to_string()),
+            provider_read_timeout_ms: Some(1000),
+            ..Env::default()
+        };
+        let file = file(
+            r#"
+            [http]
+            user_agent = "workshop"
+            read_timeout_ms = 30000
+            max_retries = 1
+
+            [providers.bambulab]
+            api_url = "https://ignored.example.com"
+            login_url = "https://login.example.com/sign-in"
+
+            [providers.bambulab.http]
+            max_retries = 4
+            proxy = "http://proxy.internal:3128"
+            "#,
+        );
+
+        let config = ProvidersConfig::from_file(&env, file).unwrap();
+
+        let bambulab = config.get(&ProviderType::BambuLab).unwrap();
+        assert_eq!(bambulab.api_url, "https://api.example.com");
+        assert_eq!(bambulab.login_url, "https://login.example.com/sign-in");
+        assert_eq!(bambulab.http.user_agent, "workshop");
+        assert_eq!(bambulab.http.read_timeout, Duration::from_secs(1));
+        assert_eq!(bambulab.http.max_retries, 4);
+        assert_eq!(
+            bambulab.http.proxy.as_ref().map(|proxy| proxy.as_str()),
+            Some("http://proxy.internal:3128/")
+        );
+        assert!(bambulab.http_client().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_values_name_the_offending_field() {
+        let env = Env {
+            bambulab_api_url: Some("api.bambulab.com".to_string()),
+            ..Env::default()
+        };
+        let error = ProvidersConfig::from_file(&env, ProvidersFile::default()).unwrap_err();
+        assert!(matches!(error, ProvidersConfigError::InvalidUrl { .. }));
+        assert!(error
+            .to_string()
+            .starts_with("providers.bambulab.api_url: \"api.bambulab.com\""));
+
+        let error = ProvidersConfig::from_file(
+            &Env::default(),
+            file("[providers.bambulab.http]\nconnect_timeout_ms = 0"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "providers.bambulab.http.connect_timeout_ms: must be greater than 0"
+        );
+
+        let error = ProvidersConfig::from_file(
+            &Env::default(),
+            file("[http]\nca_bundle = \"/nonexistent/ca.pem\""),
+        )
+        .unwrap_err();
+        assert!(matches!(error, ProvidersConfigError::ReadCaBundle { .. }));
+    }
+
+    #[test]
+    fn test_unknown_and_missing_providers_are_rejected() {
+        let error = ProvidersConfig::from_file(
+            &Env::default(),
+            file("[providers.prusa]\napi_url = \"https://prusa.example.com\""),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            ProvidersConfigError::UnknownProvider { .. }
+        ));
+
+        let error = ProvidersConfig::from_file(&Env::default(), file("enabled_providers = []"))
+            .unwrap_err();
+        assert!(matches!(error, ProvidersConfigError::NoProviderEnabled));
+
+        assert!(toml::from_str::<ProvidersFile>("[http]\ntimeout = 3").is_err());
+    }
+}
@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use crate::domain::token::ports::provider_token_service::{ProviderTokenService, ProviderType};
 
+use super::config::{ProviderConfig, ProvidersConfig};
+
 #[derive(Debug, Clone)]
 pub struct TokenProviderManager<P> {
     providers: HashMap<ProviderType, P>,
@@ -17,6 +19,19 @@ where
         }
     }
 
+    /// Registers each provider enabled in `config`, as built by `build`.
+    pub fn from_config<E>(
+        config: &ProvidersConfig,
+        build: impl Fn(&ProviderConfig) -> Result<P, E>,
+    ) -> Result<Self, E> {
+        let mut manager = Self::new();
+        for provider in config.providers() {
+            manager.register_provider(provider.provider_type.clone(), build(provider)?);
+        }
+
+        Ok(manager)
+    }
+
     pub fn register_provider(&mut self, provider_type: ProviderType, provider: P) {
         self.providers.insert(provider_type, provider);
     }
@@ -24,6 +39,10 @@ where
     pub fn get_provider(&self, provider_type: &ProviderType) -> Option<&P> {
         self.providers.get(provider_type)
     }
+
+    pub fn providers(&self) -> impl Iterator<Item = (&ProviderType, &P)> {
+        self.providers.iter()
+    }
 }
 
 impl<P> Default for TokenProviderManager<P>
@@ -0,0 +1,145 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use tokio::{signal, time::MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{config::Config, env::Env};
+
+/// Reloads the configuration on SIGHUP and whenever the content of its file
+/// changes, checked every `poll_interval`, until `shutdown` is cancelled.
+///
+/// Each valid configuration is passed to `apply`, which updates the settings
+/// that can change while serving: the log level and the login rate limits.
+/// The others, such as the address or the database pool, only take effect on
+/// the next restart. An invalid configuration is logged and ignored, the
+/// current settings staying in effect.
+pub async fn watch<F>(env: Arc<Env>, poll_interval: Duration, shutdown: CancellationToken, apply: F)
+where
+    F: Fn(&Config),
+{
+    let path = Config::file_path(&env).to_path_buf();
+    let mut content = read(&path).await;
+    let mut hangups = Hangups::new();
+    let mut interval = tokio::time::interval(poll_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        let trigger = tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = hangups.recv() => "SIGHUP",
+            _ = interval.tick() => {
+                let current = read(&path).await;
+                if current == content {
+                    continue;
+                }
+                content = current;
+                "a change of the configuration file"
+            }
+        };
+
+        info!("reloading the configuration on {}", trigger);
+        match Config::load(&env) {
+            Ok(config) => {
+                apply(&config);
+                metrics::counter!("config_reloads_total", "outcome" => "success").increment(1);
+            }
+            Err(e) => {
+                warn!(
+                    "keeping the current configuration, the new one is invalid: {}",
+                    e
+                );
+                metrics::counter!("config_reloads_total", "outcome" => "invalid").increment(1);
+            }
+        }
+    }
+}
+
+/// `None` when the file cannot be read, which [Config::load] reports.
+async fn read(path: &Path) -> Option<String> {
+    tokio::fs::read_to_string(path).await.ok()
+}
+
+/// Resolves on each SIGHUP, and never where the signal does not exist.
+struct Hangups {
+    #[cfg(unix)]
+    signal: Option<signal::unix::Signal>,
+}
+
+impl Hangups {
+    fn new() -> Self {
+        #[cfg(unix)]
+        let signal = match signal::unix::signal(signal::unix::SignalKind::hangup()) {
+            Ok(signal) => Some(signal),
+            Err(e) => {
+                warn!("failed to listen for SIGHUP: {}", e);
+                None
+            }
+        };
+
+        Self {
+            #[cfg(unix)]
+            signal,
+        }
+    }
+
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(signal) = &mut self.signal {
+            signal.recv().await;
+            return;
+        }
+
+        std::future::pending::<()>().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use tokio::sync::mpsc;
+    use tokio_util::sync::CancellationToken;
+
+    use super::watch;
+    use crate::env::Env;
+
+    #[tokio::test]
+    async fn test_changes_of_the_file_are_applied_unless_invalid() {
+        let path =
+            std::env::temp_dir().join(format!("ferrisprinter-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "[logging]\nlevel = \"info\"").unwrap();
+        let env = Arc::new(Env {
+            config_file: Some(path.clone()),
+            database_url: Some("postgres://localhost/ferris".to_string()),
+            port: Some(8080),
+            ..Env::default()
+        });
+
+        let (levels, mut applied) = mpsc::unbounded_channel();
+        let shutdown = CancellationToken::new();
+        let watcher = tokio::spawn(watch(
+            env,
+            Duration::from_millis(10),
+            shutdown.clone(),
+            move |config| levels.send(config.logging.level.clone()).unwrap(),
+        ));
+        let timeout = Duration::from_secs(5);
+        // Lets the watcher read the initial content.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        std::fs::write(&path, "[logging]\nlevel = \"debug\"").unwrap();
+        let level = tokio::time::timeout(timeout, applied.recv()).await.unwrap();
+        assert_eq!(level.as_deref(), Some("debug"));
+
+        std::fs::write(&path, "[logging]\nlevel = \"ferrisprinter=loud\"").unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        std::fs::write(&path, "[logging]\nlevel = \"warn\"").unwrap();
+        let level = tokio::time::timeout(timeout, applied.recv()).await.unwrap();
+        assert_eq!(level.as_deref(), Some("warn"));
+
+        shutdown.cancel();
+        watcher.await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+use tokio::signal;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Returns a token cancelled on the first SIGINT or SIGTERM.
+///
+/// The HTTP server and the background tasks each wait on the token, or on a
+/// [CancellationToken::child_token] of it, to stop accepting work and finish
+/// what they are doing before the process exits.
+pub fn on_signal() -> CancellationToken {
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+
+    tokio::spawn(async move {
+        let signal = terminate_signal().await;
+        info!("received {}, shutting down", signal);
+        cancel.cancel();
+    });
+
+    token
+}
+
+async fn terminate_signal() -> &'static str {
+    let ctrl_c = async {
+        if let Err(e) = signal::ctrl_c().await {
+            warn!("failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(mut stream) => {
+                stream.recv().await;
+            }
+            Err(e) => {
+                warn!("failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => "SIGINT",
+        _ = terminate => "SIGTERM",
+    }
+}
@@ -0,0 +1,233 @@
+use std::sync::OnceLock;
+
+use anyhow::Context;
+use http::HeaderMap;
+use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use opentelemetry::{
+    global,
+    trace::{TraceContextExt, TraceId, TracerProvider as _},
+    KeyValue,
+};
+use opentelemetry_http::{HeaderExtractor, HeaderInjector};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator, runtime, trace::TracerProvider, Resource,
+};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+};
+
+use crate::{config::LoggingConfig, env::LogFormat};
+
+/// Upper bounds of the latency histograms, from fast database lookups to
+/// sign-ins at a slow provider.
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Returns the handle rendering the Prometheus exposition, installing the
+/// process-wide recorder on first use.
+///
+/// Metrics are recorded through the `metrics` macros wherever they happen and
+/// are dropped until the recorder is installed, so this must be called before
+/// the services start.
+pub fn prometheus_handle() -> &'static PrometheusHandle {
+    static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+    HANDLE.get_or_init(|| {
+        let handle = PrometheusBuilder::new()
+            .set_buckets_for_metric(
+                Matcher::Suffix("duration_seconds".to_string()),
+                LATENCY_BUCKETS,
+            )
+            .expect("latency buckets are not empty")
+            .install_recorder()
+            .expect("no other metrics recorder is installed");
+
+        describe_metrics();
+        handle
+    })
+}
+
+fn describe_metrics() {
+    describe_counter!(
+        "http_requests_total",
+        "HTTP requests handled, by method, route and status"
+    );
+    describe_histogram!(
+        "http_request_duration_seconds",
+        Unit::Seconds,
+        "Time to produce the response of HTTP requests, by method, route and status"
+    );
+    describe_counter!(
+        "provider_calls_total",
+        "Calls made to the token providers, by provider, operation and outcome"
+    );
+    describe_counter!(
+        "provider_http_retries_total",
+        "Retries of HTTP calls to the providers, by provider"
+    );
+    describe_counter!(
+        "token_renewals_total",
+        "Renewals of stored refresh tokens, by provider and outcome"
+    );
+    describe_counter!(
+        "rate_limited_requests_total",
+        "Requests rejected for exceeding the quota of their API key or client IP, by key"
+    );
+    describe_counter!(
+        "config_reloads_total",
+        "Reloads of the configuration, by outcome"
+    );
+    describe_gauge!(
+        "db_pool_connections",
+        "Connections of the database pool, by state"
+    );
+    describe_gauge!(
+        "db_pool_max_connections",
+        "Maximum size of the database pool"
+    );
+}
+
+/// Flushes the spans not exported yet when dropped, so it must be held until
+/// the server stops.
+pub struct TracingGuard {
+    provider: TracerProvider,
+    log_level: LogLevelHandle,
+}
+
+impl TracingGuard {
+    pub fn log_level(&self) -> LogLevelHandle {
+        self.log_level.clone()
+    }
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to flush the pending spans: {}", e);
+        }
+    }
+}
+
+/// Changes the level of the logs, and of the exported spans, while running.
+#[derive(Debug, Clone)]
+pub struct LogLevelHandle(reload::Handle<EnvFilter, Registry>);
+
+impl LogLevelHandle {
+    /// Takes a level or filtering directives, see [LoggingConfig::level].
+    pub fn set(&self, level: &str) -> anyhow::Result<()> {
+        let filter =
+            EnvFilter::try_new(level).with_context(|| format!("invalid log level {:?}", level))?;
+
+        self.0
+            .reload(filter)
+            .context("failed to change the log level")
+    }
+}
+
+/// Installs the global subscriber, logging in the configured format.
+///
+/// Spans are always given OpenTelemetry trace ids, so that logs and outgoing
+/// requests carry them, but they are only exported when an OTLP endpoint is
+/// configured.
+pub fn init_tracing(config: &LoggingConfig) -> anyhow::Result<TracingGuard> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let mut provider = TracerProvider::builder().with_resource(Resource::new([KeyValue::new(
+        "service.name",
+        config.service_name.clone(),
+    )]));
+    if let Some(endpoint) = &config.otlp_endpoint {
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .build()
+            .context("failed to build the OTLP span exporter")?;
+        provider = provider.with_batch_exporter(exporter, runtime::Tokio);
+    }
+    let provider = provider.build();
+
+    let filter = EnvFilter::try_new(&config.level)
+        .with_context(|| format!("invalid log level {:?}", config.level))?;
+    let (filter_layer, log_level) = reload::Layer::new(filter);
+
+    let fmt_layer = match config.format {
+        LogFormat::Text => fmt::layer().boxed(),
+        LogFormat::Json => fmt::layer().json().boxed(),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(fmt_layer)
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("ferrisprinter")))
+        .try_init()
+        .context("failed to install the tracing subscriber")?;
+
+    Ok(TracingGuard {
+        provider,
+        log_level: LogLevelHandle(log_level),
+    })
+}
+
+/// Makes the span a child of the caller's trace when the request carries a
+/// W3C `traceparent` header, and records the trace id as its `trace_id` field.
+pub fn continue_trace(span: &Span, headers: &HeaderMap) {
+    let parent =
+        global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+    span.set_parent(parent);
+
+    let trace_id = span.context().span().span_context().trace_id();
+    if trace_id != TraceId::INVALID {
+        span.record("trace_id", tracing::field::display(trace_id));
+    }
+}
+
+/// Returns the `traceparent` header of the current span, to be added to an
+/// outgoing request so the provider call joins the trace.
+pub fn trace_context_headers() -> HeaderMap {
+    let context = Span::current().context();
+    let mut headers = HeaderMap::new();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(&mut headers))
+    });
+
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, HeaderValue};
+    use opentelemetry::{global, trace::TracerProvider as _};
+    use opentelemetry_sdk::{propagation::TraceContextPropagator, trace::TracerProvider};
+    use tracing::info_span;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::{continue_trace, trace_context_headers};
+
+    #[test]
+    fn test_trace_context_is_continued_and_propagated() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let provider = TracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+
+        let span = info_span!("http_request", trace_id = tracing::field::Empty);
+        continue_trace(&span, &headers);
+        let outgoing = span.in_scope(trace_context_headers);
+
+        let traceparent = outgoing.get("traceparent").unwrap().to_str().unwrap();
+        assert!(traceparent.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+        assert!(!traceparent.contains("00f067aa0ba902b7"));
+    }
+}
@@ -0,0 +1,788 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use thiserror::Error;
+use tracing_subscriber::EnvFilter;
+
+use crate::{
+    application::{
+        http::{BindAddress, ClientAuth, RateLimitConfig, TlsConfig},
+        providers::config::{
+            HttpFile, ProviderFile, ProvidersConfig, ProvidersConfigError, ProvidersFile,
+        },
+    },
+    domain::{secret::Secret, token::models::login_attempts::LockoutPolicy},
+    env::{Env, LogFormat},
+    infrastructure::db::DatabaseBackend,
+};
+
+/// Read from the working directory when [Env::config_file] is not set.
+pub const DEFAULT_CONFIG_FILE: &str = "ferrisprinter.toml";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Could not read {}: {source}", path.display())]
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid configuration file {}: {source}", path.display())]
+    ParseFile {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("{field} is required, set it in the configuration file or as {env}")]
+    Missing {
+        field: &'static str,
+        env: &'static str,
+    },
+    #[error("{field}: {reason}")]
+    InvalidValue { field: &'static str, reason: String },
+    #[error(transparent)]
+    Providers(#[from] ProvidersConfigError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    pub address: BindAddress,
+    pub tls: Option<TlsConfig>,
+    pub shutdown_timeout: Duration,
+    pub health_check_timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseConfig {
+    pub url: Secret,
+    /// Told by the scheme of [DatabaseConfig::url].
+    pub backend: DatabaseBackend,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// How long a query may wait for a connection of the pool.
+    pub acquire_timeout: Duration,
+    /// Applies the pending migrations on startup.
+    pub run_migrations: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthConfig {
+    pub admin_api_key: Option<Secret>,
+    pub login_rate_limit: RateLimitConfig,
+    pub lockout: LockoutPolicy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditConfig {
+    /// How long the audit events are kept for.
+    pub retention: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggingConfig {
+    /// A level, such as `debug`, or filtering directives in the syntax of
+    /// `RUST_LOG`, such as `info,ferrisprinter=debug`.
+    pub level: String,
+    pub format: LogFormat,
+    pub otlp_endpoint: Option<String>,
+    pub service_name: String,
+}
+
+/// The settings of the service, from the configuration file overridden by the
+/// environment, see [Env].
+///
+/// ```toml
+/// [server]
+/// address = "::" # or "unix:/run/ferrisprinter.sock"
+/// port = 8443
+/// shutdown_timeout_ms = 30000
+/// health_check_timeout_ms = 2000
+///
+/// [server.tls]
+/// cert_file = "/etc/ferrisprinter/tls.crt"
+/// key_file = "/etc/ferrisprinter/tls.key"
+/// client_ca_file = "/etc/ferrisprinter/clients-ca.crt"
+/// client_auth = "optional"
+///
+/// [database]
+/// url = "postgres://ferris@db.internal/ferris" # or "sqlite:///var/lib/ferrisprinter/ferris.db", or "memory:"
+/// max_connections = 20
+/// min_connections = 2
+/// acquire_timeout_ms = 5000
+/// run_migrations = true
+///
+/// [auth]
+/// admin_api_key = "..."
+/// max_failures = 5
+/// lockout_secs = 900
+///
+/// [auth.login_rate_limit]
+/// per_api_key = 10
+/// per_client_ip = 30
+/// window_secs = 60
+///
+/// [audit]
+/// retention_days = 365
+///
+/// [logging]
+/// level = "info,ferrisprinter=debug"
+/// format = "json"
+/// otlp_endpoint = "http://localhost:4318"
+/// ```
+///
+/// along with the provider settings described in [ProvidersConfig]. The log
+/// level and the login rate limits are reloaded while running, see
+/// [crate::application::reload].
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub auth: AuthConfig,
+    pub audit: AuditConfig,
+    pub logging: LoggingConfig,
+    pub providers: ProvidersConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    server: ServerFile,
+    database: DatabaseFile,
+    auth: AuthFile,
+    audit: AuditFile,
+    logging: LoggingFile,
+    enabled_providers: Option<Vec<String>>,
+    http: HttpFile,
+    providers: HashMap<String, ProviderFile>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ServerFile {
+    address: Option<String>,
+    port: Option<u16>,
+    shutdown_timeout_ms: Option<u64>,
+    health_check_timeout_ms: Option<u64>,
+    tls: TlsFile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TlsFile {
+    cert_file: Option<PathBuf>,
+    key_file: Option<PathBuf>,
+    client_ca_file: Option<PathBuf>,
+    client_auth: Option<ClientAuth>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DatabaseFile {
+    url: Option<Secret>,
+    max_connections: Option<u32>,
+    min_connections: Option<u32>,
+    acquire_timeout_ms: Option<u64>,
+    run_migrations: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct AuthFile {
+    admin_api_key: Option<Secret>,
+    max_failures: Option<u32>,
+    lockout_secs: Option<u64>,
+    login_rate_limit: RateLimitFile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RateLimitFile {
+    per_api_key: Option<u32>,
+    per_client_ip: Option<u32>,
+    window_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct AuditFile {
+    retention_days: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LoggingFile {
+    level: Option<String>,
+    format: Option<LogFormat>,
+    otlp_endpoint: Option<String>,
+    service_name: Option<String>,
+}
+
+fn positive<T: Copy + Into<u64>>(field: &'static str, value: T) -> Result<T, ConfigError> {
+    if value.into() == 0 {
+        return Err(ConfigError::InvalidValue {
+            field,
+            reason: "must be greater than 0".to_string(),
+        });
+    }
+
+    Ok(value)
+}
+
+impl DatabaseConfig {
+    fn from_file(env: &Env, file: DatabaseFile) -> Result<Self, ConfigError> {
+        let max_connections = env
+            .database_max_connections
+            .or(file.max_connections)
+            .unwrap_or(5);
+        positive("database.max_connections", max_connections)?;
+        let url = env
+            .database_url
+            .clone()
+            .map(Secret::new)
+            .or(file.url)
+            .or_else(|| env.demo.then(|| Secret::new("memory:")))
+            .ok_or(ConfigError::Missing {
+                field: "database.url",
+                env: "DATABASE_URL",
+            })?;
+        let backend =
+            DatabaseBackend::from_url(url.expose()).ok_or_else(|| ConfigError::InvalidValue {
+                field: "database.url",
+                reason: "expected a postgres://, sqlite: or memory: URL".to_string(),
+            })?;
+        let database = DatabaseConfig {
+            url,
+            backend,
+            max_connections,
+            min_connections: env
+                .database_min_connections
+                .or(file.min_connections)
+                .unwrap_or(0),
+            acquire_timeout: Duration::from_millis(positive(
+                "database.acquire_timeout_ms",
+                env.database_acquire_timeout_ms
+                    .or(file.acquire_timeout_ms)
+                    .unwrap_or(30000),
+            )?),
+            run_migrations: env
+                .database_run_migrations
+                .or(file.run_migrations)
+                .unwrap_or(false),
+        };
+        if database.min_connections > database.max_connections {
+            return Err(ConfigError::InvalidValue {
+                field: "database.min_connections",
+                reason: format!(
+                    "cannot exceed database.max_connections ({})",
+                    database.max_connections
+                ),
+            });
+        }
+
+        Ok(database)
+    }
+}
+
+impl LoggingConfig {
+    fn from_file(env: &Env, file: LoggingFile) -> Result<Self, ConfigError> {
+        let level = env
+            .log_level
+            .clone()
+            .or(file.level)
+            .unwrap_or_else(|| "info".to_string());
+        if let Err(e) = EnvFilter::try_new(&level) {
+            return Err(ConfigError::InvalidValue {
+                field: "logging.level",
+                reason: e.to_string(),
+            });
+        }
+
+        Ok(LoggingConfig {
+            level,
+            format: env.log_format.or(file.format).unwrap_or_default(),
+            otlp_endpoint: env
+                .otel_exporter_otlp_endpoint
+                .clone()
+                .or(file.otlp_endpoint),
+            service_name: env
+                .otel_service_name
+                .clone()
+                .or(file.service_name)
+                .unwrap_or_else(|| "ferrisprinter".to_string()),
+        })
+    }
+}
+
+/// The settings of the `migrate` command, which does not serve and thus only
+/// requires those of the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationConfig {
+    pub database: DatabaseConfig,
+    pub logging: LoggingConfig,
+}
+
+impl Config {
+    /// The file read by [Config::load], which may not exist unless it is
+    /// given by [Env::config_file].
+    pub fn file_path(env: &Env) -> &Path {
+        env.config_file
+            .as_deref()
+            .unwrap_or(Path::new(DEFAULT_CONFIG_FILE))
+    }
+
+    /// Reads the configuration file and validates the settings.
+    pub fn load(env: &Env) -> Result<Self, ConfigError> {
+        Self::from_file(env, Self::read_file(env)?)
+    }
+
+    /// Reads the configuration file and validates the settings of the
+    /// database and of the logs only.
+    pub fn load_migration(env: &Env) -> Result<MigrationConfig, ConfigError> {
+        let file = Self::read_file(env)?;
+
+        Ok(MigrationConfig {
+            database: DatabaseConfig::from_file(env, file.database)?,
+            logging: LoggingConfig::from_file(env, file.logging)?,
+        })
+    }
+
+    fn read_file(env: &Env) -> Result<ConfigFile, ConfigError> {
+        let path = Self::file_path(env);
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && env.config_file.is_none() => {
+                String::new()
+            }
+            Err(source) => {
+                return Err(ConfigError::ReadFile {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+
+        toml::from_str(&content).map_err(|source| ConfigError::ParseFile {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    fn from_file(env: &Env, file: ConfigFile) -> Result<Self, ConfigError> {
+        let address = env.bind_address.clone().or(file.server.address);
+        let port = env.port.or(file.server.port);
+        let address = match address
+            .as_deref()
+            .map(|address| address.strip_prefix("unix:"))
+        {
+            Some(Some(path)) if !path.is_empty() => BindAddress::Unix(PathBuf::from(path)),
+            Some(Some(_)) => {
+                return Err(ConfigError::InvalidValue {
+                    field: "server.address",
+                    reason: "the path of the Unix socket is missing".to_string(),
+                })
+            }
+            _ => BindAddress::Tcp(SocketAddr::new(
+                address
+                    .map(|address| {
+                        address
+                            .parse::<IpAddr>()
+                            .map_err(|_| ConfigError::InvalidValue {
+                                field: "server.address",
+                                reason: format!(
+                                    "{:?} is neither an IP address nor unix:<path>",
+                                    address
+                                ),
+                            })
+                    })
+                    .transpose()?
+                    .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+                port.ok_or(ConfigError::Missing {
+                    field: "server.port",
+                    env: "PORT",
+                })?,
+            )),
+        };
+
+        let client_ca_file = env
+            .tls_client_ca_file
+            .clone()
+            .or(file.server.tls.client_ca_file);
+        let tls = match (
+            env.tls_cert_file.clone().or(file.server.tls.cert_file),
+            env.tls_key_file.clone().or(file.server.tls.key_file),
+        ) {
+            (Some(cert_file), Some(key_file)) => Some(TlsConfig {
+                cert_file,
+                key_file,
+                client_ca_file,
+                client_auth: env
+                    .tls_client_auth
+                    .or(file.server.tls.client_auth)
+                    .unwrap_or_default(),
+            }),
+            (Some(_), None) => {
+                return Err(ConfigError::Missing {
+                    field: "server.tls.key_file",
+                    env: "TLS_KEY_FILE",
+                })
+            }
+            (None, None) if client_ca_file.is_none() => None,
+            // Client certificates can only be verified over TLS.
+            (None, _) => {
+                return Err(ConfigError::Missing {
+                    field: "server.tls.cert_file",
+                    env: "TLS_CERT_FILE",
+                })
+            }
+        };
+
+        let server = ServerConfig {
+            address,
+            tls,
+            shutdown_timeout: Duration::from_millis(
+                env.shutdown_timeout_ms
+                    .or(file.server.shutdown_timeout_ms)
+                    .unwrap_or(30000),
+            ),
+            health_check_timeout: Duration::from_millis(positive(
+                "server.health_check_timeout_ms",
+                env.health_check_timeout_ms
+                    .or(file.server.health_check_timeout_ms)
+                    .unwrap_or(2000),
+            )?),
+        };
+
+        let database = DatabaseConfig::from_file(env, file.database)?;
+
+        let rate_limit = RateLimitConfig::default();
+        let lockout = LockoutPolicy::default();
+        let auth = AuthConfig {
+            admin_api_key: env
+                .admin_api_key
+                .clone()
+                .map(Secret::new)
+                .or(file.auth.admin_api_key),
+            login_rate_limit: RateLimitConfig {
+                per_api_key: positive(
+                    "auth.login_rate_limit.per_api_key",
+                    env.login_rate_limit_per_api_key
+                        .or(file.auth.login_rate_limit.per_api_key)
+                        .unwrap_or(rate_limit.per_api_key),
+                )?,
+                per_client_ip: positive(
+                    "auth.login_rate_limit.per_client_ip",
+                    env.login_rate_limit_per_client_ip
+                        .or(file.auth.login_rate_limit.per_client_ip)
+                        .unwrap_or(rate_limit.per_client_ip),
+                )?,
+                window: env
+                    .login_rate_limit_window_secs
+                    .or(file.auth.login_rate_limit.window_secs)
+                    .map(|secs| positive("auth.login_rate_limit.window_secs", secs))
+                    .transpose()?
+                    .map_or(rate_limit.window, Duration::from_secs),
+            },
+            lockout: LockoutPolicy {
+                max_failures: positive(
+                    "auth.max_failures",
+                    env.login_max_failures
+                        .or(file.auth.max_failures)
+                        .unwrap_or(lockout.max_failures),
+                )?,
+                lockout: env
+                    .login_lockout_secs
+                    .or(file.auth.lockout_secs)
+                    .map_or(lockout.lockout, Duration::from_secs),
+            },
+        };
+
+        let retention_days = positive(
+            "audit.retention_days",
+            env.audit_retention_days
+                .or(file.audit.retention_days)
+                .unwrap_or(365),
+        )?;
+        let audit = AuditConfig {
+            retention: Duration::from_secs(retention_days.saturating_mul(24 * 60 * 60)),
+        };
+
+        let logging = LoggingConfig::from_file(env, file.logging)?;
+
+        let providers = ProvidersConfig::from_file(
+            env,
+            ProvidersFile {
+                enabled_providers: file.enabled_providers,
+                http: file.http,
+                providers: file.providers,
+            },
+        )?;
+
+        Ok(Self {
+            server,
+            database,
+            auth,
+            audit,
+            logging,
+            providers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::{Config, ConfigError, ConfigFile};
+    use crate::{
+        application::http::{BindAddress, ClientAuth},
+        domain::token::ports::provider_token_service::ProviderType,
+        env::{Env, LogFormat},
+        infrastructure::db::DatabaseBackend,
+    };
+
+    fn env() -> Env {
+        Env {
+            database_url: Some("postgres://localhost/ferris".to_string()),
+            port: Some(8080),
+            ..Env::default()
+        }
+    }
+
+    fn file(content: &str) -> ConfigFile {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_defaults_only_require_the_port_and_database() {
+        let config = Config::from_file(&env(), ConfigFile::default()).unwrap();
+
+        assert_eq!(config.server.address.to_string(), "0.0.0.0:8080");
+        assert_eq!(config.database.backend, DatabaseBackend::Postgres);
+        assert_eq!(
+            config.audit.retention,
+            Duration::from_secs(365 * 24 * 60 * 60)
+        );
+        assert_eq!(config.database.max_connections, 5);
+        assert_eq!(config.logging.level, "info");
+        assert!(config.auth.admin_api_key.is_none());
+
+        let error = Config::from_file(&Env::default(), ConfigFile::default()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "server.port is required, set it in the configuration file or as PORT"
+        );
+    }
+
+    #[test]
+    fn test_demo_mode_defaults_to_the_memory_database() {
+        let demo_env = Env {
+            demo: true,
+            database_url: None,
+            ..env()
+        };
+        let config = Config::from_file(&demo_env, ConfigFile::default()).unwrap();
+        assert_eq!(config.database.backend, DatabaseBackend::Memory);
+
+        let config =
+            Config::from_file(&demo_env, file("[database]\nurl = \"sqlite::memory:\"")).unwrap();
+        assert_eq!(config.database.backend, DatabaseBackend::Sqlite);
+    }
+
+    #[test]
+    fn test_environment_overrides_the_file() {
+        let env = Env {
+            log_level: Some("debug".to_string()),
+            database_max_connections: Some(8),
+            ..env()
+        };
+        let file = file(
+            r#"
+            enabled_providers = ["bambulab"]
+
+            [server]
+            address = "::1"
+            port = 9000
+
+            [database]
+            url = "postgres://db.internal/ferris"
+            max_connections = 20
+            min_connections = 2
+            run_migrations = true
+
+            [auth]
+            admin_api_key = "operator"
+
+            [auth.login_rate_limit]
+            per_api_key = 3
+
+            [audit]
+            retention_days = 30
+
+            [logging]
+            level = "warn"
+            format = "json"
+
+            [providers.bambulab]
+            api_url = "https://api.example.com"
+            "#,
+        );
+
+        let config = Config::from_file(&env, file).unwrap();
+
+        assert_eq!(config.server.address.to_string(), "[::1]:8080");
+        assert_eq!(config.database.url.expose(), "postgres://localhost/ferris");
+        assert_eq!(config.database.max_connections, 8);
+        assert_eq!(config.database.min_connections, 2);
+        assert!(config.database.run_migrations);
+        assert_eq!(
+            config.auth.admin_api_key.as_ref().map(|key| key.expose()),
+            Some("operator")
+        );
+        assert_eq!(config.auth.login_rate_limit.per_api_key, 3);
+        assert_eq!(config.auth.login_rate_limit.window, Duration::from_secs(60));
+        assert_eq!(
+            config.audit.retention,
+            Duration::from_secs(30 * 24 * 60 * 60)
+        );
+        assert_eq!(config.logging.level, "debug");
+        assert_eq!(config.logging.format, LogFormat::Json);
+        assert_eq!(
+            config
+                .providers
+                .get(&ProviderType::BambuLab)
+                .unwrap()
+                .api_url,
+            "https://api.example.com"
+        );
+    }
+
+    #[test]
+    fn test_migrations_only_require_the_database() {
+        let path =
+            std::env::temp_dir().join(format!("ferrisprinter-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "[database]\nurl = \"sqlite::memory:\"").unwrap();
+        let env = Env {
+            config_file: Some(path.clone()),
+            ..Env::default()
+        };
+
+        let config = Config::load_migration(&env);
+        let error = Config::load(&env).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(config.database.backend, DatabaseBackend::Sqlite);
+        assert_eq!(config.logging.level, "info");
+        assert!(matches!(
+            error,
+            ConfigError::Missing {
+                field: "server.port",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_invalid_values_name_the_offending_field() {
+        let error = Config::from_file(&env(), file("[logging]\nlevel = \"ferrisprinter=loud\""))
+            .unwrap_err();
+        assert!(error.to_string().starts_with("logging.level: "));
+
+        let error = Config::from_file(
+            &env(),
+            file("[database]\nmax_connections = 2\nmin_connections = 3"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "database.min_connections: cannot exceed database.max_connections (2)"
+        );
+
+        let mysql_env = Env {
+            database_url: Some("mysql://localhost/ferris".to_string()),
+            ..env()
+        };
+        let error = Config::from_file(&mysql_env, file("")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "database.url: expected a postgres://, sqlite: or memory: URL"
+        );
+
+        let error = Config::from_file(&env(), file("[audit]\nretention_days = 0")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "audit.retention_days: must be greater than 0"
+        );
+
+        let error = Config::from_file(&env(), file("[auth]\nmax_failures = 0")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "auth.max_failures: must be greater than 0"
+        );
+
+        let error = Config::from_file(&env(), file("[auth.login_rate_limit]\nper_client_ip = 0"))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "auth.login_rate_limit.per_client_ip: must be greater than 0"
+        );
+
+        let error = Config::from_file(&env(), file("[http]\nconnect_timeout_ms = 0")).unwrap_err();
+        assert!(matches!(error, ConfigError::Providers(_)));
+
+        assert!(toml::from_str::<ConfigFile>("[server]\nhost = \"::\"").is_err());
+    }
+
+    #[test]
+    fn test_unix_socket_and_tls_are_configured() {
+        let unix_env = Env {
+            bind_address: Some("unix:/run/ferrisprinter.sock".to_string()),
+            port: None,
+            ..env()
+        };
+        let tls_file = file(
+            r#"
+            [server.tls]
+            cert_file = "/etc/ferrisprinter/tls.crt"
+            key_file = "/etc/ferrisprinter/tls.key"
+            client_ca_file = "/etc/ferrisprinter/clients-ca.crt"
+            "#,
+        );
+
+        let config = Config::from_file(&unix_env, tls_file).unwrap();
+
+        assert_eq!(
+            config.server.address,
+            BindAddress::Unix(PathBuf::from("/run/ferrisprinter.sock"))
+        );
+        let tls = config.server.tls.unwrap();
+        assert_eq!(tls.key_file, PathBuf::from("/etc/ferrisprinter/tls.key"));
+        assert_eq!(tls.client_auth, ClientAuth::Required);
+
+        let error = Config::from_file(
+            &Env {
+                bind_address: Some("localhost".to_string()),
+                ..env()
+            },
+            ConfigFile::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "server.address: \"localhost\" is neither an IP address nor unix:<path>"
+        );
+
+        let error = Config::from_file(
+            &env(),
+            file("[server.tls]\nclient_ca_file = \"/etc/ferrisprinter/clients-ca.crt\""),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "server.tls.cert_file is required, set it in the configuration file or as TLS_CERT_FILE"
+        );
+    }
+}
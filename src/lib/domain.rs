@@ -1 +1,6 @@
+pub mod audit;
+pub mod health;
+pub mod proxy;
+pub mod secret;
+pub mod tenant;
 pub mod token;
@@ -0,0 +1,363 @@
+use std::{fmt::Display, net::IpAddr, str::FromStr};
+
+use thiserror::Error;
+use time::OffsetDateTime;
+
+/// Something done with the credentials of a tenant or to its printers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditAction {
+    /// An account was linked to a printer.
+    TokenCreate,
+    TokenRead,
+    /// The raw provider token was exported.
+    TokenExport,
+    /// The account linked to a printer was logged in again.
+    TokenUpdate,
+    /// The access token was renewed while proxying a request.
+    TokenRenew,
+    TokenDelete,
+    /// A request changing the state of a printer was proxied to its provider.
+    PrinterCommand,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("Unknown audit action: {0}")]
+pub struct UnknownAuditActionError(String);
+
+impl AuditAction {
+    /// Whether the action hands out a secret, and so must not be carried out
+    /// unless it is on record.
+    pub fn reveals_secret(&self) -> bool {
+        matches!(self, AuditAction::TokenExport)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::TokenCreate => "token.create",
+            AuditAction::TokenRead => "token.read",
+            AuditAction::TokenExport => "token.export",
+            AuditAction::TokenUpdate => "token.update",
+            AuditAction::TokenRenew => "token.renew",
+            AuditAction::TokenDelete => "token.delete",
+            AuditAction::PrinterCommand => "printer.command",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = UnknownAuditActionError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "token.create" => Ok(AuditAction::TokenCreate),
+            "token.read" => Ok(AuditAction::TokenRead),
+            "token.export" => Ok(AuditAction::TokenExport),
+            "token.update" => Ok(AuditAction::TokenUpdate),
+            "token.renew" => Ok(AuditAction::TokenRenew),
+            "token.delete" => Ok(AuditAction::TokenDelete),
+            "printer.command" => Ok(AuditAction::PrinterCommand),
+            _ => Err(UnknownAuditActionError(value.to_string())),
+        }
+    }
+}
+
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditOutcome {
+    Success,
+    /// The caller was not allowed to perform the action.
+    Denied,
+    Failure,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("Unknown audit outcome: {0}")]
+pub struct UnknownAuditOutcomeError(String);
+
+impl AuditOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Denied => "denied",
+            AuditOutcome::Failure => "failure",
+        }
+    }
+}
+
+impl FromStr for AuditOutcome {
+    type Err = UnknownAuditOutcomeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "success" => Ok(AuditOutcome::Success),
+            "denied" => Ok(AuditOutcome::Denied),
+            "failure" => Ok(AuditOutcome::Failure),
+            _ => Err(UnknownAuditOutcomeError(value.to_string())),
+        }
+    }
+}
+
+impl Display for AuditOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// An entry of the audit log. Events are only ever appended, and removed
+/// once older than the retention period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub id: uuid::Uuid,
+    pub occurred_at: OffsetDateTime,
+    pub tenant_id: uuid::Uuid,
+    /// The actor, that is the API key the request was authenticated with.
+    pub api_key_id: uuid::Uuid,
+    pub action: AuditAction,
+    pub outcome: AuditOutcome,
+    /// Serial number of the printer acted on.
+    pub serial_number: Option<String>,
+    /// The stored token acted on, when it was found.
+    pub token_id: Option<uuid::Uuid>,
+    /// What the action was about beyond its target, such as the method and
+    /// path of a printer command.
+    pub detail: Option<String>,
+    pub request_id: Option<String>,
+    /// Absent for requests received on a Unix socket.
+    pub client_ip: Option<IpAddr>,
+}
+
+impl AuditEvent {
+    /// An event occurring now, successful until told otherwise.
+    pub fn new(tenant_id: uuid::Uuid, api_key_id: uuid::Uuid, action: AuditAction) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            occurred_at: OffsetDateTime::now_utc(),
+            tenant_id,
+            api_key_id,
+            action,
+            outcome: AuditOutcome::Success,
+            serial_number: None,
+            token_id: None,
+            detail: None,
+            request_id: None,
+            client_ip: None,
+        }
+    }
+
+    pub fn with_outcome(mut self, outcome: AuditOutcome) -> Self {
+        self.outcome = outcome;
+        self
+    }
+
+    pub fn with_serial_number(mut self, serial_number: &str) -> Self {
+        self.serial_number = Some(serial_number.to_string());
+        self
+    }
+
+    pub fn with_token_id(mut self, token_id: Option<uuid::Uuid>) -> Self {
+        self.token_id = token_id;
+        self
+    }
+
+    pub fn with_detail(mut self, detail: String) -> Self {
+        self.detail = Some(detail);
+        self
+    }
+
+    pub fn with_request(mut self, request_id: Option<String>, client_ip: Option<IpAddr>) -> Self {
+        self.request_id = request_id;
+        self.client_ip = client_ip;
+        self
+    }
+}
+
+/// An [AuditEvent] as stored, with its enumerations and address as text.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct AuditEventRow {
+    pub id: uuid::Uuid,
+    pub occurred_at: OffsetDateTime,
+    pub tenant_id: uuid::Uuid,
+    pub api_key_id: uuid::Uuid,
+    pub action: String,
+    pub outcome: String,
+    pub serial_number: Option<String>,
+    pub token_id: Option<uuid::Uuid>,
+    pub detail: Option<String>,
+    pub request_id: Option<String>,
+    pub client_ip: Option<String>,
+}
+
+impl From<AuditEventRow> for AuditEvent {
+    fn from(row: AuditEventRow) -> Self {
+        Self {
+            id: row.id,
+            occurred_at: row.occurred_at,
+            tenant_id: row.tenant_id,
+            api_key_id: row.api_key_id,
+            action: AuditAction::from_str(&row.action).unwrap(),
+            outcome: AuditOutcome::from_str(&row.outcome).unwrap(),
+            serial_number: row.serial_number,
+            token_id: row.token_id,
+            detail: row.detail,
+            request_id: row.request_id,
+            client_ip: row.client_ip.and_then(|ip| ip.parse().ok()),
+        }
+    }
+}
+
+impl From<&AuditEvent> for AuditEventRow {
+    fn from(event: &AuditEvent) -> Self {
+        Self {
+            id: event.id,
+            occurred_at: event.occurred_at,
+            tenant_id: event.tenant_id,
+            api_key_id: event.api_key_id,
+            action: event.action.as_str().to_string(),
+            outcome: event.outcome.as_str().to_string(),
+            serial_number: event.serial_number.clone(),
+            token_id: event.token_id,
+            detail: event.detail.clone(),
+            request_id: event.request_id.clone(),
+            client_ip: event.client_ip.map(|ip| ip.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RecordAuditEventError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ListAuditEventsError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum PurgeAuditEventsError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// Filters and pagination applied when listing the audit log of a tenant,
+/// newest events first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListAuditEventsQuery {
+    pub action: Option<AuditAction>,
+    pub outcome: Option<AuditOutcome>,
+    pub serial_number: Option<String>,
+    pub api_key_id: Option<uuid::Uuid>,
+    /// Inclusive.
+    pub since: Option<OffsetDateTime>,
+    /// Exclusive.
+    pub until: Option<OffsetDateTime>,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl ListAuditEventsQuery {
+    pub const DEFAULT_LIMIT: u32 = 50;
+    pub const MAX_LIMIT: u32 = 500;
+
+    /// An unfiltered query, with `limit` clamped to
+    /// [ListAuditEventsQuery::MAX_LIMIT].
+    pub fn new(limit: Option<u32>, offset: Option<u32>) -> Self {
+        Self {
+            action: None,
+            outcome: None,
+            serial_number: None,
+            api_key_id: None,
+            since: None,
+            until: None,
+            limit: limit
+                .unwrap_or(Self::DEFAULT_LIMIT)
+                .clamp(1, Self::MAX_LIMIT),
+            offset: offset.unwrap_or(0),
+        }
+    }
+
+    /// Whether `event` passes the filters, for the backends unable to apply
+    /// them in a query.
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        self.action.is_none_or(|action| event.action == action)
+            && self.outcome.is_none_or(|outcome| event.outcome == outcome)
+            && self
+                .serial_number
+                .as_ref()
+                .is_none_or(|serial_number| event.serial_number.as_ref() == Some(serial_number))
+            && self
+                .api_key_id
+                .is_none_or(|api_key_id| event.api_key_id == api_key_id)
+            && self.since.is_none_or(|since| event.occurred_at >= since)
+            && self.until.is_none_or(|until| event.occurred_at < until)
+    }
+}
+
+/// A page of events along with the number of events matching the query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEventPage {
+    pub items: Vec<AuditEvent>,
+    pub total: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::{AuditAction, AuditEvent, AuditOutcome, ListAuditEventsQuery};
+
+    #[test]
+    fn test_actions_and_outcomes_round_trip() {
+        for action in [
+            AuditAction::TokenCreate,
+            AuditAction::TokenRead,
+            AuditAction::TokenExport,
+            AuditAction::TokenUpdate,
+            AuditAction::TokenRenew,
+            AuditAction::TokenDelete,
+            AuditAction::PrinterCommand,
+        ] {
+            assert_eq!(AuditAction::from_str(action.as_str()).unwrap(), action);
+        }
+        for outcome in [
+            AuditOutcome::Success,
+            AuditOutcome::Denied,
+            AuditOutcome::Failure,
+        ] {
+            assert_eq!(AuditOutcome::from_str(outcome.as_str()).unwrap(), outcome);
+        }
+        assert!(AuditAction::from_str("token.steal").is_err());
+    }
+
+    #[test]
+    fn test_query_filters_events() {
+        let event = AuditEvent::new(
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+            AuditAction::TokenRead,
+        )
+        .with_serial_number("01S00C123456789");
+
+        let mut query = ListAuditEventsQuery::new(None, None);
+        assert!(query.matches(&event));
+        assert_eq!(query.limit, ListAuditEventsQuery::DEFAULT_LIMIT);
+
+        query.serial_number = Some("01S00C123456789".to_string());
+        query.since = Some(event.occurred_at);
+        assert!(query.matches(&event));
+
+        query.until = Some(event.occurred_at);
+        assert!(!query.matches(&event));
+
+        query.until = None;
+        query.action = Some(AuditAction::TokenDelete);
+        assert!(!query.matches(&event));
+    }
+}
@@ -0,0 +1,52 @@
+use std::future::Future;
+
+use time::OffsetDateTime;
+
+use crate::domain::audit::models::audit::{
+    AuditEvent, AuditEventPage, ListAuditEventsError, ListAuditEventsQuery, PurgeAuditEventsError,
+    RecordAuditEventError,
+};
+
+pub trait AuditService: Clone + Send + Sync + 'static {
+    /// Asynchronously records an [AuditEvent].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [RecordAuditEventError] when the event could not be stored and its action [reveals a secret](crate::domain::audit::models::audit::AuditAction::reveals_secret), which must then not be carried out.
+    /// - MUST only log the failure for any other action, so that the audit log being unavailable never fails it.
+    fn record(
+        &self,
+        event: AuditEvent,
+    ) -> impl Future<Output = Result<(), RecordAuditEventError>> + Send;
+    fn list_events(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListAuditEventsQuery,
+    ) -> impl Future<Output = Result<AuditEventPage, ListAuditEventsError>> + Send;
+    /// Asynchronously deletes the events older than the retention period,
+    /// returning how many were.
+    fn purge_expired(&self) -> impl Future<Output = Result<u64, PurgeAuditEventsError>> + Send;
+}
+
+/// Append-only record of who did what with the credentials and printers of
+/// each tenant.
+pub trait AuditLog: Clone + Send + Sync + 'static {
+    /// Asynchronously appends an [AuditEvent]. Events are never updated.
+    fn record(
+        &self,
+        event: &AuditEvent,
+    ) -> impl Future<Output = Result<(), RecordAuditEventError>> + Send;
+    /// Asynchronously lists the events of a tenant matching `query`, newest
+    /// first.
+    fn list_events(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListAuditEventsQuery,
+    ) -> impl Future<Output = Result<AuditEventPage, ListAuditEventsError>> + Send;
+    /// Asynchronously deletes the events of every tenant that occurred before
+    /// `before`, returning how many were.
+    fn purge_before(
+        &self,
+        before: OffsetDateTime,
+    ) -> impl Future<Output = Result<u64, PurgeAuditEventsError>> + Send;
+}
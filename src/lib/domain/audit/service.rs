@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use time::OffsetDateTime;
+use tracing::{error, info, warn};
+
+use super::{
+    models::audit::{
+        AuditEvent, AuditEventPage, AuditOutcome, ListAuditEventsError, ListAuditEventsQuery,
+        PurgeAuditEventsError, RecordAuditEventError,
+    },
+    ports::audit::{AuditLog, AuditService},
+};
+
+#[derive(Debug, Clone)]
+pub struct AuditServiceImpl<L>
+where
+    L: AuditLog,
+{
+    audit_log: L,
+    retention: Duration,
+}
+
+impl<L> AuditServiceImpl<L>
+where
+    L: AuditLog,
+{
+    /// Events are kept for `retention`, see [AuditService::purge_expired].
+    pub fn new(audit_log: L, retention: Duration) -> Self {
+        Self {
+            audit_log,
+            retention,
+        }
+    }
+}
+
+impl<L> AuditService for AuditServiceImpl<L>
+where
+    L: AuditLog,
+{
+    async fn record(&self, event: AuditEvent) -> Result<(), RecordAuditEventError> {
+        // Also logged under the `audit` target, which outlives the retention
+        // period wherever the logs are shipped.
+        macro_rules! log_event {
+            ($level:ident) => {
+                $level!(
+                    target: "audit",
+                    action = %event.action,
+                    outcome = %event.outcome,
+                    tenant_id = %event.tenant_id,
+                    api_key_id = %event.api_key_id,
+                    serial_number = event.serial_number.as_deref(),
+                    token_id = event.token_id.map(|id| id.to_string()),
+                    detail = event.detail.as_deref(),
+                    request_id = event.request_id.as_deref(),
+                    client_ip = event.client_ip.map(|ip| ip.to_string()),
+                )
+            };
+        }
+        match event.outcome {
+            AuditOutcome::Success => log_event!(info),
+            AuditOutcome::Denied | AuditOutcome::Failure => log_event!(warn),
+        }
+
+        if let Err(e) = self.audit_log.record(&event).await {
+            error!(
+                "Failed to record the audit event {} of tenant {}: {}",
+                event.action, event.tenant_id, e
+            );
+            if event.action.reveals_secret() {
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn list_events(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListAuditEventsQuery,
+    ) -> Result<AuditEventPage, ListAuditEventsError> {
+        self.audit_log.list_events(tenant_id, query).await
+    }
+
+    async fn purge_expired(&self) -> Result<u64, PurgeAuditEventsError> {
+        self.audit_log
+            .purge_before(OffsetDateTime::now_utc() - self.retention)
+            .await
+    }
+}
@@ -0,0 +1,127 @@
+use std::{fmt::Display, time::Duration};
+
+use thiserror::Error;
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DependencyKind {
+    Database,
+    Provider,
+}
+
+impl DependencyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DependencyKind::Database => "database",
+            DependencyKind::Provider => "provider",
+        }
+    }
+}
+
+impl Display for DependencyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+impl HealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthStatus::Up => "up",
+            HealthStatus::Down => "down",
+        }
+    }
+}
+
+impl Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// State of the circuit breaker guarding the calls to a dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CircuitState {
+    Closed,
+    /// Calls fail fast without reaching the dependency.
+    Open,
+    /// The next call is let through to probe whether the dependency recovered.
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+impl Display for CircuitState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum HealthCheckError {
+    #[error("no answer within {}ms", .0.as_millis())]
+    TimedOut(Duration),
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// The most recent failed check of a dependency, kept after it recovers so
+/// operators can tell a flapping dependency from a healthy one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyError {
+    pub message: String,
+    pub occurred_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyHealth {
+    pub name: String,
+    pub kind: DependencyKind,
+    /// Whether the service can serve requests without this dependency. Only
+    /// critical dependencies affect readiness.
+    pub critical: bool,
+    pub status: HealthStatus,
+    pub latency: Duration,
+    pub last_error: Option<DependencyError>,
+    /// For dependencies called through a circuit breaker.
+    pub circuit: Option<CircuitState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    pub dependencies: Vec<DependencyHealth>,
+}
+
+impl HealthReport {
+    /// A report is ready when every critical dependency is up; providers are
+    /// only reported, since an outage at a provider must not take the
+    /// service out of rotation.
+    pub fn is_ready(&self) -> bool {
+        self.dependencies
+            .iter()
+            .filter(|dependency| dependency.critical)
+            .all(|dependency| dependency.status == HealthStatus::Up)
+    }
+
+    pub fn status(&self) -> HealthStatus {
+        if self.is_ready() {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use std::future::Future;
+
+use crate::domain::health::models::health::{HealthCheckError, HealthReport};
+
+pub trait HealthService: Clone + Send + Sync + 'static {
+    /// Asynchronously checks every dependency of the service, each within
+    /// the configured timeout.
+    fn check(&self) -> impl Future<Output = HealthReport> + Send;
+    /// Asynchronously checks the critical dependencies, which decide whether
+    /// the service can take traffic. Unlike [HealthService::check], the
+    /// reachability of the providers is reported as last checked, until
+    /// older than a TTL, so that frequent probes add little traffic to them.
+    fn check_readiness(&self) -> impl Future<Output = HealthReport> + Send;
+}
+
+pub trait DatabaseHealthCheck: Send + Sync + Clone + 'static {
+    /// Name of the database in the [HealthReport], such as `postgres`.
+    fn name(&self) -> &'static str;
+    /// Asynchronously runs a trivial query against the database.
+    fn ping(&self) -> impl Future<Output = Result<(), HealthCheckError>> + Send;
+}
@@ -0,0 +1,192 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures_util::future::join_all;
+use time::OffsetDateTime;
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::{
+    application::providers::token_provider_manager::TokenProviderManager,
+    domain::token::ports::provider_token_service::ProviderTokenService,
+};
+
+use super::{
+    models::health::{
+        CircuitState, DependencyError, DependencyHealth, DependencyKind, HealthCheckError,
+        HealthReport, HealthStatus,
+    },
+    ports::health::{DatabaseHealthCheck, HealthService},
+};
+
+/// How long the reachability of the providers reported by readiness probes
+/// is reused, so that probes, frequent and sent by every replica, do not
+/// turn into a steady load on the providers.
+pub const PROVIDER_CHECK_TTL: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+struct ProviderChecks {
+    checked_at: Instant,
+    dependencies: Vec<DependencyHealth>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthServiceImpl<D, P>
+where
+    D: DatabaseHealthCheck,
+    P: ProviderTokenService,
+{
+    database: D,
+    token_provider_manager: Arc<TokenProviderManager<P>>,
+    timeout: Duration,
+    last_errors: Arc<Mutex<HashMap<String, DependencyError>>>,
+    provider_check_ttl: Duration,
+    /// Held while the providers are checked, so that concurrent probes
+    /// wait for the same check.
+    provider_checks: Arc<AsyncMutex<Option<ProviderChecks>>>,
+}
+
+impl<D, P> HealthServiceImpl<D, P>
+where
+    D: DatabaseHealthCheck,
+    P: ProviderTokenService,
+{
+    pub fn new(
+        database: D,
+        token_provider_manager: Arc<TokenProviderManager<P>>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            database,
+            token_provider_manager,
+            timeout,
+            last_errors: Arc::new(Mutex::new(HashMap::new())),
+            provider_check_ttl: PROVIDER_CHECK_TTL,
+            provider_checks: Arc::new(AsyncMutex::new(None)),
+        }
+    }
+
+    pub fn with_provider_check_ttl(mut self, provider_check_ttl: Duration) -> Self {
+        self.provider_check_ttl = provider_check_ttl;
+        self
+    }
+
+    async fn check_database(&self) -> DependencyHealth {
+        self.check_dependency(
+            self.database.name().to_string(),
+            DependencyKind::Database,
+            true,
+            self.database.ping(),
+            || None,
+        )
+        .await
+    }
+
+    async fn check_providers(&self) -> Vec<DependencyHealth> {
+        let mut providers: Vec<_> = self.token_provider_manager.providers().collect();
+        providers.sort_by_key(|(provider_type, _)| *provider_type);
+        join_all(providers.into_iter().map(|(provider_type, provider)| {
+            self.check_dependency(
+                provider_type.to_string(),
+                DependencyKind::Provider,
+                false,
+                async move {
+                    provider
+                        .check_reachability()
+                        .await
+                        .map_err(|e| HealthCheckError::Failed(e.to_string()))
+                },
+                || provider.circuit_state(),
+            )
+        }))
+        .await
+    }
+
+    /// The providers as last checked, checked again once older than the TTL.
+    async fn cached_providers(&self) -> Vec<DependencyHealth> {
+        let mut checks = self.provider_checks.lock().await;
+        if let Some(checks) = checks
+            .as_ref()
+            .filter(|checks| checks.checked_at.elapsed() < self.provider_check_ttl)
+        {
+            return checks.dependencies.clone();
+        }
+
+        let dependencies = self.check_providers().await;
+        *checks = Some(ProviderChecks {
+            checked_at: Instant::now(),
+            dependencies: dependencies.clone(),
+        });
+
+        dependencies
+    }
+
+    async fn check_dependency(
+        &self,
+        name: String,
+        kind: DependencyKind,
+        critical: bool,
+        check: impl Future<Output = Result<(), HealthCheckError>>,
+        circuit: impl FnOnce() -> Option<CircuitState>,
+    ) -> DependencyHealth {
+        let started_at = Instant::now();
+        let result = tokio::time::timeout(self.timeout, check)
+            .await
+            .unwrap_or(Err(HealthCheckError::TimedOut(self.timeout)));
+        let latency = started_at.elapsed();
+
+        let mut last_errors = self.last_errors.lock().unwrap();
+        if let Err(e) = &result {
+            last_errors.insert(
+                name.clone(),
+                DependencyError {
+                    message: e.to_string(),
+                    occurred_at: OffsetDateTime::now_utc(),
+                },
+            );
+        }
+
+        DependencyHealth {
+            last_error: last_errors.get(&name).cloned(),
+            name,
+            kind,
+            critical,
+            status: match result {
+                Ok(()) => HealthStatus::Up,
+                Err(_) => HealthStatus::Down,
+            },
+            latency,
+            // Read after the check, which may have moved the breaker.
+            circuit: circuit(),
+        }
+    }
+}
+
+impl<D, P> HealthService for HealthServiceImpl<D, P>
+where
+    D: DatabaseHealthCheck,
+    P: ProviderTokenService,
+{
+    async fn check(&self) -> HealthReport {
+        let (database, providers) = tokio::join!(self.check_database(), self.check_providers());
+        *self.provider_checks.lock().await = Some(ProviderChecks {
+            checked_at: Instant::now(),
+            dependencies: providers.clone(),
+        });
+
+        HealthReport {
+            dependencies: std::iter::once(database).chain(providers).collect(),
+        }
+    }
+
+    async fn check_readiness(&self) -> HealthReport {
+        let (database, providers) = tokio::join!(self.check_database(), self.cached_providers());
+
+        HealthReport {
+            dependencies: std::iter::once(database).chain(providers).collect(),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use bytes::Bytes;
+use futures_util::stream::BoxStream;
+use http::{Method, StatusCode};
+use thiserror::Error;
+
+use crate::domain::token::models::refresh_token::{FindRefreshTokenError, UpdateRefreshTokenError};
+
+/// An upstream path prefix callers may reach through the proxy, along with the
+/// method allowed on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyRule {
+    method: Method,
+    path_prefix: String,
+}
+
+impl ProxyRule {
+    pub fn new(method: Method, path_prefix: &str) -> Self {
+        Self {
+            method,
+            path_prefix: path_prefix.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// A rule matches its prefix itself and any path below it, never a
+    /// sibling sharing the same leading characters.
+    pub fn matches(&self, method: &Method, path: &ProxyPath) -> bool {
+        if self.method != *method {
+            return false;
+        }
+
+        match path.as_str().strip_prefix(&self.path_prefix) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProxyAllowlist(Vec<ProxyRule>);
+
+impl ProxyAllowlist {
+    pub fn new(rules: Vec<ProxyRule>) -> Self {
+        Self(rules)
+    }
+
+    pub fn is_allowed(&self, method: &Method, path: &ProxyPath) -> bool {
+        self.0.iter().any(|rule| rule.matches(method, path))
+    }
+}
+
+/// Absolute upstream path, guaranteed not to contain dot segments that could
+/// be used to escape an allowlisted prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProxyPath(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("Invalid proxy path: {0}")]
+pub struct InvalidProxyPathError(String);
+
+impl ProxyPath {
+    pub fn new(value: &str) -> Result<ProxyPath, InvalidProxyPathError> {
+        let is_valid = value.starts_with('/')
+            && value
+                .split('/')
+                .skip(1)
+                .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+
+        if is_valid {
+            Ok(Self(value.to_string()))
+        } else {
+            Err(InvalidProxyPathError(value.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyRequest {
+    pub method: Method,
+    pub path: ProxyPath,
+    pub query: Option<String>,
+    pub content_type: Option<String>,
+    /// Buffered so the request can be replayed after a token renewal.
+    pub body: Bytes,
+}
+
+pub type ProxyBody = BoxStream<'static, Result<Bytes, std::io::Error>>;
+
+pub struct ProxyResponse {
+    pub status: StatusCode,
+    pub content_type: Option<String>,
+    pub body: ProxyBody,
+    /// Whether the access token had to be renewed to make the request.
+    pub token_renewed: bool,
+}
+
+impl std::fmt::Debug for ProxyResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyResponse")
+            .field("status", &self.status)
+            .field("content_type", &self.content_type)
+            .field("token_renewed", &self.token_renewed)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProxyError {
+    #[error("{method} {path} is not allowed through the proxy")]
+    NotAllowed { method: Method, path: String },
+    #[error(transparent)]
+    TokenNotFound(#[from] FindRefreshTokenError),
+    #[error("Could not renew the provider token: {0}")]
+    Renewal(#[from] UpdateRefreshTokenError),
+    #[error("The provider could not be reached: {0}")]
+    Upstream(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+
+    use super::{ProxyAllowlist, ProxyPath, ProxyRule};
+
+    #[test]
+    fn test_proxy_path_rejects_dot_segments() {
+        assert!(ProxyPath::new("/v1/user-service/my/tasks").is_ok());
+        assert!(ProxyPath::new("v1/user-service").is_err());
+        assert!(ProxyPath::new("/v1/user-service/my/tasks/../../user/refreshtoken").is_err());
+        assert!(ProxyPath::new("/v1//user-service").is_err());
+    }
+
+    #[test]
+    fn test_allowlist_matches_method_and_prefix() {
+        let allowlist = ProxyAllowlist::new(vec![ProxyRule::new(
+            Method::GET,
+            "/v1/user-service/my/tasks",
+        )]);
+
+        let allowed = |method: Method, path: &str| {
+            allowlist.is_allowed(&method, &ProxyPath::new(path).unwrap())
+        };
+
+        assert!(allowed(Method::GET, "/v1/user-service/my/tasks"));
+        assert!(allowed(Method::GET, "/v1/user-service/my/tasks/42"));
+        assert!(!allowed(Method::POST, "/v1/user-service/my/tasks"));
+        assert!(!allowed(Method::GET, "/v1/user-service/my/tasks-archive"));
+        assert!(!allowed(Method::GET, "/v1/user-service/user/refreshtoken"));
+    }
+}
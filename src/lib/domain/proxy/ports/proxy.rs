@@ -0,0 +1,32 @@
+use std::future::Future;
+
+use crate::domain::{
+    proxy::models::proxy::{ProxyError, ProxyRequest, ProxyResponse},
+    token::models::token::Token,
+};
+
+pub trait ProxyService: Clone + Send + Sync + 'static {
+    /// Asynchronously forwards a request to the provider API on behalf of the
+    /// printer's linked account, renewing its access token once if rejected.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [ProxyError::NotAllowed] without contacting the provider if the
+    ///   request is not covered by the allowlist.
+    fn forward(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+        request: ProxyRequest,
+    ) -> impl Future<Output = Result<ProxyResponse, ProxyError>> + Send;
+}
+
+pub trait ProviderApiClient: Send + Sync + Clone + 'static {
+    /// Asynchronously sends the request to the provider API, authenticated
+    /// with the given access token.
+    fn send(
+        &self,
+        access_token: &Token,
+        request: &ProxyRequest,
+    ) -> impl Future<Output = Result<ProxyResponse, ProxyError>> + Send;
+}
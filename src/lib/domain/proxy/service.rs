@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use http::StatusCode;
+use tracing::{info, info_span, Instrument};
+
+use crate::domain::token::{
+    models::{
+        refresh_token::{FindRefreshTokenError, RefreshToken},
+        token::Token,
+    },
+    ports::{provider_token_service::ProviderType, refresh_token::RefreshTokenService},
+};
+
+use super::{
+    models::proxy::{ProxyAllowlist, ProxyError, ProxyRequest, ProxyResponse},
+    ports::proxy::{ProviderApiClient, ProxyService},
+};
+
+#[derive(Debug, Clone)]
+pub struct ProxyServiceImpl<R, C>
+where
+    R: RefreshTokenService,
+    C: ProviderApiClient,
+{
+    provider_type: ProviderType,
+    refresh_token_service: Arc<R>,
+    api_client: C,
+    allowlist: ProxyAllowlist,
+}
+
+impl<R, C> ProxyServiceImpl<R, C>
+where
+    R: RefreshTokenService,
+    C: ProviderApiClient,
+{
+    pub fn new(
+        provider_type: ProviderType,
+        refresh_token_service: Arc<R>,
+        api_client: C,
+        allowlist: ProxyAllowlist,
+    ) -> Self {
+        Self {
+            provider_type,
+            refresh_token_service,
+            api_client,
+            allowlist,
+        }
+    }
+
+    async fn renew_access_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> Result<Token, ProxyError> {
+        let refresh_token = self
+            .refresh_token_service
+            .renew_refresh_token(tenant_id, serial_number)
+            .await?;
+
+        refresh_token
+            .access_token
+            .ok_or_else(|| ProxyError::Upstream("the provider returned no access token".into()))
+    }
+
+    /// Counts the forwarded call under `provider_calls_total`, with the same
+    /// outcomes as the token operations.
+    fn record_call(&self, result: &Result<ProxyResponse, ProxyError>) {
+        let outcome = match result {
+            Ok(response) if response.status == StatusCode::UNAUTHORIZED => "invalid_token",
+            Ok(response) if response.status == StatusCode::TOO_MANY_REQUESTS => "rate_limited",
+            Ok(response) if response.status.is_server_error() => "unavailable",
+            Ok(_) => "success",
+            Err(_) => "unavailable",
+        };
+
+        metrics::counter!(
+            "provider_calls_total",
+            "provider" => self.provider_type.as_str(),
+            "operation" => "proxy",
+            "outcome" => outcome
+        )
+        .increment(1);
+    }
+
+    /// Tokens of other providers are reported as missing, exactly like tokens
+    /// of other tenants.
+    fn ensure_provider(&self, refresh_token: &RefreshToken) -> Result<(), ProxyError> {
+        if refresh_token.provider == self.provider_type {
+            Ok(())
+        } else {
+            Err(FindRefreshTokenError::NotFound {
+                serial_number: refresh_token.serial_number.to_string(),
+            }
+            .into())
+        }
+    }
+}
+
+impl<R, C> ProxyService for ProxyServiceImpl<R, C>
+where
+    R: RefreshTokenService,
+    C: ProviderApiClient,
+{
+    async fn forward(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+        request: ProxyRequest,
+    ) -> Result<ProxyResponse, ProxyError> {
+        if !self.allowlist.is_allowed(&request.method, &request.path) {
+            return Err(ProxyError::NotAllowed {
+                method: request.method,
+                path: request.path.as_str().to_string(),
+            });
+        }
+
+        let refresh_token = self
+            .refresh_token_service
+            .find_by_serial_number(tenant_id, serial_number)
+            .await?;
+        self.ensure_provider(&refresh_token)?;
+
+        let (access_token, token_renewed) = match refresh_token.access_token {
+            Some(access_token) => (access_token, false),
+            None => (
+                self.renew_access_token(tenant_id, serial_number).await?,
+                true,
+            ),
+        };
+
+        let span = info_span!(
+            "provider_call",
+            provider = %self.provider_type,
+            operation = "proxy",
+            serial_number,
+            http.method = %request.method,
+            http.path = request.path.as_str()
+        );
+
+        let response = self
+            .api_client
+            .send(&access_token, &request)
+            .instrument(span.clone())
+            .await;
+        self.record_call(&response);
+        let response = response?;
+        if response.status != StatusCode::UNAUTHORIZED {
+            return Ok(ProxyResponse {
+                token_renewed,
+                ..response
+            });
+        }
+
+        info!(
+            "Access token rejected by {} for serial_number {}, renewing",
+            self.provider_type, serial_number
+        );
+        let access_token = self.renew_access_token(tenant_id, serial_number).await?;
+
+        let response = self
+            .api_client
+            .send(&access_token, &request)
+            .instrument(span)
+            .await;
+        self.record_call(&response);
+
+        response.map(|response| ProxyResponse {
+            token_renewed: true,
+            ..response
+        })
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt::{Debug, Display};
+
+use serde::{Deserialize, Deserializer, Serializer};
+use zeroize::Zeroize;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// A credential or token that must never end up in logs.
+///
+/// It is formatted as `[REDACTED]`, wiped from memory when dropped, and only
+/// readable through [Secret::expose]. It does not implement `Serialize`:
+/// fields that must be sent verbatim opt in with
+/// `#[serde(serialize_with = "serialize_exposed")]`.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Returns the plaintext value, for the few places that must send it.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Display for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+impl AsRef<Secret> for Secret {
+    fn as_ref(&self) -> &Secret {
+        self
+    }
+}
+
+/// Serializes the plaintext value of a secret, for the request and response
+/// bodies that exist to carry it.
+pub fn serialize_exposed<T, S>(secret: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<Secret>,
+    S: Serializer,
+{
+    serializer.serialize_str(secret.as_ref().expose())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::{serialize_exposed, Secret};
+
+    #[test]
+    fn test_secret_is_redacted_when_formatted() {
+        let secret = Secret::new("hunter2");
+
+        assert_eq!(format!("{}", secret), "[REDACTED]");
+        assert!(!format!("{:?}", secret).contains("hunter2"));
+        assert!(!format!("{:#?}", Some(&secret)).contains("hunter2"));
+        assert_eq!(secret.expose(), "hunter2");
+    }
+
+    #[test]
+    fn test_secret_is_serialized_only_when_allowed() {
+        #[derive(Serialize)]
+        struct Body {
+            #[serde(serialize_with = "serialize_exposed")]
+            password: Secret,
+        }
+
+        let body = Body {
+            password: Secret::new("hunter2"),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&body).unwrap(),
+            r#"{"password":"hunter2"}"#
+        );
+    }
+}
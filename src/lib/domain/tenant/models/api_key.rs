@@ -0,0 +1,158 @@
+use std::{fmt::Display, str::FromStr};
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use time::OffsetDateTime;
+
+use crate::domain::secret::Secret;
+
+const API_KEY_PREFIX: &str = "fp_";
+
+/// A plaintext API key as handed out to a tenant.
+///
+/// Only its SHA-256 digest is ever persisted, so the plaintext value is
+/// returned exactly once, when the key is issued.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKey(Secret);
+
+impl ApiKey {
+    pub fn generate() -> Self {
+        Self(Secret::new(format!(
+            "{}{}{}",
+            API_KEY_PREFIX,
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        )))
+    }
+
+    pub fn new(value: &str) -> Result<ApiKey, InvalidApiKeyError> {
+        let trimmed = value.trim();
+
+        if trimmed.starts_with(API_KEY_PREFIX) && trimmed.len() > API_KEY_PREFIX.len() {
+            Ok(Self(Secret::new(trimmed)))
+        } else {
+            Err(InvalidApiKeyError)
+        }
+    }
+
+    pub fn expose(&self) -> &str {
+        self.0.expose()
+    }
+
+    /// Hex encoded SHA-256 digest of the key, used for storage and lookups.
+    pub fn hash(&self) -> String {
+        format!("{:x}", Sha256::digest(self.0.expose().as_bytes()))
+    }
+}
+
+impl AsRef<Secret> for ApiKey {
+    fn as_ref(&self) -> &Secret {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("API key is malformed")]
+pub struct InvalidApiKeyError;
+
+/// Permission granted to an [ApiKey] on top of the regular token operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Scope {
+    /// Privileged operations of the tenant, such as exporting raw provider tokens.
+    Admin,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("Unknown scope: {0}")]
+pub struct UnknownScopeError(String);
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = UnknownScopeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "admin" => Ok(Scope::Admin),
+            _ => Err(UnknownScopeError(value.to_string())),
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Metadata of an issued [ApiKey], never containing the key itself.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiKeyInfo {
+    pub id: uuid::Uuid,
+    pub tenant_id: uuid::Uuid,
+    pub scopes: Vec<Scope>,
+    pub created_at: OffsetDateTime,
+}
+
+impl ApiKeyInfo {
+    pub fn new(
+        id: uuid::Uuid,
+        tenant_id: uuid::Uuid,
+        scopes: Vec<Scope>,
+        created_at: OffsetDateTime,
+    ) -> Self {
+        Self {
+            id,
+            tenant_id,
+            scopes,
+            created_at,
+        }
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AuthenticateTenantError {
+    #[error("Invalid API key")]
+    InvalidApiKey,
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiKey;
+
+    #[test]
+    fn test_generated_api_key_is_parsable() {
+        let api_key = ApiKey::generate();
+
+        let parsed = ApiKey::new(api_key.expose()).unwrap();
+
+        assert_eq!(parsed, api_key);
+        assert_eq!(parsed.hash(), api_key.hash());
+    }
+
+    #[test]
+    fn test_api_key_rejects_foreign_values() {
+        assert!(ApiKey::new("").is_err());
+        assert!(ApiKey::new("fp_").is_err());
+        assert!(ApiKey::new("some-other-token").is_err());
+    }
+
+    #[test]
+    fn test_api_key_debug_is_redacted() {
+        let api_key = ApiKey::generate();
+
+        assert!(!format!("{:?}", api_key).contains(api_key.expose()));
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+use time::OffsetDateTime;
+
+/// A department (or any other isolated party) sharing the deployment.
+///
+/// Every refresh token belongs to exactly one tenant and is never visible to
+/// callers authenticated for another tenant.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tenant {
+    pub id: uuid::Uuid,
+    pub name: TenantName,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+impl Tenant {
+    pub fn new(
+        id: uuid::Uuid,
+        name: TenantName,
+        created_at: OffsetDateTime,
+        updated_at: OffsetDateTime,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::FromRow)]
+pub struct TenantRow {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TenantName(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("Tenant name cannot be empty")]
+pub struct TenantNameEmptyError;
+
+impl TenantName {
+    pub fn new(value: &str) -> Result<TenantName, TenantNameEmptyError> {
+        let trimmed = value.trim();
+
+        if trimmed.is_empty() {
+            Err(TenantNameEmptyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for TenantName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateTenantRequest {
+    name: TenantName,
+}
+
+impl CreateTenantRequest {
+    pub fn new(name: TenantName) -> Self {
+        Self { name }
+    }
+
+    pub fn name(&self) -> &TenantName {
+        &self.name
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CreateTenantError {
+    #[error("Tenant with name {name} already exists")]
+    Duplicate { name: TenantName },
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum FindTenantError {
+    #[error("Tenant with id {id} not found")]
+    NotFound { id: uuid::Uuid },
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ListTenantsError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
@@ -0,0 +1,78 @@
+use std::future::Future;
+
+use crate::domain::tenant::models::{
+    api_key::{ApiKey, ApiKeyInfo, AuthenticateTenantError, Scope},
+    tenant::{CreateTenantError, CreateTenantRequest, FindTenantError, ListTenantsError, Tenant},
+};
+
+pub trait TenantService: Clone + Send + Sync + 'static {
+    /// Asynchronously creates a new [Tenant] along with its first [ApiKey].
+    fn create_tenant(
+        &self,
+        request: &CreateTenantRequest,
+    ) -> impl Future<Output = Result<(Tenant, ApiKey), CreateTenantError>> + Send;
+    fn list_tenants(&self) -> impl Future<Output = Result<Vec<Tenant>, ListTenantsError>> + Send;
+    fn find_tenant(
+        &self,
+        tenant_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<Tenant, FindTenantError>> + Send;
+    /// Asynchronously deletes a [Tenant], its API keys and every token it owns.
+    fn delete_tenant(
+        &self,
+        tenant_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<(), FindTenantError>> + Send;
+    /// Asynchronously issues an additional [ApiKey] for an existing [Tenant].
+    fn create_api_key(
+        &self,
+        tenant_id: &uuid::Uuid,
+        scopes: &[Scope],
+    ) -> impl Future<Output = Result<(ApiKeyInfo, ApiKey), FindTenantError>> + Send;
+    /// Asynchronously resolves the [Tenant] owning the given [ApiKey], along
+    /// with the key's metadata.
+    fn authenticate(
+        &self,
+        api_key: &ApiKey,
+    ) -> impl Future<Output = Result<(Tenant, ApiKeyInfo), AuthenticateTenantError>> + Send;
+}
+
+pub trait TenantRepository: Send + Sync + Clone + 'static {
+    /// Asynchronously creates a new [Tenant] and stores the hash of its first API key.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [CreateTenantError::Duplicate] if a tenant with the same name already exists.
+    fn create_tenant(
+        &self,
+        name: &str,
+        api_key_hash: &str,
+    ) -> impl Future<Output = Result<Tenant, CreateTenantError>> + Send;
+    fn list_tenants(&self) -> impl Future<Output = Result<Vec<Tenant>, ListTenantsError>> + Send;
+    fn find_tenant(
+        &self,
+        tenant_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<Tenant, FindTenantError>> + Send;
+    /// Asynchronously deletes a [Tenant].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [FindTenantError::NotFound] if no tenant has the given id.
+    fn delete_tenant(
+        &self,
+        tenant_id: &uuid::Uuid,
+    ) -> impl Future<Output = Result<(), FindTenantError>> + Send;
+    fn create_api_key(
+        &self,
+        tenant_id: &uuid::Uuid,
+        api_key_hash: &str,
+        scopes: &[Scope],
+    ) -> impl Future<Output = Result<ApiKeyInfo, FindTenantError>> + Send;
+    /// Asynchronously finds the API key with the given hash and the [Tenant] owning it.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [AuthenticateTenantError::InvalidApiKey] if no key matches.
+    fn find_by_api_key_hash(
+        &self,
+        api_key_hash: &str,
+    ) -> impl Future<Output = Result<(Tenant, ApiKeyInfo), AuthenticateTenantError>> + Send;
+}
@@ -0,0 +1,79 @@
+use super::{
+    models::{
+        api_key::{ApiKey, ApiKeyInfo, AuthenticateTenantError, Scope},
+        tenant::{
+            CreateTenantError, CreateTenantRequest, FindTenantError, ListTenantsError, Tenant,
+        },
+    },
+    ports::tenant::{TenantRepository, TenantService},
+};
+
+#[derive(Debug, Clone)]
+pub struct TenantServiceImpl<R>
+where
+    R: TenantRepository,
+{
+    tenant_repository: R,
+}
+
+impl<R> TenantServiceImpl<R>
+where
+    R: TenantRepository,
+{
+    pub fn new(tenant_repository: R) -> Self {
+        Self { tenant_repository }
+    }
+}
+
+impl<R> TenantService for TenantServiceImpl<R>
+where
+    R: TenantRepository,
+{
+    async fn create_tenant(
+        &self,
+        request: &CreateTenantRequest,
+    ) -> Result<(Tenant, ApiKey), CreateTenantError> {
+        let api_key = ApiKey::generate();
+        let tenant = self
+            .tenant_repository
+            .create_tenant(request.name().as_str(), &api_key.hash())
+            .await?;
+
+        Ok((tenant, api_key))
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<Tenant>, ListTenantsError> {
+        self.tenant_repository.list_tenants().await
+    }
+
+    async fn find_tenant(&self, tenant_id: &uuid::Uuid) -> Result<Tenant, FindTenantError> {
+        self.tenant_repository.find_tenant(tenant_id).await
+    }
+
+    async fn delete_tenant(&self, tenant_id: &uuid::Uuid) -> Result<(), FindTenantError> {
+        self.tenant_repository.delete_tenant(tenant_id).await
+    }
+
+    async fn create_api_key(
+        &self,
+        tenant_id: &uuid::Uuid,
+        scopes: &[Scope],
+    ) -> Result<(ApiKeyInfo, ApiKey), FindTenantError> {
+        let api_key = ApiKey::generate();
+        let info = self
+            .tenant_repository
+            .create_api_key(tenant_id, &api_key.hash(), scopes)
+            .await?;
+
+        Ok((info, api_key))
+    }
+
+    async fn authenticate(
+        &self,
+        api_key: &ApiKey,
+    ) -> Result<(Tenant, ApiKeyInfo), AuthenticateTenantError> {
+        self.tenant_repository
+            .find_by_api_key_hash(&api_key.hash())
+            .await
+    }
+}
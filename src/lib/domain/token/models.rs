@@ -1,2 +1,3 @@
+pub mod login_attempts;
 pub mod refresh_token;
 pub mod token;
@@ -0,0 +1,225 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+use crate::domain::token::ports::provider_token_service::ProviderType;
+
+use super::token::CreateTokensError;
+
+/// How many times in a row the provider may reject the credentials of an
+/// account before further logins to it are refused, and for how long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockoutPolicy {
+    pub max_failures: u32,
+    pub lockout: Duration,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self {
+            max_failures: 5,
+            lockout: Duration::from_secs(15 * 60),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error(
+    "Too many failed logins to the provider account, retry in {} seconds",
+    retry_after.as_secs().max(1)
+)]
+pub struct LoginLockedOutError {
+    pub retry_after: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Failures {
+    count: u32,
+    /// Failures older than the lockout window are forgotten.
+    first_at: Instant,
+    locked_until: Option<Instant>,
+}
+
+impl Failures {
+    fn is_expired(&self, policy: &LockoutPolicy, now: Instant) -> bool {
+        match self.locked_until {
+            Some(locked_until) => locked_until <= now,
+            None => self.first_at + policy.lockout <= now,
+        }
+    }
+}
+
+/// Failed logins per provider account, so that a client retrying wrong
+/// credentials does not get the account locked by the provider itself.
+///
+/// Only rejected credentials count: an unavailable provider says nothing about
+/// the password. The counters are kept in memory and thus per instance.
+#[derive(Debug, Default)]
+pub struct LoginAttempts {
+    policy: LockoutPolicy,
+    failures: Mutex<HashMap<(ProviderType, String), Failures>>,
+}
+
+impl LoginAttempts {
+    pub fn new(policy: LockoutPolicy) -> Self {
+        Self {
+            policy,
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fails while the account is locked out.
+    pub fn check(
+        &self,
+        provider: &ProviderType,
+        username: &str,
+    ) -> Result<(), LoginLockedOutError> {
+        self.check_at(provider, username, Instant::now())
+    }
+
+    /// Counts the outcome of a login to the account.
+    pub fn record<T>(
+        &self,
+        provider: &ProviderType,
+        username: &str,
+        result: &Result<T, CreateTokensError>,
+    ) {
+        self.record_at(provider, username, result, Instant::now())
+    }
+
+    fn key(provider: &ProviderType, username: &str) -> (ProviderType, String) {
+        (provider.clone(), username.trim().to_lowercase())
+    }
+
+    fn check_at(
+        &self,
+        provider: &ProviderType,
+        username: &str,
+        now: Instant,
+    ) -> Result<(), LoginLockedOutError> {
+        let failures = self.failures.lock().unwrap();
+
+        match failures
+            .get(&Self::key(provider, username))
+            .and_then(|failures| failures.locked_until)
+        {
+            Some(locked_until) if locked_until > now => Err(LoginLockedOutError {
+                retry_after: locked_until - now,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_at<T>(
+        &self,
+        provider: &ProviderType,
+        username: &str,
+        result: &Result<T, CreateTokensError>,
+        now: Instant,
+    ) {
+        let key = Self::key(provider, username);
+        let mut failures = self.failures.lock().unwrap();
+
+        match result {
+            Ok(_) => {
+                failures.remove(&key);
+            }
+            Err(CreateTokensError::InvalidCredentials) => {
+                failures.retain(|_, failures| !failures.is_expired(&self.policy, now));
+
+                let entry = failures.entry(key).or_insert(Failures {
+                    count: 0,
+                    first_at: now,
+                    locked_until: None,
+                });
+                entry.count += 1;
+                if entry.count >= self.policy.max_failures {
+                    entry.locked_until = Some(now + self.policy.lockout);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{LockoutPolicy, LoginAttempts};
+    use crate::domain::token::{
+        models::token::CreateTokensError, ports::provider_token_service::ProviderType,
+    };
+
+    const REJECTED: Result<(), CreateTokensError> = Err(CreateTokensError::InvalidCredentials);
+
+    fn attempts() -> LoginAttempts {
+        LoginAttempts::new(LockoutPolicy {
+            max_failures: 3,
+            lockout: Duration::from_secs(60),
+        })
+    }
+
+    #[test]
+    fn test_account_is_locked_out_after_repeated_rejections() {
+        let attempts = attempts();
+        let provider = ProviderType::BambuLab;
+        let now = Instant::now();
+
+        for _ in 0..2 {
+            attempts.record_at(&provider, "maker@example.com", &REJECTED, now);
+        }
+        assert!(attempts
+            .check_at(&provider, "maker@example.com", now)
+            .is_ok());
+
+        attempts.record_at(&provider, "Maker@Example.com", &REJECTED, now);
+        let locked = attempts
+            .check_at(
+                &provider,
+                "maker@example.com",
+                now + Duration::from_secs(20),
+            )
+            .unwrap_err();
+        assert_eq!(locked.retry_after, Duration::from_secs(40));
+        assert!(attempts
+            .check_at(&provider, "other@example.com", now)
+            .is_ok());
+
+        assert!(attempts
+            .check_at(
+                &provider,
+                "maker@example.com",
+                now + Duration::from_secs(60)
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_only_rejected_credentials_count() {
+        let attempts = attempts();
+        let provider = ProviderType::BambuLab;
+        let now = Instant::now();
+
+        attempts.record_at(&provider, "maker@example.com", &REJECTED, now);
+        attempts.record_at(&provider, "maker@example.com", &REJECTED, now);
+        attempts.record_at(&provider, "maker@example.com", &Ok(()), now);
+        for _ in 0..3 {
+            attempts.record_at::<()>(
+                &provider,
+                "maker@example.com",
+                &Err(CreateTokensError::Unavailable("timeout".to_string())),
+                now,
+            );
+        }
+        attempts.record_at(&provider, "maker@example.com", &REJECTED, now);
+
+        assert!(attempts
+            .check_at(&provider, "maker@example.com", now)
+            .is_ok());
+    }
+}
@@ -1,22 +1,36 @@
 use thiserror::Error;
 
 use derive_more::From;
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
 
-use super::token::{SerialNumber, Token};
+use crate::domain::token::ports::provider_token_service::ProviderType;
+
+use super::login_attempts::LoginLockedOutError;
+use super::token::{CreateTokensError, Password, SerialNumber, Token, Username};
+
+/// How long before its expiry a token is reported as [TokenHealth::ExpiringSoon].
+const EXPIRY_WARNING_WINDOW: Duration = Duration::days(7);
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RefreshToken {
     pub id: uuid::Uuid,
+    pub tenant_id: uuid::Uuid,
+    pub provider: ProviderType,
     pub serial_number: SerialNumber,
     pub token: Token,
+    /// Latest access token obtained alongside [RefreshToken::token], used by the
+    /// proxy to call the provider on behalf of the tenant.
+    pub access_token: Option<Token>,
     pub created_at: time::OffsetDateTime,
     pub updated_at: time::OffsetDateTime,
+    pub expires_at: Option<time::OffsetDateTime>,
 }
 
 impl RefreshToken {
     pub fn new(
         id: uuid::Uuid,
+        tenant_id: uuid::Uuid,
+        provider: ProviderType,
         serial_number: SerialNumber,
         token: Token,
         created_at: time::OffsetDateTime,
@@ -24,32 +38,98 @@ impl RefreshToken {
     ) -> Self {
         Self {
             id,
+            tenant_id,
+            provider,
             serial_number,
             token,
+            access_token: None,
             created_at,
             updated_at,
+            expires_at: None,
+        }
+    }
+
+    pub fn with_access_token(mut self, access_token: Option<Token>) -> Self {
+        self.access_token = access_token;
+        self
+    }
+
+    pub fn with_expires_at(mut self, expires_at: Option<time::OffsetDateTime>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    pub fn health(&self, now: OffsetDateTime) -> TokenHealth {
+        match self.expires_at {
+            None => TokenHealth::Unknown,
+            Some(expires_at) if expires_at <= now => TokenHealth::Expired,
+            Some(expires_at) if expires_at - now <= EXPIRY_WARNING_WINDOW => {
+                TokenHealth::ExpiringSoon
+            }
+            Some(_) => TokenHealth::Healthy,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, From)]
+/// Coarse health of a stored token, derived from its expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenHealth {
+    Healthy,
+    ExpiringSoon,
+    Expired,
+    /// The provider did not disclose when the token expires.
+    Unknown,
+}
+
+impl TokenHealth {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenHealth::Healthy => "healthy",
+            TokenHealth::ExpiringSoon => "expiring_soon",
+            TokenHealth::Expired => "expired",
+            TokenHealth::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, From, sqlx::FromRow)]
 pub struct RefreshTokenRow {
     pub id: uuid::Uuid,
+    pub tenant_id: uuid::Uuid,
+    pub provider: String,
     pub serial_number: String,
     pub token: String,
+    pub access_token: Option<String>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+/// The tokens are plain strings as read from the database, so they are left
+/// out rather than wrapped.
+impl std::fmt::Debug for RefreshTokenRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RefreshTokenRow")
+            .field("id", &self.id)
+            .field("tenant_id", &self.tenant_id)
+            .field("provider", &self.provider)
+            .field("serial_number", &self.serial_number)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, From)]
 pub struct CreateRefreshTokenRequest {
-    username: String,
-    password: String,
+    username: Username,
+    password: Password,
     serial_number: SerialNumber,
 }
 
 impl CreateRefreshTokenRequest {
-    pub fn new(username: String, password: String, serial_number: SerialNumber) -> Self {
+    pub fn new(username: Username, password: Password, serial_number: SerialNumber) -> Self {
         Self {
             username,
             password,
@@ -62,28 +142,237 @@ impl CreateRefreshTokenRequest {
     }
 
     pub fn username(&self) -> &str {
-        &self.username
+        self.username.as_str()
     }
 
-    pub fn password(&self) -> &str {
+    pub fn password(&self) -> &Password {
         &self.password
     }
 }
 
+/// What to do when the tenant already owns a token for the requested serial number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CreateMode {
+    /// Fail with [CreateRefreshTokenError::Duplicate].
+    #[default]
+    Reject,
+    /// Replace the stored tokens, keeping the identity of the existing token.
+    Replace,
+}
+
+/// Client supplied key making retries of a creation safe: a token created with
+/// a key is replaced, instead of reported as a duplicate, when the same key is
+/// presented again.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IdempotencyKey(String);
+
+#[derive(Clone, Debug, Error)]
+#[error(
+    "Idempotency key must be between 1 and {} characters",
+    IdempotencyKey::MAX_LENGTH
+)]
+pub struct InvalidIdempotencyKeyError;
+
+impl IdempotencyKey {
+    pub const MAX_LENGTH: usize = 255;
+
+    pub fn new(value: &str) -> Result<IdempotencyKey, InvalidIdempotencyKeyError> {
+        let trimmed = value.trim();
+
+        if trimmed.is_empty() || trimmed.len() > Self::MAX_LENGTH {
+            Err(InvalidIdempotencyKeyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// How a conflicting registration of the same serial number is resolved.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CreateRefreshTokenOptions {
+    pub mode: CreateMode,
+    pub idempotency_key: Option<IdempotencyKey>,
+}
+
+/// Result of a creation, telling a fresh token apart from a replaced one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CreatedRefreshToken {
+    Created(RefreshToken),
+    Replaced(RefreshToken),
+}
+
+impl CreatedRefreshToken {
+    pub fn refresh_token(&self) -> &RefreshToken {
+        match self {
+            CreatedRefreshToken::Created(refresh_token)
+            | CreatedRefreshToken::Replaced(refresh_token) => refresh_token,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum CreateRefreshTokenError {
     #[error("Token with serial number {name} already exists")]
     Duplicate { name: SerialNumber },
+    #[error("Token provider not found")]
+    ProviderNotFound,
     #[error(transparent)]
-    Unknown(#[from] anyhow::Error),
+    LockedOut(#[from] LoginLockedOutError),
+    #[error(transparent)]
+    Provider(#[from] CreateTokensError),
     #[error("Database error: {0}")]
     DatabaseError(#[from] sqlx::Error),
-    #[error("Token provider not found")]
-    ProviderNotFound,
 }
 
 #[derive(Debug, Error)]
 pub enum FindRefreshTokenError {
     #[error("Token with serial number {serial_number} not found")]
-    NotFound { serial_number: SerialNumber },
+    NotFound { serial_number: String },
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteRefreshTokenError {
+    #[error(transparent)]
+    NotFound(#[from] FindRefreshTokenError),
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ListRefreshTokensError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// Filters and pagination applied when listing the tokens of a tenant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListRefreshTokensQuery {
+    pub provider: Option<ProviderType>,
+    pub serial_number: Option<SerialNumber>,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl ListRefreshTokensQuery {
+    pub const DEFAULT_LIMIT: u32 = 20;
+    pub const MAX_LIMIT: u32 = 100;
+
+    /// Builds a query, clamping `limit` to [ListRefreshTokensQuery::MAX_LIMIT].
+    pub fn new(
+        provider: Option<ProviderType>,
+        serial_number: Option<SerialNumber>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Self {
+        Self {
+            provider,
+            serial_number,
+            limit: limit
+                .unwrap_or(Self::DEFAULT_LIMIT)
+                .clamp(1, Self::MAX_LIMIT),
+            offset: offset.unwrap_or(0),
+        }
+    }
+}
+
+/// A page of tokens along with the number of tokens matching the query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshTokenPage {
+    pub items: Vec<RefreshToken>,
+    pub total: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateRefreshTokenError {
+    #[error(transparent)]
+    NotFound(#[from] FindRefreshTokenError),
+    #[error("Token provider not found")]
+    ProviderNotFound,
+    #[error(transparent)]
+    LockedOut(#[from] LoginLockedOutError),
+    #[error(transparent)]
+    Provider(#[from] CreateTokensError),
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Duration, OffsetDateTime};
+
+    use super::{CreateRefreshTokenRequest, RefreshToken, RefreshTokenRow, TokenHealth};
+    use crate::domain::token::{
+        models::token::{Password, SerialNumber, Token, Username},
+        ports::provider_token_service::ProviderType,
+    };
+
+    fn refresh_token(expires_at: Option<OffsetDateTime>) -> RefreshToken {
+        let now = OffsetDateTime::now_utc();
+
+        RefreshToken::new(
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+            ProviderType::BambuLab,
+            SerialNumber::new("01S00C123456789").unwrap(),
+            Token::new("secret").unwrap(),
+            now,
+            now,
+        )
+        .with_expires_at(expires_at)
+    }
+
+    #[test]
+    fn test_health_is_derived_from_expiry() {
+        let now = OffsetDateTime::now_utc();
+
+        assert_eq!(refresh_token(None).health(now), TokenHealth::Unknown);
+        assert_eq!(
+            refresh_token(Some(now - Duration::hours(1))).health(now),
+            TokenHealth::Expired
+        );
+        assert_eq!(
+            refresh_token(Some(now + Duration::days(2))).health(now),
+            TokenHealth::ExpiringSoon
+        );
+        assert_eq!(
+            refresh_token(Some(now + Duration::days(60))).health(now),
+            TokenHealth::Healthy
+        );
+    }
+
+    #[test]
+    fn test_debug_output_contains_no_secret() {
+        let now = OffsetDateTime::now_utc();
+        let refresh_token =
+            refresh_token(None).with_access_token(Some(Token::new("access-secret").unwrap()));
+        let request = CreateRefreshTokenRequest::new(
+            Username::new("user@example.com").unwrap(),
+            Password::new("password-secret").unwrap(),
+            SerialNumber::new("01S00C123456789").unwrap(),
+        );
+        let row = RefreshTokenRow {
+            id: uuid::Uuid::new_v4(),
+            tenant_id: uuid::Uuid::new_v4(),
+            provider: "bambulab".to_string(),
+            serial_number: "01S00C123456789".to_string(),
+            token: "row-secret".to_string(),
+            access_token: Some("row-access-secret".to_string()),
+            created_at: now,
+            updated_at: now,
+            expires_at: None,
+        };
+
+        let formatted = format!("{:?} {:#?} {:?}", refresh_token, request, row);
+
+        for secret in ["secret", "password-secret", "access-secret", "row-secret"] {
+            assert!(!formatted.contains(secret), "{} leaked", secret);
+        }
+        assert!(formatted.contains("user@example.com"));
+    }
 }
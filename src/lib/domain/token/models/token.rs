@@ -1,24 +1,61 @@
 use std::fmt::Display;
 use thiserror::Error;
+use time::OffsetDateTime;
 
+use crate::domain::secret::Secret;
+
+/// Serial number of a Bambu Lab printer: 15 uppercase letters and digits, the
+/// first three identifying the printer model.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SerialNumber(String);
 
 #[derive(Clone, Debug, Error)]
-#[error("Serial number cannot be empty")]
-pub struct SerialNumberEmptyError;
+pub enum InvalidSerialNumberError {
+    #[error("Serial number cannot be empty")]
+    Empty,
+    #[error("Serial number must be {} characters long", SerialNumber::LENGTH)]
+    Length,
+    #[error("Serial number may only contain uppercase letters and digits")]
+    Charset,
+    #[error("Serial number does not belong to a known printer model")]
+    UnknownModel,
+}
 
 impl SerialNumber {
-    pub fn new(value: &str) -> Result<SerialNumber, SerialNumberEmptyError> {
+    pub const LENGTH: usize = 15;
+
+    /// Prefixes of the X1 Carbon, X1, X1E, P1P, P1S, A1 mini, A1 and H2D.
+    const MODEL_PREFIXES: [&'static str; 8] =
+        ["00M", "00W", "03W", "01S", "01P", "030", "039", "094"];
+
+    pub fn new(value: &str) -> Result<SerialNumber, InvalidSerialNumberError> {
         let trimmed = value.trim();
 
         if trimmed.is_empty() {
-            Err(SerialNumberEmptyError)
+            Err(InvalidSerialNumberError::Empty)
+        } else if trimmed.len() != Self::LENGTH {
+            Err(InvalidSerialNumberError::Length)
+        } else if !trimmed
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        {
+            Err(InvalidSerialNumberError::Charset)
+        } else if !Self::MODEL_PREFIXES
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+        {
+            Err(InvalidSerialNumberError::UnknownModel)
         } else {
             Ok(Self(trimmed.to_string()))
         }
     }
 
+    /// Wraps a serial number that was validated before being stored, so that
+    /// tightening the rules never makes existing tokens unreadable.
+    pub(crate) fn from_stored(value: &str) -> Self {
+        Self(value.to_string())
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
@@ -30,8 +67,78 @@ impl Display for SerialNumber {
     }
 }
 
+/// Account of the provider, identified by its email address.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Token(String);
+pub struct Username(String);
+
+#[derive(Clone, Debug, Error)]
+pub enum InvalidUsernameError {
+    #[error("Username cannot be empty")]
+    Empty,
+    #[error("Username must be the email address of the account")]
+    NotAnEmail,
+}
+
+impl Username {
+    pub fn new(value: &str) -> Result<Username, InvalidUsernameError> {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return Err(InvalidUsernameError::Empty);
+        }
+
+        let is_email = match trimmed.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain.split('.').count() >= 2
+                    && domain.split('.').all(|label| !label.is_empty())
+                    && !trimmed.chars().any(char::is_whitespace)
+            }
+            None => false,
+        };
+
+        if is_email {
+            Ok(Self(trimmed.to_string()))
+        } else {
+            Err(InvalidUsernameError::NotAnEmail)
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Password of a provider account, kept verbatim.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Password(Secret);
+
+#[derive(Clone, Debug, Error)]
+#[error("Password cannot be empty")]
+pub struct PasswordEmptyError;
+
+impl Password {
+    pub fn new(value: &str) -> Result<Password, PasswordEmptyError> {
+        if value.trim().is_empty() {
+            Err(PasswordEmptyError)
+        } else {
+            Ok(Self(Secret::new(value)))
+        }
+    }
+
+    pub fn expose(&self) -> &str {
+        self.0.expose()
+    }
+}
+
+impl AsRef<Secret> for Password {
+    fn as_ref(&self) -> &Secret {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Token(Secret);
 
 #[derive(Clone, Debug, Error)]
 #[error("Token cannot be empty")]
@@ -44,11 +151,17 @@ impl Token {
         if trimmed.is_empty() {
             Err(TokenEmptyError)
         } else {
-            Ok(Self(trimmed.to_string()))
+            Ok(Self(Secret::new(trimmed)))
         }
     }
 
-    pub fn as_str(&self) -> &str {
+    pub fn expose(&self) -> &str {
+        self.0.expose()
+    }
+}
+
+impl AsRef<Secret> for Token {
+    fn as_ref(&self) -> &Secret {
         &self.0
     }
 }
@@ -57,14 +170,89 @@ impl Token {
 pub struct Tokens {
     pub access_token: Token,
     pub refresh_token: Token,
+    /// Expiry of the refresh token, when the provider discloses it.
+    pub refresh_token_expires_at: Option<OffsetDateTime>,
 }
 
-#[derive(Debug, Error)]
+/// Failures of a provider when issuing, renewing or revoking tokens.
+#[derive(Debug, Clone, Error)]
 pub enum CreateTokensError {
-    #[error("The provider was not found")]
-    ProviderNotFound,
-    #[error("The provider returned an error")]
-    ProviderError,
+    #[error("The provider rejected the credentials")]
+    InvalidCredentials,
+    #[error("The provider requires the account to complete a verification step")]
+    VerificationRequired,
+    #[error("The provider is rate limiting requests")]
+    RateLimited,
+    #[error("The provider is unavailable: {0}")]
+    Unavailable(String),
+    #[error("The provider returned an unexpected response: {0}")]
+    ProviderError(String),
+    /// The provider no longer accepts the stored refresh token.
     #[error("The token is invalid")]
     InvalidToken,
 }
+
+impl CreateTokensError {
+    /// Short and stable name of the failure, used as a metric label.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            CreateTokensError::InvalidCredentials => "invalid_credentials",
+            CreateTokensError::VerificationRequired => "verification_required",
+            CreateTokensError::RateLimited => "rate_limited",
+            CreateTokensError::Unavailable(_) => "unavailable",
+            CreateTokensError::ProviderError(_) => "provider_error",
+            CreateTokensError::InvalidToken => "invalid_token",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InvalidSerialNumberError, InvalidUsernameError, SerialNumber, Username};
+
+    #[test]
+    fn test_serial_number_requires_the_bambu_format() {
+        assert!(SerialNumber::new(" 01S00C123456789 ").is_ok());
+        assert!(matches!(
+            SerialNumber::new(""),
+            Err(InvalidSerialNumberError::Empty)
+        ));
+        assert!(matches!(
+            SerialNumber::new("01S00C1234"),
+            Err(InvalidSerialNumberError::Length)
+        ));
+        assert!(matches!(
+            SerialNumber::new("01s00c123456789"),
+            Err(InvalidSerialNumberError::Charset)
+        ));
+        assert!(matches!(
+            SerialNumber::new("ZZZ00C123456789"),
+            Err(InvalidSerialNumberError::UnknownModel)
+        ));
+    }
+
+    #[test]
+    fn test_username_must_be_an_email_address() {
+        assert!(Username::new("maker@example.com").is_ok());
+        assert!(matches!(
+            Username::new(" "),
+            Err(InvalidUsernameError::Empty)
+        ));
+        for invalid in [
+            "maker",
+            "maker@",
+            "@example.com",
+            "maker@example",
+            "ma ker@example.com",
+        ] {
+            assert!(
+                matches!(
+                    Username::new(invalid),
+                    Err(InvalidUsernameError::NotAnEmail)
+                ),
+                "{} should be rejected",
+                invalid
+            );
+        }
+    }
+}
@@ -1,19 +1,74 @@
-use std::future::Future;
+use std::{fmt::Display, future::Future, str::FromStr};
 
-use crate::domain::token::models::token::{CreateTokensError, Tokens};
+use thiserror::Error;
 
-#[derive(Hash, Eq, PartialEq, Debug, Clone)]
+use crate::domain::{
+    health::models::health::CircuitState,
+    token::models::token::{CreateTokensError, Password, Token, Tokens},
+};
+
+#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Debug, Clone)]
 pub enum ProviderType {
     BambuLab,
 }
+
+#[derive(Clone, Debug, Error)]
+#[error("Unknown provider: {0}")]
+pub struct UnknownProviderError(String);
+
+impl ProviderType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderType::BambuLab => "bambulab",
+        }
+    }
+}
+
+impl FromStr for ProviderType {
+    type Err = UnknownProviderError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "bambulab" => Ok(ProviderType::BambuLab),
+            _ => Err(UnknownProviderError(value.to_string())),
+        }
+    }
+}
+
+impl Display for ProviderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
 pub trait ProviderTokenService: Send + Sync + Clone + 'static {
     fn authenticate(
         &self,
         username: String,
-        password: String,
+        password: Password,
     ) -> impl Future<Output = Result<Tokens, CreateTokensError>> + Send;
     fn renew_tokens(
         &self,
-        refresh_token: String,
+        refresh_token: &Token,
     ) -> impl Future<Output = Result<Tokens, CreateTokensError>> + Send;
+    /// Revokes the refresh token at the provider, so it cannot be used once
+    /// deleted from the service. Providers without a revocation endpoint keep
+    /// this default, which does nothing.
+    fn revoke_tokens(
+        &self,
+        _refresh_token: &Token,
+    ) -> impl Future<Output = Result<(), CreateTokensError>> + Send {
+        async { Ok(()) }
+    }
+    /// Asynchronously checks that the provider can be reached, without
+    /// authenticating nor affecting the circuit breaker of the calls made on
+    /// behalf of tenants. Providers without an endpoint cheap enough to probe
+    /// keep this default, which reports them as reachable.
+    fn check_reachability(&self) -> impl Future<Output = Result<(), CreateTokensError>> + Send {
+        async { Ok(()) }
+    }
+    /// State of the circuit breaker guarding the calls to the provider, if
+    /// they go through one.
+    fn circuit_state(&self) -> Option<CircuitState> {
+        None
+    }
 }
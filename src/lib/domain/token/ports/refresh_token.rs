@@ -1,39 +1,122 @@
 use std::future::Future;
 
-use crate::domain::token::models::refresh_token::{
-    CreateRefreshTokenError, FindRefreshTokenError, RefreshToken,
+use crate::domain::token::models::{
+    refresh_token::{
+        CreateRefreshTokenError, CreateRefreshTokenOptions, CreatedRefreshToken,
+        DeleteRefreshTokenError, FindRefreshTokenError, ListRefreshTokensError,
+        ListRefreshTokensQuery, RefreshToken, RefreshTokenPage, UpdateRefreshTokenError,
+    },
+    token::{Password, SerialNumber, Tokens},
 };
 
 use super::provider_token_service::ProviderType;
 
 pub trait RefreshTokenService: Clone + Send + Sync + 'static {
-    /// Asynchronously creates a new [RefreshToken].
+    /// Asynchronously creates a new [RefreshToken], or replaces the existing one
+    /// when `options` allow it.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [CreateRefreshTokenError::LockedOut] without calling the provider while the account is locked out after repeated rejected logins.
     fn create_refresh_token(
         &self,
+        tenant_id: &uuid::Uuid,
         username: String,
-        password: String,
-        serial_number: &str,
+        password: Password,
+        serial_number: &SerialNumber,
         provider_type: ProviderType,
-    ) -> impl Future<Output = Result<RefreshToken, CreateRefreshTokenError>> + Send;
+        options: &CreateRefreshTokenOptions,
+    ) -> impl Future<Output = Result<CreatedRefreshToken, CreateRefreshTokenError>> + Send;
     fn find_by_serial_number(
         &self,
+        tenant_id: &uuid::Uuid,
         serial_number: &str,
     ) -> impl Future<Output = Result<RefreshToken, FindRefreshTokenError>> + Send;
+    fn list_refresh_tokens(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListRefreshTokensQuery,
+    ) -> impl Future<Output = Result<RefreshTokenPage, ListRefreshTokensError>> + Send;
+    /// Asynchronously logs in again with new credentials, replacing the stored
+    /// tokens of an existing [RefreshToken].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [UpdateRefreshTokenError::LockedOut] without calling the provider while the account is locked out after repeated rejected logins.
+    fn update_credentials(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+        username: String,
+        password: Password,
+    ) -> impl Future<Output = Result<RefreshToken, UpdateRefreshTokenError>> + Send;
+    /// Asynchronously deletes a [RefreshToken], revoking it at its provider first
+    /// when the provider supports it.
+    fn delete_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> impl Future<Output = Result<RefreshToken, DeleteRefreshTokenError>> + Send;
+    /// Asynchronously exchanges the stored refresh token for a fresh pair of
+    /// tokens at its provider and persists them.
+    fn renew_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> impl Future<Output = Result<RefreshToken, UpdateRefreshTokenError>> + Send;
 }
 
 pub trait RefreshTokenRepository: Send + Sync + Clone + 'static {
-    /// Asynchronously creates a new [RefreshToken].
+    /// Asynchronously creates a new [RefreshToken]. When the tenant already owns a
+    /// token with the same [SerialNumber], it is replaced atomically if `options`
+    /// ask for [CreateMode::Replace] or carry the [IdempotencyKey] it was created with.
     ///
     /// # Errors
     ///
-    /// - MUST return [CreateRefreshTokenError::Duplicate] if a token with the same [SerialNumber] already exists.
+    /// - MUST return [CreateRefreshTokenError::Duplicate] if the tenant already owns a token with the same [SerialNumber] that may not be replaced.
     fn create_refresh_token(
         &self,
-        token: &str,
-        serial_number: &str,
-    ) -> impl Future<Output = Result<RefreshToken, CreateRefreshTokenError>> + Send;
+        tenant_id: &uuid::Uuid,
+        provider_type: &ProviderType,
+        tokens: &Tokens,
+        serial_number: &SerialNumber,
+        options: &CreateRefreshTokenOptions,
+    ) -> impl Future<Output = Result<CreatedRefreshToken, CreateRefreshTokenError>> + Send;
+    /// Asynchronously finds the [RefreshToken] of a printer owned by the given tenant.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [FindRefreshTokenError::NotFound] if the token belongs to another tenant.
     fn find_by_serial_number(
         &self,
+        tenant_id: &uuid::Uuid,
         serial_number: &str,
     ) -> impl Future<Output = Result<RefreshToken, FindRefreshTokenError>> + Send;
+    /// Asynchronously replaces the provider tokens of an existing [RefreshToken].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [UpdateRefreshTokenError::NotFound] if the token belongs to another tenant.
+    fn update_tokens(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+        tokens: &Tokens,
+    ) -> impl Future<Output = Result<RefreshToken, UpdateRefreshTokenError>> + Send;
+    /// Asynchronously lists the tokens of a tenant, ordered by serial number.
+    fn list_refresh_tokens(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListRefreshTokensQuery,
+    ) -> impl Future<Output = Result<RefreshTokenPage, ListRefreshTokensError>> + Send;
+    /// Asynchronously deletes a [RefreshToken].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [DeleteRefreshTokenError::NotFound] if the token belongs to another tenant.
+    fn delete_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> impl Future<Output = Result<RefreshToken, DeleteRefreshTokenError>> + Send;
 }
@@ -1,15 +1,61 @@
 use std::sync::Arc;
 
+use tracing::{info_span, warn, Instrument, Span};
+
 use crate::application::providers::token_provider_manager::TokenProviderManager;
 
 use super::{
-    models::refresh_token::{CreateRefreshTokenError, FindRefreshTokenError, RefreshToken},
+    models::{
+        login_attempts::{LockoutPolicy, LoginAttempts},
+        refresh_token::{
+            CreateRefreshTokenError, CreateRefreshTokenOptions, CreatedRefreshToken,
+            DeleteRefreshTokenError, FindRefreshTokenError, ListRefreshTokensError,
+            ListRefreshTokensQuery, RefreshToken, RefreshTokenPage, UpdateRefreshTokenError,
+        },
+        token::{CreateTokensError, Password, SerialNumber},
+    },
     ports::{
         provider_token_service::{ProviderTokenService, ProviderType},
         refresh_token::{RefreshTokenRepository, RefreshTokenService},
     },
 };
 
+/// Counts a call to a provider under `provider_calls_total`, labelled with
+/// the reason of its failure if any.
+fn record_provider_call<T>(
+    provider_type: &ProviderType,
+    operation: &'static str,
+    result: &Result<T, CreateTokensError>,
+) {
+    let outcome = match result {
+        Ok(_) => "success",
+        Err(e) => e.reason(),
+    };
+
+    metrics::counter!(
+        "provider_calls_total",
+        "provider" => provider_type.as_str(),
+        "operation" => operation,
+        "outcome" => outcome
+    )
+    .increment(1);
+}
+
+/// Span of a call to a provider, recording which printer it is made for but
+/// never the credentials or tokens sent.
+fn provider_span(
+    provider_type: &ProviderType,
+    operation: &'static str,
+    serial_number: &str,
+) -> Span {
+    info_span!(
+        "provider_call",
+        provider = %provider_type,
+        operation,
+        serial_number
+    )
+}
+
 #[derive(Debug, Clone)]
 pub struct RefreshTokenServiceImpl<R, P>
 where
@@ -18,6 +64,7 @@ where
 {
     refresh_token_repository: R,
     token_provider_manager: Arc<TokenProviderManager<P>>,
+    login_attempts: Arc<LoginAttempts>,
 }
 
 impl<R, P> RefreshTokenServiceImpl<R, P>
@@ -28,10 +75,12 @@ where
     pub fn new(
         refresh_token_repository: R,
         token_provider_manager: Arc<TokenProviderManager<P>>,
+        lockout_policy: LockoutPolicy,
     ) -> Self {
         Self {
             refresh_token_repository,
             token_provider_manager,
+            login_attempts: Arc::new(LoginAttempts::new(lockout_policy)),
         }
     }
 }
@@ -43,28 +92,178 @@ where
 {
     async fn create_refresh_token(
         &self,
+        tenant_id: &uuid::Uuid,
         username: String,
-        password: String,
-        serial_number: &str,
+        password: Password,
+        serial_number: &SerialNumber,
         provider_type: ProviderType,
-    ) -> Result<RefreshToken, CreateRefreshTokenError> {
+        options: &CreateRefreshTokenOptions,
+    ) -> Result<CreatedRefreshToken, CreateRefreshTokenError> {
         let provider = self
             .token_provider_manager
             .get_provider(&provider_type)
             .ok_or(CreateRefreshTokenError::ProviderNotFound)?;
+        self.login_attempts.check(&provider_type, &username)?;
+
+        let tokens = provider
+            .authenticate(username.clone(), password)
+            .instrument(provider_span(
+                &provider_type,
+                "authenticate",
+                serial_number.as_str(),
+            ))
+            .await;
+        record_provider_call(&provider_type, "authenticate", &tokens);
+        self.login_attempts
+            .record(&provider_type, &username, &tokens);
 
-        let tokens = provider.authenticate(username, password).await.unwrap();
         self.refresh_token_repository
-            .create_refresh_token(tokens.refresh_token.as_str(), serial_number)
+            .create_refresh_token(tenant_id, &provider_type, &tokens?, serial_number, options)
             .await
     }
 
     async fn find_by_serial_number(
         &self,
+        tenant_id: &uuid::Uuid,
         serial_number: &str,
     ) -> Result<RefreshToken, FindRefreshTokenError> {
         self.refresh_token_repository
-            .find_by_serial_number(serial_number)
+            .find_by_serial_number(tenant_id, serial_number)
+            .await
+    }
+
+    async fn list_refresh_tokens(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListRefreshTokensQuery,
+    ) -> Result<RefreshTokenPage, ListRefreshTokensError> {
+        self.refresh_token_repository
+            .list_refresh_tokens(tenant_id, query)
+            .await
+    }
+
+    async fn update_credentials(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+        username: String,
+        password: Password,
+    ) -> Result<RefreshToken, UpdateRefreshTokenError> {
+        let refresh_token = self
+            .refresh_token_repository
+            .find_by_serial_number(tenant_id, serial_number)
+            .await?;
+
+        let provider = self
+            .token_provider_manager
+            .get_provider(&refresh_token.provider)
+            .ok_or(UpdateRefreshTokenError::ProviderNotFound)?;
+        self.login_attempts
+            .check(&refresh_token.provider, &username)?;
+
+        let tokens = provider
+            .authenticate(username.clone(), password)
+            .instrument(provider_span(
+                &refresh_token.provider,
+                "authenticate",
+                serial_number,
+            ))
+            .await;
+        record_provider_call(&refresh_token.provider, "authenticate", &tokens);
+        self.login_attempts
+            .record(&refresh_token.provider, &username, &tokens);
+        let tokens = tokens?;
+
+        self.refresh_token_repository
+            .update_tokens(tenant_id, serial_number, &tokens)
+            .await
+    }
+
+    async fn delete_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> Result<RefreshToken, DeleteRefreshTokenError> {
+        let refresh_token = self
+            .refresh_token_repository
+            .find_by_serial_number(tenant_id, serial_number)
+            .await?;
+
+        // Revocation is best effort: a provider outage must not prevent the
+        // tenant from removing the token from the service.
+        if let Some(provider) = self
+            .token_provider_manager
+            .get_provider(&refresh_token.provider)
+        {
+            let revoked = provider
+                .revoke_tokens(&refresh_token.token)
+                .instrument(provider_span(
+                    &refresh_token.provider,
+                    "revoke",
+                    serial_number,
+                ))
+                .await;
+            record_provider_call(&refresh_token.provider, "revoke", &revoked);
+
+            if let Err(e) = revoked {
+                warn!(
+                    "Failed to revoke the token of serial_number {} at {}: {}",
+                    serial_number, refresh_token.provider, e
+                );
+            }
+        }
+
+        self.refresh_token_repository
+            .delete_refresh_token(tenant_id, serial_number)
             .await
     }
+
+    async fn renew_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> Result<RefreshToken, UpdateRefreshTokenError> {
+        let refresh_token = self
+            .refresh_token_repository
+            .find_by_serial_number(tenant_id, serial_number)
+            .await?;
+
+        let provider = self
+            .token_provider_manager
+            .get_provider(&refresh_token.provider)
+            .ok_or(UpdateRefreshTokenError::ProviderNotFound)?;
+
+        let tokens = provider
+            .renew_tokens(&refresh_token.token)
+            .instrument(provider_span(
+                &refresh_token.provider,
+                "renew",
+                serial_number,
+            ))
+            .await;
+        record_provider_call(&refresh_token.provider, "renew", &tokens);
+
+        let renewed = match tokens {
+            Ok(tokens) => {
+                self.refresh_token_repository
+                    .update_tokens(tenant_id, serial_number, &tokens)
+                    .await
+            }
+            Err(e) => Err(e.into()),
+        };
+
+        let outcome = match &renewed {
+            Ok(_) => "renewed",
+            Err(UpdateRefreshTokenError::Provider(e)) => e.reason(),
+            Err(_) => "storage_error",
+        };
+        metrics::counter!(
+            "token_renewals_total",
+            "provider" => refresh_token.provider.as_str(),
+            "outcome" => outcome
+        )
+        .increment(1);
+
+        renewed
+    }
 }
@@ -1,10 +1,225 @@
-use clap::Parser;
+use std::path::PathBuf;
 
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+
+use crate::{
+    application::http::ClientAuth, domain::token::ports::provider_token_service::ProviderType,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per line, carrying the fields of the enclosing spans
+    /// and thus the trace id of the request.
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Manages the migrations of the database instead of serving.
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateCommand,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum MigrateCommand {
+    /// Applies the pending migrations.
+    Up,
+    /// Reverts the latest migration, or those applied after `--target`.
+    Down {
+        #[arg(long)]
+        target: Option<i64>,
+    },
+    /// Lists the migrations and whether they are applied.
+    Status,
+}
+
+/// Settings read from the environment. Each of them overrides its
+/// counterpart in the configuration file, see [crate::config::Config], which
+/// also holds the defaults.
 #[derive(Debug, Clone, Default, Parser)]
 pub struct Env {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// TOML file holding the settings not given in the environment, by
+    /// default `ferrisprinter.toml` in the working directory when it exists.
+    #[clap(env)]
+    pub config_file: Option<PathBuf>,
+
+    /// Runs without a database nor a provider account: the tenants and the
+    /// tokens are kept in memory, and fake providers issue tokens for any
+    /// credentials.
+    #[clap(long, env)]
+    pub demo: bool,
+
+    /// Defaults to `memory:` in [Env::demo] mode.
+    #[clap(env)]
+    pub database_url: Option<String>,
+
+    #[clap(env)]
+    pub database_max_connections: Option<u32>,
+
+    #[clap(env)]
+    pub database_min_connections: Option<u32>,
+
+    /// How long a query may wait for a connection of the pool.
+    #[clap(env)]
+    pub database_acquire_timeout_ms: Option<u64>,
+
+    /// Applies the pending migrations on startup, holding an advisory lock so
+    /// that replicas starting together do not race.
+    #[clap(env)]
+    pub database_run_migrations: Option<bool>,
+
+    /// Address to listen on, such as `127.0.0.1`, `::` or
+    /// `unix:/run/ferrisprinter.sock`.
+    #[clap(env)]
+    pub bind_address: Option<String>,
+
+    /// Port to listen on, unless on a Unix socket.
+    #[clap(env)]
+    pub port: Option<u16>,
+
+    /// PEM certificate chain served over HTTPS, along with `TLS_KEY_FILE`.
+    /// Both are reloaded when they change.
+    #[clap(env)]
+    pub tls_cert_file: Option<PathBuf>,
+
+    #[clap(env)]
+    pub tls_key_file: Option<PathBuf>,
+
+    /// PEM bundle of the CAs issuing the client certificates of the callers,
+    /// which are then verified.
+    #[clap(env)]
+    pub tls_client_ca_file: Option<PathBuf>,
+
+    #[clap(env, value_enum)]
+    pub tls_client_auth: Option<ClientAuth>,
+
+    /// Operator key required by the tenant management endpoints, which are
+    /// disabled when it is not set.
+    #[clap(env)]
+    pub admin_api_key: Option<String>,
+
+    /// How long each dependency gets to answer a health check.
+    #[clap(env)]
+    pub health_check_timeout_ms: Option<u64>,
+
+    /// How long in-flight requests may take to complete once a shutdown is
+    /// requested, after which their connections are closed.
+    #[clap(env)]
+    pub shutdown_timeout_ms: Option<u64>,
+
+    /// Logins to a provider allowed per API key in each rate limit window.
     #[clap(env)]
-    pub database_url: String,
+    pub login_rate_limit_per_api_key: Option<u32>,
 
+    /// Logins to a provider allowed per client IP in each rate limit window.
     #[clap(env)]
-    pub port: String,
+    pub login_rate_limit_per_client_ip: Option<u32>,
+
+    #[clap(env)]
+    pub login_rate_limit_window_secs: Option<u64>,
+
+    /// Logins the provider may reject in a row before the account is locked
+    /// out for `LOGIN_LOCKOUT_SECS`.
+    #[clap(env)]
+    pub login_max_failures: Option<u32>,
+
+    #[clap(env)]
+    pub login_lockout_secs: Option<u64>,
+
+    /// Days the audit events are kept for before being deleted.
+    #[clap(env)]
+    pub audit_retention_days: Option<u64>,
+
+    /// Comma-separated providers to enable, all of them by default.
+    #[clap(long, env, value_delimiter = ',')]
+    pub enabled_providers: Option<Vec<ProviderType>>,
+
+    #[clap(env)]
+    pub bambulab_api_url: Option<String>,
+
+    #[clap(env)]
+    pub bambulab_login_url: Option<String>,
+
+    /// User agent of the calls made to every provider.
+    #[clap(env)]
+    pub provider_user_agent: Option<String>,
+
+    /// Proxy through which every provider is called, such as
+    /// `http://proxy.internal:3128`.
+    #[clap(env)]
+    pub provider_http_proxy: Option<String>,
+
+    /// PEM bundle of certificates trusted in addition to the system ones when
+    /// calling the providers.
+    #[clap(env)]
+    pub provider_ca_bundle: Option<PathBuf>,
+
+    #[clap(env)]
+    pub provider_connect_timeout_ms: Option<u64>,
+
+    #[clap(env)]
+    pub provider_read_timeout_ms: Option<u64>,
+
+    #[clap(env)]
+    pub provider_max_retries: Option<u32>,
+
+    /// Minimum level of the logs, or filtering directives such as
+    /// `info,ferrisprinter=debug`.
+    #[clap(env)]
+    pub log_level: Option<String>,
+
+    #[clap(env, value_enum)]
+    pub log_format: Option<LogFormat>,
+
+    /// Base URL of an OTLP/HTTP collector, such as `http://localhost:4318`.
+    /// Traces are not exported when it is not set.
+    #[clap(env)]
+    pub otel_exporter_otlp_endpoint: Option<String>,
+
+    #[clap(env)]
+    pub otel_service_name: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, Parser};
+
+    use super::{Command, Env, MigrateCommand};
+
+    #[test]
+    fn test_arguments_are_consistent() {
+        Env::command().debug_assert();
+    }
+
+    #[test]
+    fn test_migrate_subcommand_is_parsed() {
+        let env = Env::try_parse_from(["ferrisprinter_server", "migrate", "down", "--target", "3"])
+            .unwrap();
+        assert_eq!(
+            env.command,
+            Some(Command::Migrate {
+                action: MigrateCommand::Down { target: Some(3) }
+            })
+        );
+
+        let env = Env::try_parse_from(["ferrisprinter_server", "migrate", "status"]).unwrap();
+        assert_eq!(
+            env.command,
+            Some(Command::Migrate {
+                action: MigrateCommand::Status
+            })
+        );
+
+        let env = Env::try_parse_from(["ferrisprinter_server"]).unwrap();
+        assert_eq!(env.command, None);
+    }
 }
@@ -1,2 +1,8 @@
+pub mod audit;
+#[cfg(test)]
+mod conformance;
 pub mod db;
+pub mod proxy;
+pub mod resilience;
+pub mod tenant;
 pub mod token;
@@ -0,0 +1,73 @@
+use std::{cmp::Reverse, sync::Arc};
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::{
+    domain::audit::{
+        models::audit::{
+            AuditEvent, AuditEventPage, ListAuditEventsError, ListAuditEventsQuery,
+            PurgeAuditEventsError, RecordAuditEventError,
+        },
+        ports::audit::AuditLog,
+    },
+    infrastructure::db::memory::InMemory,
+};
+
+#[derive(Debug, Clone)]
+pub struct InMemoryAuditLog {
+    memory: Arc<InMemory>,
+}
+
+impl InMemoryAuditLog {
+    pub fn new(memory: Arc<InMemory>) -> Self {
+        Self { memory }
+    }
+}
+
+impl AuditLog for InMemoryAuditLog {
+    #[instrument(name = "audit_events.record", skip_all, fields(db.system = "memory", tenant_id = %event.tenant_id, action = %event.action))]
+    async fn record(&self, event: &AuditEvent) -> Result<(), RecordAuditEventError> {
+        self.memory.tables().audit_events.push(event.clone());
+
+        Ok(())
+    }
+
+    #[instrument(name = "audit_events.list", skip_all, fields(db.system = "memory", tenant_id = %tenant_id))]
+    async fn list_events(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListAuditEventsQuery,
+    ) -> Result<AuditEventPage, ListAuditEventsError> {
+        let tables = self.memory.tables();
+        let mut matching: Vec<&AuditEvent> = tables
+            .audit_events
+            .iter()
+            .filter(|event| event.tenant_id == *tenant_id && query.matches(event))
+            .collect();
+        matching.sort_by_key(|event| Reverse((event.occurred_at, event.id)));
+
+        Ok(AuditEventPage {
+            total: matching.len() as u64,
+            items: matching
+                .into_iter()
+                .skip(query.offset as usize)
+                .take(query.limit as usize)
+                .cloned()
+                .collect(),
+        })
+    }
+
+    #[instrument(name = "audit_events.purge", skip_all, fields(db.system = "memory"))]
+    async fn purge_before(&self, before: OffsetDateTime) -> Result<u64, PurgeAuditEventsError> {
+        let mut tables = self.memory.tables();
+        let count = tables.audit_events.len();
+        tables
+            .audit_events
+            .retain(|event| event.occurred_at >= before);
+        let purged = (count - tables.audit_events.len()) as u64;
+
+        info!("Deletion of {} audit events older than {}", purged, before);
+
+        Ok(purged)
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::{
+    domain::audit::{
+        models::audit::{
+            AuditEvent, AuditEventPage, AuditEventRow, ListAuditEventsError, ListAuditEventsQuery,
+            PurgeAuditEventsError, RecordAuditEventError,
+        },
+        ports::audit::AuditLog,
+    },
+    infrastructure::db::postgres::Postgres,
+};
+
+#[derive(Debug, Clone)]
+pub struct PostgresAuditLog {
+    postgres: Arc<Postgres>,
+}
+
+impl PostgresAuditLog {
+    pub fn new(postgres: Arc<Postgres>) -> Self {
+        Self { postgres }
+    }
+}
+
+impl AuditLog for PostgresAuditLog {
+    #[instrument(name = "audit_events.record", skip_all, fields(db.system = "postgresql", tenant_id = %event.tenant_id, action = %event.action))]
+    async fn record(&self, event: &AuditEvent) -> Result<(), RecordAuditEventError> {
+        let row = AuditEventRow::from(event);
+
+        sqlx::query!(
+            r#"INSERT INTO audit_events (id, occurred_at, tenant_id, api_key_id, action, outcome, serial_number, token_id, detail, request_id, client_ip)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
+            row.id,
+            row.occurred_at,
+            row.tenant_id,
+            row.api_key_id,
+            row.action,
+            row.outcome,
+            row.serial_number,
+            row.token_id,
+            row.detail,
+            row.request_id,
+            row.client_ip,
+        )
+        .execute(&*self.postgres.get_pool())
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "audit_events.list", skip_all, fields(db.system = "postgresql", tenant_id = %tenant_id))]
+    async fn list_events(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListAuditEventsQuery,
+    ) -> Result<AuditEventPage, ListAuditEventsError> {
+        let action = query.action.map(|action| action.as_str());
+        let outcome = query.outcome.map(|outcome| outcome.as_str());
+
+        let rows = sqlx::query_as!(
+            AuditEventRow,
+            r#"SELECT id, occurred_at, tenant_id, api_key_id, action, outcome, serial_number, token_id, detail, request_id, client_ip FROM audit_events
+               WHERE tenant_id=$1 AND ($2::TEXT IS NULL OR action=$2) AND ($3::TEXT IS NULL OR outcome=$3)
+                 AND ($4::TEXT IS NULL OR serial_number=$4) AND ($5::UUID IS NULL OR api_key_id=$5)
+                 AND ($6::TIMESTAMPTZ IS NULL OR occurred_at>=$6) AND ($7::TIMESTAMPTZ IS NULL OR occurred_at<$7)
+               ORDER BY occurred_at DESC, id DESC LIMIT $8 OFFSET $9"#,
+            tenant_id,
+            action,
+            outcome,
+            query.serial_number,
+            query.api_key_id,
+            query.since,
+            query.until,
+            i64::from(query.limit),
+            i64::from(query.offset),
+        )
+        .fetch_all(&*self.postgres.get_pool())
+        .await?;
+
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "total!" FROM audit_events
+               WHERE tenant_id=$1 AND ($2::TEXT IS NULL OR action=$2) AND ($3::TEXT IS NULL OR outcome=$3)
+                 AND ($4::TEXT IS NULL OR serial_number=$4) AND ($5::UUID IS NULL OR api_key_id=$5)
+                 AND ($6::TIMESTAMPTZ IS NULL OR occurred_at>=$6) AND ($7::TIMESTAMPTZ IS NULL OR occurred_at<$7)"#,
+            tenant_id,
+            action,
+            outcome,
+            query.serial_number,
+            query.api_key_id,
+            query.since,
+            query.until,
+        )
+        .fetch_one(&*self.postgres.get_pool())
+        .await?;
+
+        Ok(AuditEventPage {
+            items: rows.into_iter().map(AuditEvent::from).collect(),
+            total: total as u64,
+        })
+    }
+
+    #[instrument(name = "audit_events.purge", skip_all, fields(db.system = "postgresql"))]
+    async fn purge_before(&self, before: OffsetDateTime) -> Result<u64, PurgeAuditEventsError> {
+        let result = sqlx::query!(r#"DELETE FROM audit_events WHERE occurred_at<$1"#, before)
+            .execute(&*self.postgres.get_pool())
+            .await?;
+
+        info!(
+            "Deletion of {} audit events older than {}",
+            result.rows_affected(),
+            before
+        );
+
+        Ok(result.rows_affected())
+    }
+}
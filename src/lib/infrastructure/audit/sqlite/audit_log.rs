@@ -0,0 +1,128 @@
+use std::sync::Arc;
+use time::{OffsetDateTime, UtcOffset};
+use tracing::{info, instrument};
+
+use crate::{
+    domain::audit::{
+        models::audit::{
+            AuditEvent, AuditEventPage, AuditEventRow, ListAuditEventsError, ListAuditEventsQuery,
+            PurgeAuditEventsError, RecordAuditEventError,
+        },
+        ports::audit::AuditLog,
+    },
+    infrastructure::db::sqlite::Sqlite,
+};
+
+/// The timestamps are RFC 3339 strings, whose fractional seconds are left
+/// out when zero, so they are compared through `julianday` rather than as
+/// text.
+#[derive(Debug, Clone)]
+pub struct SqliteAuditLog {
+    sqlite: Arc<Sqlite>,
+}
+
+impl SqliteAuditLog {
+    pub fn new(sqlite: Arc<Sqlite>) -> Self {
+        Self { sqlite }
+    }
+}
+
+fn utc(timestamp: Option<OffsetDateTime>) -> Option<OffsetDateTime> {
+    timestamp.map(|timestamp| timestamp.to_offset(UtcOffset::UTC))
+}
+
+impl AuditLog for SqliteAuditLog {
+    #[instrument(name = "audit_events.record", skip_all, fields(db.system = "sqlite", tenant_id = %event.tenant_id, action = %event.action))]
+    async fn record(&self, event: &AuditEvent) -> Result<(), RecordAuditEventError> {
+        let row = AuditEventRow::from(event);
+
+        sqlx::query(
+            r#"INSERT INTO audit_events (id, occurred_at, tenant_id, api_key_id, action, outcome, serial_number, token_id, detail, request_id, client_ip)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"#,
+        )
+        .bind(row.id)
+        .bind(row.occurred_at.to_offset(UtcOffset::UTC))
+        .bind(row.tenant_id)
+        .bind(row.api_key_id)
+        .bind(row.action)
+        .bind(row.outcome)
+        .bind(row.serial_number)
+        .bind(row.token_id)
+        .bind(row.detail)
+        .bind(row.request_id)
+        .bind(row.client_ip)
+        .execute(&*self.sqlite.get_pool())
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "audit_events.list", skip_all, fields(db.system = "sqlite", tenant_id = %tenant_id))]
+    async fn list_events(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListAuditEventsQuery,
+    ) -> Result<AuditEventPage, ListAuditEventsError> {
+        let action = query.action.map(|action| action.as_str());
+        let outcome = query.outcome.map(|outcome| outcome.as_str());
+        let since = utc(query.since);
+        let until = utc(query.until);
+
+        let rows = sqlx::query_as::<_, AuditEventRow>(
+            r#"SELECT id, occurred_at, tenant_id, api_key_id, action, outcome, serial_number, token_id, detail, request_id, client_ip FROM audit_events
+               WHERE tenant_id=?1 AND (?2 IS NULL OR action=?2) AND (?3 IS NULL OR outcome=?3)
+                 AND (?4 IS NULL OR serial_number=?4) AND (?5 IS NULL OR api_key_id=?5)
+                 AND (?6 IS NULL OR julianday(occurred_at)>=julianday(?6)) AND (?7 IS NULL OR julianday(occurred_at)<julianday(?7))
+               ORDER BY julianday(occurred_at) DESC, id DESC LIMIT ?8 OFFSET ?9"#,
+        )
+        .bind(tenant_id)
+        .bind(action)
+        .bind(outcome)
+        .bind(&query.serial_number)
+        .bind(query.api_key_id)
+        .bind(since)
+        .bind(until)
+        .bind(i64::from(query.limit))
+        .bind(i64::from(query.offset))
+        .fetch_all(&*self.sqlite.get_pool())
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM audit_events
+               WHERE tenant_id=?1 AND (?2 IS NULL OR action=?2) AND (?3 IS NULL OR outcome=?3)
+                 AND (?4 IS NULL OR serial_number=?4) AND (?5 IS NULL OR api_key_id=?5)
+                 AND (?6 IS NULL OR julianday(occurred_at)>=julianday(?6)) AND (?7 IS NULL OR julianday(occurred_at)<julianday(?7))"#,
+        )
+        .bind(tenant_id)
+        .bind(action)
+        .bind(outcome)
+        .bind(&query.serial_number)
+        .bind(query.api_key_id)
+        .bind(since)
+        .bind(until)
+        .fetch_one(&*self.sqlite.get_pool())
+        .await?;
+
+        Ok(AuditEventPage {
+            items: rows.into_iter().map(AuditEvent::from).collect(),
+            total: total as u64,
+        })
+    }
+
+    #[instrument(name = "audit_events.purge", skip_all, fields(db.system = "sqlite"))]
+    async fn purge_before(&self, before: OffsetDateTime) -> Result<u64, PurgeAuditEventsError> {
+        let result =
+            sqlx::query(r#"DELETE FROM audit_events WHERE julianday(occurred_at)<julianday(?1)"#)
+                .bind(before.to_offset(UtcOffset::UTC))
+                .execute(&*self.sqlite.get_pool())
+                .await?;
+
+        info!(
+            "Deletion of {} audit events older than {}",
+            result.rows_affected(),
+            before
+        );
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,688 @@
+//! Behaviour every implementation of the repository ports must share, run
+//! against each database backend.
+
+use std::sync::Arc;
+
+use crate::{
+    config::DatabaseConfig,
+    domain::{
+        audit::{
+            models::audit::{AuditAction, AuditEvent, AuditOutcome, ListAuditEventsQuery},
+            ports::audit::AuditLog,
+        },
+        secret::Secret,
+        tenant::{
+            models::{
+                api_key::{AuthenticateTenantError, Scope},
+                tenant::{CreateTenantError, FindTenantError},
+            },
+            ports::tenant::TenantRepository,
+        },
+        token::{
+            models::{
+                refresh_token::{
+                    CreateMode, CreateRefreshTokenError, CreateRefreshTokenOptions,
+                    CreatedRefreshToken, DeleteRefreshTokenError, FindRefreshTokenError,
+                    IdempotencyKey, ListRefreshTokensError, ListRefreshTokensQuery,
+                    RefreshTokenPage, UpdateRefreshTokenError,
+                },
+                token::{SerialNumber, Token, Tokens},
+            },
+            ports::{provider_token_service::ProviderType, refresh_token::RefreshTokenRepository},
+        },
+    },
+    infrastructure::{
+        db::{sqlite::Sqlite, DatabaseBackend},
+        tenant::sqlite::tenant_repository::SqliteTenantRepository,
+        token::sqlite::refresh_token_repository::SqliteRefreshTokenRepository,
+    },
+};
+
+const SERIAL_NUMBER: &str = "01S00C123456789";
+
+fn serial(serial_number: &str) -> SerialNumber {
+    SerialNumber::new(serial_number).unwrap()
+}
+
+fn tokens(refresh_token: &str) -> Tokens {
+    Tokens {
+        access_token: Token::new(&format!("access-{}", refresh_token)).unwrap(),
+        refresh_token: Token::new(refresh_token).unwrap(),
+        refresh_token_expires_at: None,
+    }
+}
+
+fn options(mode: CreateMode, idempotency_key: Option<&str>) -> CreateRefreshTokenOptions {
+    CreateRefreshTokenOptions {
+        mode,
+        idempotency_key: idempotency_key.map(|key| IdempotencyKey::new(key).unwrap()),
+    }
+}
+
+async fn create_tenant(tenants: &impl TenantRepository, name: &str) -> uuid::Uuid {
+    tenants
+        .create_tenant(name, &format!("hash-{}", name))
+        .await
+        .unwrap()
+        .id
+}
+
+async fn test_tenants_are_created_found_and_deleted(
+    tenants: impl TenantRepository,
+    _: impl RefreshTokenRepository,
+    _: impl AuditLog,
+) {
+    let makerspace = create_tenant(&tenants, "makerspace").await;
+    let workshop = create_tenant(&tenants, "workshop").await;
+
+    let error = tenants
+        .create_tenant("makerspace", "another-hash")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, CreateTenantError::Duplicate { .. }));
+
+    let tenant = tenants.find_tenant(&makerspace).await.unwrap();
+    assert_eq!(tenant.name.as_str(), "makerspace");
+    let names: Vec<String> = tenants
+        .list_tenants()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|tenant| tenant.name.as_str().to_string())
+        .collect();
+    assert_eq!(names, ["makerspace", "workshop"]);
+
+    tenants.delete_tenant(&workshop).await.unwrap();
+    let error = tenants.find_tenant(&workshop).await.unwrap_err();
+    assert!(matches!(error, FindTenantError::NotFound { id } if id == workshop));
+    let error = tenants.delete_tenant(&workshop).await.unwrap_err();
+    assert!(matches!(error, FindTenantError::NotFound { .. }));
+}
+
+async fn test_api_keys_authenticate_their_tenant(
+    tenants: impl TenantRepository,
+    _: impl RefreshTokenRepository,
+    _: impl AuditLog,
+) {
+    let tenant_id = create_tenant(&tenants, "makerspace").await;
+    let created = tenants
+        .create_api_key(&tenant_id, "admin-hash", &[Scope::Admin])
+        .await
+        .unwrap();
+
+    let (tenant, api_key) = tenants
+        .find_by_api_key_hash("hash-makerspace")
+        .await
+        .unwrap();
+    assert_eq!(tenant.id, tenant_id);
+    assert!(api_key.scopes.is_empty());
+
+    let (tenant, api_key) = tenants.find_by_api_key_hash("admin-hash").await.unwrap();
+    assert_eq!(tenant.id, tenant_id);
+    assert_eq!(api_key.id, created.id);
+    assert_eq!(api_key.scopes, [Scope::Admin]);
+
+    let error = tenants.find_by_api_key_hash("unknown").await.unwrap_err();
+    assert!(matches!(error, AuthenticateTenantError::InvalidApiKey));
+
+    let unknown = uuid::Uuid::new_v4();
+    let error = tenants
+        .create_api_key(&unknown, "orphan-hash", &[])
+        .await
+        .unwrap_err();
+    assert!(matches!(error, FindTenantError::NotFound { id } if id == unknown));
+}
+
+async fn test_refresh_tokens_are_scoped_to_their_tenant(
+    tenants: impl TenantRepository,
+    refresh_tokens: impl RefreshTokenRepository,
+    _: impl AuditLog,
+) {
+    let tenant_id = create_tenant(&tenants, "makerspace").await;
+    let other_tenant_id = create_tenant(&tenants, "workshop").await;
+
+    let created = refresh_tokens
+        .create_refresh_token(
+            &tenant_id,
+            &ProviderType::BambuLab,
+            &tokens("first"),
+            &serial(SERIAL_NUMBER),
+            &CreateRefreshTokenOptions::default(),
+        )
+        .await
+        .unwrap();
+    let CreatedRefreshToken::Created(created) = created else {
+        panic!("expected a created token, got {:?}", created);
+    };
+    assert_eq!(created.tenant_id, tenant_id);
+    assert_eq!(created.provider, ProviderType::BambuLab);
+    assert_eq!(created.serial_number.as_str(), SERIAL_NUMBER);
+    assert_eq!(created.token.expose(), "first");
+    assert_eq!(
+        created.access_token.as_ref().map(Token::expose),
+        Some("access-first")
+    );
+
+    let found = refresh_tokens
+        .find_by_serial_number(&tenant_id, SERIAL_NUMBER)
+        .await
+        .unwrap();
+    assert_eq!(found.id, created.id);
+    assert_eq!(found.token.expose(), "first");
+
+    let error = refresh_tokens
+        .find_by_serial_number(&other_tenant_id, SERIAL_NUMBER)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, FindRefreshTokenError::NotFound { .. }));
+    let error = refresh_tokens
+        .update_tokens(&other_tenant_id, SERIAL_NUMBER, &tokens("stolen"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, UpdateRefreshTokenError::NotFound(_)));
+    let error = refresh_tokens
+        .delete_refresh_token(&other_tenant_id, SERIAL_NUMBER)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, DeleteRefreshTokenError::NotFound(_)));
+
+    // Another tenant may register the same printer.
+    let other = refresh_tokens
+        .create_refresh_token(
+            &other_tenant_id,
+            &ProviderType::BambuLab,
+            &tokens("other"),
+            &serial(SERIAL_NUMBER),
+            &CreateRefreshTokenOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(other, CreatedRefreshToken::Created(_)));
+    assert_ne!(other.refresh_token().id, created.id);
+}
+
+async fn test_duplicate_serial_numbers_are_replaced_only_when_allowed(
+    tenants: impl TenantRepository,
+    refresh_tokens: impl RefreshTokenRepository,
+    _: impl AuditLog,
+) {
+    let tenant_id = create_tenant(&tenants, "makerspace").await;
+    let create = |refresh_token: &'static str, options: CreateRefreshTokenOptions| {
+        let refresh_tokens = refresh_tokens.clone();
+        async move {
+            refresh_tokens
+                .create_refresh_token(
+                    &tenant_id,
+                    &ProviderType::BambuLab,
+                    &tokens(refresh_token),
+                    &serial(SERIAL_NUMBER),
+                    &options,
+                )
+                .await
+        }
+    };
+
+    let created = create("first", options(CreateMode::Reject, Some("retry-1")))
+        .await
+        .unwrap();
+    let id = created.refresh_token().id;
+
+    let error = create("second", options(CreateMode::Reject, None))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, CreateRefreshTokenError::Duplicate { ref name } if name.as_str() == SERIAL_NUMBER)
+    );
+    let error = create("second", options(CreateMode::Reject, Some("retry-2")))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, CreateRefreshTokenError::Duplicate { .. }));
+
+    // The duplicates left the stored token untouched.
+    let found = refresh_tokens
+        .find_by_serial_number(&tenant_id, SERIAL_NUMBER)
+        .await
+        .unwrap();
+    assert_eq!(found.token.expose(), "first");
+
+    // A retry carrying the same key replaces the token.
+    let retried = create("retried", options(CreateMode::Reject, Some("retry-1")))
+        .await
+        .unwrap();
+    let CreatedRefreshToken::Replaced(retried) = retried else {
+        panic!("expected a replaced token, got {:?}", retried);
+    };
+    assert_eq!(retried.id, id);
+    assert_eq!(retried.token.expose(), "retried");
+
+    let replaced = create("replaced", options(CreateMode::Replace, None))
+        .await
+        .unwrap();
+    let CreatedRefreshToken::Replaced(replaced) = replaced else {
+        panic!("expected a replaced token, got {:?}", replaced);
+    };
+    assert_eq!(replaced.id, id);
+    assert_eq!(replaced.created_at, found.created_at);
+    assert_eq!(replaced.token.expose(), "replaced");
+
+    // Replacing without a key keeps the one the token was created with.
+    let retried = create("again", options(CreateMode::Reject, Some("retry-1")))
+        .await
+        .unwrap();
+    assert!(matches!(retried, CreatedRefreshToken::Replaced(_)));
+}
+
+async fn test_refresh_tokens_are_updated_and_deleted(
+    tenants: impl TenantRepository,
+    refresh_tokens: impl RefreshTokenRepository,
+    _: impl AuditLog,
+) {
+    let tenant_id = create_tenant(&tenants, "makerspace").await;
+    let created = refresh_tokens
+        .create_refresh_token(
+            &tenant_id,
+            &ProviderType::BambuLab,
+            &tokens("first"),
+            &serial(SERIAL_NUMBER),
+            &CreateRefreshTokenOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    // 2030-01-01, whole seconds being stored exactly by every backend.
+    let expires_at = time::OffsetDateTime::from_unix_timestamp(1_893_456_000).unwrap();
+    let renewed = refresh_tokens
+        .update_tokens(
+            &tenant_id,
+            SERIAL_NUMBER,
+            &Tokens {
+                refresh_token_expires_at: Some(expires_at),
+                ..tokens("renewed")
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(renewed.id, created.refresh_token().id);
+    assert_eq!(renewed.token.expose(), "renewed");
+    assert_eq!(
+        renewed.access_token.as_ref().map(Token::expose),
+        Some("access-renewed")
+    );
+    assert_eq!(renewed.expires_at, Some(expires_at));
+    assert!(renewed.updated_at >= created.refresh_token().updated_at);
+
+    let deleted = refresh_tokens
+        .delete_refresh_token(&tenant_id, SERIAL_NUMBER)
+        .await
+        .unwrap();
+    assert_eq!(deleted.token.expose(), "renewed");
+    let error = refresh_tokens
+        .find_by_serial_number(&tenant_id, SERIAL_NUMBER)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, FindRefreshTokenError::NotFound { .. }));
+    let error = refresh_tokens
+        .update_tokens(&tenant_id, SERIAL_NUMBER, &tokens("late"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, UpdateRefreshTokenError::NotFound(_)));
+}
+
+async fn test_refresh_tokens_are_listed_by_serial_number(
+    tenants: impl TenantRepository,
+    refresh_tokens: impl RefreshTokenRepository,
+    _: impl AuditLog,
+) {
+    let tenant_id = create_tenant(&tenants, "makerspace").await;
+    let other_tenant_id = create_tenant(&tenants, "workshop").await;
+    for (tenant_id, serial_number) in [
+        (tenant_id, "01S00A000000003"),
+        (tenant_id, "01S00A000000001"),
+        (tenant_id, "01S00A000000002"),
+        (other_tenant_id, "01S00A000000004"),
+    ] {
+        refresh_tokens
+            .create_refresh_token(
+                &tenant_id,
+                &ProviderType::BambuLab,
+                &tokens(serial_number),
+                &serial(serial_number),
+                &CreateRefreshTokenOptions::default(),
+            )
+            .await
+            .unwrap();
+    }
+    let serial_numbers = |page: &RefreshTokenPage| {
+        page.items
+            .iter()
+            .map(|token| token.serial_number.as_str().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let page = refresh_tokens
+        .list_refresh_tokens(
+            &tenant_id,
+            &ListRefreshTokensQuery::new(None, None, None, None),
+        )
+        .await
+        .unwrap();
+    assert_eq!(page.total, 3);
+    assert_eq!(
+        serial_numbers(&page),
+        ["01S00A000000001", "01S00A000000002", "01S00A000000003"]
+    );
+
+    let page = refresh_tokens
+        .list_refresh_tokens(
+            &tenant_id,
+            &ListRefreshTokensQuery::new(None, None, Some(1), Some(1)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(page.total, 3);
+    assert_eq!(serial_numbers(&page), ["01S00A000000002"]);
+
+    let query = ListRefreshTokensQuery::new(
+        Some(ProviderType::BambuLab),
+        Some(SerialNumber::new("01S00A000000003").unwrap()),
+        None,
+        None,
+    );
+    let page = refresh_tokens
+        .list_refresh_tokens(&tenant_id, &query)
+        .await
+        .unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(serial_numbers(&page), ["01S00A000000003"]);
+
+    // Deleting a tenant deletes its tokens.
+    tenants.delete_tenant(&tenant_id).await.unwrap();
+    let page = refresh_tokens
+        .list_refresh_tokens(
+            &tenant_id,
+            &ListRefreshTokensQuery::new(None, None, None, None),
+        )
+        .await
+        .unwrap();
+    assert_eq!(page.total, 0);
+    let page = refresh_tokens
+        .list_refresh_tokens(
+            &other_tenant_id,
+            &ListRefreshTokensQuery::new(None, None, None, None),
+        )
+        .await
+        .unwrap();
+    assert_eq!(serial_numbers(&page), ["01S00A000000004"]);
+}
+
+/// An event of `tenant_id` recorded `seconds` after a fixed instant, some
+/// of them on a fraction of a second.
+fn audit_event(
+    tenant_id: uuid::Uuid,
+    api_key_id: uuid::Uuid,
+    action: AuditAction,
+    seconds: i64,
+) -> AuditEvent {
+    let mut event = AuditEvent::new(tenant_id, api_key_id, action);
+    event.occurred_at = time::OffsetDateTime::from_unix_timestamp(1_731_229_200 + seconds).unwrap()
+        + time::Duration::milliseconds(seconds % 2 * 250);
+    event
+}
+
+async fn test_audit_events_are_recorded_and_filtered(
+    _: impl TenantRepository,
+    _: impl RefreshTokenRepository,
+    audit_log: impl AuditLog,
+) {
+    let tenant_id = uuid::Uuid::new_v4();
+    let other_tenant_id = uuid::Uuid::new_v4();
+    let api_key_id = uuid::Uuid::new_v4();
+    let other_api_key_id = uuid::Uuid::new_v4();
+    let token_id = uuid::Uuid::new_v4();
+
+    let created = audit_event(tenant_id, api_key_id, AuditAction::TokenCreate, 0)
+        .with_serial_number(SERIAL_NUMBER)
+        .with_token_id(Some(token_id))
+        .with_request(
+            Some("request-1".to_string()),
+            Some("192.0.2.1".parse().unwrap()),
+        );
+    let read = audit_event(tenant_id, other_api_key_id, AuditAction::TokenRead, 1)
+        .with_serial_number(SERIAL_NUMBER)
+        .with_token_id(Some(token_id));
+    let denied = audit_event(tenant_id, other_api_key_id, AuditAction::TokenExport, 2)
+        .with_serial_number("01S00C987654321")
+        .with_outcome(AuditOutcome::Denied);
+    let command = audit_event(tenant_id, api_key_id, AuditAction::PrinterCommand, 3)
+        .with_serial_number(SERIAL_NUMBER)
+        .with_detail("POST /v1/iot-service/api/user/print".to_string())
+        .with_outcome(AuditOutcome::Failure);
+    let other = audit_event(other_tenant_id, api_key_id, AuditAction::TokenCreate, 4);
+    for event in [&created, &read, &denied, &command, &other] {
+        audit_log.record(event).await.unwrap();
+    }
+
+    let page = audit_log
+        .list_events(&tenant_id, &ListAuditEventsQuery::new(None, None))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 4);
+    assert_eq!(
+        page.items,
+        vec![
+            command.clone(),
+            denied.clone(),
+            read.clone(),
+            created.clone()
+        ]
+    );
+
+    let page = audit_log
+        .list_events(&tenant_id, &ListAuditEventsQuery::new(Some(2), Some(1)))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 4);
+    assert_eq!(page.items, vec![denied.clone(), read.clone()]);
+
+    let mut query = ListAuditEventsQuery::new(None, None);
+    query.serial_number = Some(SERIAL_NUMBER.to_string());
+    query.api_key_id = Some(api_key_id);
+    let page = audit_log.list_events(&tenant_id, &query).await.unwrap();
+    assert_eq!(page.items, vec![command.clone(), created.clone()]);
+
+    let mut query = ListAuditEventsQuery::new(None, None);
+    query.action = Some(AuditAction::TokenExport);
+    query.outcome = Some(AuditOutcome::Denied);
+    let page = audit_log.list_events(&tenant_id, &query).await.unwrap();
+    assert_eq!(page.items, vec![denied.clone()]);
+
+    let mut query = ListAuditEventsQuery::new(None, None);
+    query.since = Some(read.occurred_at);
+    query.until = Some(command.occurred_at);
+    let page = audit_log.list_events(&tenant_id, &query).await.unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(page.items, vec![denied, read]);
+
+    let page = audit_log
+        .list_events(
+            &uuid::Uuid::new_v4(),
+            &ListAuditEventsQuery::new(None, None),
+        )
+        .await
+        .unwrap();
+    assert_eq!(page.total, 0);
+    assert!(page.items.is_empty());
+}
+
+async fn test_audit_events_are_purged_once_past_retention(
+    _: impl TenantRepository,
+    _: impl RefreshTokenRepository,
+    audit_log: impl AuditLog,
+) {
+    let tenant_id = uuid::Uuid::new_v4();
+    let other_tenant_id = uuid::Uuid::new_v4();
+    let api_key_id = uuid::Uuid::new_v4();
+
+    let oldest = audit_event(tenant_id, api_key_id, AuditAction::TokenRead, 0);
+    let old = audit_event(other_tenant_id, api_key_id, AuditAction::TokenRead, 1);
+    let recent = audit_event(tenant_id, api_key_id, AuditAction::TokenDelete, 2);
+    for event in [&oldest, &old, &recent] {
+        audit_log.record(event).await.unwrap();
+    }
+
+    assert_eq!(audit_log.purge_before(recent.occurred_at).await.unwrap(), 2);
+    assert_eq!(audit_log.purge_before(recent.occurred_at).await.unwrap(), 0);
+
+    let page = audit_log
+        .list_events(&tenant_id, &ListAuditEventsQuery::new(None, None))
+        .await
+        .unwrap();
+    assert_eq!(page.items, vec![recent]);
+    let page = audit_log
+        .list_events(&other_tenant_id, &ListAuditEventsQuery::new(None, None))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 0);
+}
+
+/// A migrated database private to the test.
+async fn sqlite() -> Arc<Sqlite> {
+    let sqlite = Sqlite::new(&DatabaseConfig {
+        url: Secret::new("sqlite::memory:"),
+        backend: DatabaseBackend::Sqlite,
+        max_connections: 1,
+        min_connections: 1,
+        acquire_timeout: std::time::Duration::from_secs(5),
+        run_migrations: true,
+    })
+    .await
+    .unwrap();
+    sqlite.migrate().await.unwrap();
+
+    Arc::new(sqlite)
+}
+
+/// Runs each case against every backend, in a module named after it.
+macro_rules! conformance_tests {
+    ($($case:ident),* $(,)?) => {
+        mod postgres {
+            use crate::infrastructure::{
+                audit::postgres::audit_log::PostgresAuditLog,
+                db::postgres::testing::test_database,
+                tenant::postgres::tenant_repository::PostgresTenantRepository,
+                token::postgres::refresh_token_repository::PostgresRefreshTokenRepository,
+            };
+
+            $(
+                #[tokio::test]
+                async fn $case() {
+                    let Some(database) = test_database(true).await else {
+                        return;
+                    };
+                    super::$case(
+                        PostgresTenantRepository::new(database.postgres()),
+                        PostgresRefreshTokenRepository::new(database.postgres()),
+                        PostgresAuditLog::new(database.postgres()),
+                    )
+                    .await;
+                    database.remove().await;
+                }
+            )*
+        }
+
+        mod sqlite {
+            use std::sync::Arc;
+
+            use crate::infrastructure::{
+                audit::sqlite::audit_log::SqliteAuditLog,
+                tenant::sqlite::tenant_repository::SqliteTenantRepository,
+                token::sqlite::refresh_token_repository::SqliteRefreshTokenRepository,
+            };
+
+            $(
+                #[tokio::test]
+                async fn $case() {
+                    let sqlite = super::sqlite().await;
+                    super::$case(
+                        SqliteTenantRepository::new(Arc::clone(&sqlite)),
+                        SqliteRefreshTokenRepository::new(Arc::clone(&sqlite)),
+                        SqliteAuditLog::new(sqlite),
+                    )
+                    .await;
+                }
+            )*
+        }
+
+        mod memory {
+            use std::sync::Arc;
+
+            use crate::infrastructure::{
+                audit::memory::audit_log::InMemoryAuditLog,
+                db::memory::InMemory,
+                tenant::memory::tenant_repository::InMemoryTenantRepository,
+                token::memory::refresh_token_repository::InMemoryRefreshTokenRepository,
+            };
+
+            $(
+                #[tokio::test]
+                async fn $case() {
+                    let memory = Arc::new(InMemory::new());
+                    super::$case(
+                        InMemoryTenantRepository::new(Arc::clone(&memory)),
+                        InMemoryRefreshTokenRepository::new(Arc::clone(&memory)),
+                        InMemoryAuditLog::new(memory),
+                    )
+                    .await;
+                }
+            )*
+        }
+    };
+}
+
+conformance_tests!(
+    test_tenants_are_created_found_and_deleted,
+    test_api_keys_authenticate_their_tenant,
+    test_refresh_tokens_are_scoped_to_their_tenant,
+    test_duplicate_serial_numbers_are_replaced_only_when_allowed,
+    test_refresh_tokens_are_updated_and_deleted,
+    test_refresh_tokens_are_listed_by_serial_number,
+    test_audit_events_are_recorded_and_filtered,
+    test_audit_events_are_purged_once_past_retention,
+);
+
+/// A stored row that no longer makes a valid token, such as one edited by
+/// hand, fails the listing with a database error instead of panicking.
+#[tokio::test]
+async fn test_invalid_rows_are_database_errors() {
+    let sqlite = sqlite().await;
+    let tenants = SqliteTenantRepository::new(Arc::clone(&sqlite));
+    let refresh_tokens = SqliteRefreshTokenRepository::new(Arc::clone(&sqlite));
+    let tenant_id = create_tenant(&tenants, "makerspace").await;
+    refresh_tokens
+        .create_refresh_token(
+            &tenant_id,
+            &ProviderType::BambuLab,
+            &tokens("first"),
+            &serial(SERIAL_NUMBER),
+            &CreateRefreshTokenOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    sqlx::query("UPDATE refresh_tokens SET provider = 'unknown'")
+        .execute(&*sqlite.get_pool())
+        .await
+        .unwrap();
+
+    let error = refresh_tokens
+        .list_refresh_tokens(
+            &tenant_id,
+            &ListRefreshTokensQuery::new(None, None, None, None),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ListRefreshTokensError::DatabaseError(sqlx::Error::Decode(_))
+    ));
+}
@@ -1 +1,216 @@
+use std::sync::Arc;
+
+use sqlx::{
+    migrate::{Migrate, MigrateError, Migrator},
+    Pool,
+};
+
+use crate::{
+    config::DatabaseConfig,
+    domain::health::{models::health::HealthCheckError, ports::health::DatabaseHealthCheck},
+};
+
+pub mod memory;
 pub mod postgres;
+pub mod sqlite;
+
+/// The database a `DATABASE_URL` points to, told by its scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseBackend {
+    /// `postgres://` or `postgresql://`.
+    Postgres,
+    /// `sqlite:`, such as `sqlite:///var/lib/ferrisprinter/ferris.db` or
+    /// `sqlite::memory:`, for a single node where running Postgres is
+    /// overkill.
+    Sqlite,
+    /// `memory:`, nothing being persisted. Meant for the demo mode.
+    Memory,
+}
+
+impl DatabaseBackend {
+    pub fn from_url(url: &str) -> Option<Self> {
+        let (scheme, _) = url.split_once(':')?;
+        match scheme {
+            "postgres" | "postgresql" => Some(DatabaseBackend::Postgres),
+            "sqlite" => Some(DatabaseBackend::Sqlite),
+            "memory" => Some(DatabaseBackend::Memory),
+            _ => None,
+        }
+    }
+}
+
+/// The database of the [DatabaseBackend] configured, handling the concerns
+/// shared by the backends. The repositories take the backend itself.
+#[derive(Debug, Clone)]
+pub enum Database {
+    Postgres(Arc<postgres::Postgres>),
+    Sqlite(Arc<sqlite::Sqlite>),
+    Memory(Arc<memory::InMemory>),
+}
+
+impl Database {
+    pub async fn connect(config: &DatabaseConfig) -> anyhow::Result<Self> {
+        Ok(match config.backend {
+            DatabaseBackend::Postgres => {
+                Database::Postgres(Arc::new(postgres::Postgres::new(config).await?))
+            }
+            DatabaseBackend::Sqlite => {
+                Database::Sqlite(Arc::new(sqlite::Sqlite::new(config).await?))
+            }
+            DatabaseBackend::Memory => Database::Memory(Arc::new(memory::InMemory::new())),
+        })
+    }
+
+    pub async fn migrate(&self) -> anyhow::Result<()> {
+        match self {
+            Database::Postgres(postgres) => postgres.migrate().await,
+            Database::Sqlite(sqlite) => sqlite.migrate().await,
+            Database::Memory(_) => Ok(()),
+        }
+    }
+
+    pub async fn revert_migrations(&self, target: Option<i64>) -> anyhow::Result<()> {
+        match self {
+            Database::Postgres(postgres) => postgres.revert_migrations(target).await,
+            Database::Sqlite(sqlite) => sqlite.revert_migrations(target).await,
+            Database::Memory(_) => Ok(()),
+        }
+    }
+
+    pub async fn migration_status(&self) -> anyhow::Result<Vec<MigrationStatus>> {
+        match self {
+            Database::Postgres(postgres) => postgres.migration_status().await,
+            Database::Sqlite(sqlite) => sqlite.migration_status().await,
+            Database::Memory(_) => Ok(Vec::new()),
+        }
+    }
+
+    pub fn record_pool_metrics(&self) {
+        match self {
+            Database::Postgres(postgres) => postgres.record_pool_metrics(),
+            Database::Sqlite(sqlite) => sqlite.record_pool_metrics(),
+            Database::Memory(_) => {}
+        }
+    }
+
+    pub async fn close(&self) {
+        match self {
+            Database::Postgres(postgres) => postgres.close().await,
+            Database::Sqlite(sqlite) => sqlite.close().await,
+            Database::Memory(_) => {}
+        }
+    }
+}
+
+impl DatabaseHealthCheck for Database {
+    fn name(&self) -> &'static str {
+        match self {
+            Database::Postgres(postgres) => postgres.name(),
+            Database::Sqlite(sqlite) => sqlite.name(),
+            Database::Memory(memory) => memory.name(),
+        }
+    }
+
+    async fn ping(&self) -> Result<(), HealthCheckError> {
+        match self {
+            Database::Postgres(postgres) => postgres.ping().await,
+            Database::Sqlite(sqlite) => sqlite.ping().await,
+            Database::Memory(memory) => memory.ping().await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+    /// The migration was changed after being applied.
+    pub modified: bool,
+}
+
+/// Lists the migrations of `migrator`, oldest first, along with whether they
+/// are applied to the database of `conn`.
+async fn migration_status<C>(
+    migrator: &Migrator,
+    conn: &mut C,
+) -> Result<Vec<MigrationStatus>, MigrateError>
+where
+    C: Migrate + ?Sized,
+{
+    conn.ensure_migrations_table().await?;
+    let applied = conn.list_applied_migrations().await?;
+
+    Ok(migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| {
+            let applied = applied
+                .iter()
+                .find(|applied| applied.version == migration.version);
+
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                applied: applied.is_some(),
+                modified: applied.is_some_and(|applied| applied.checksum != migration.checksum),
+            }
+        })
+        .collect())
+}
+
+/// The version to revert to so that only the latest applied migration is
+/// undone, 0 when at most one is applied.
+async fn previous_version<C>(conn: &mut C) -> Result<i64, MigrateError>
+where
+    C: Migrate + ?Sized,
+{
+    conn.ensure_migrations_table().await?;
+    let mut versions: Vec<i64> = conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| migration.version)
+        .collect();
+    versions.sort_unstable();
+    versions.pop();
+
+    Ok(versions.pop().unwrap_or(0))
+}
+
+/// Publishes the usage of the pool under `db_pool_connections`. It changes
+/// with every query, so it is sampled rather than tracked.
+fn record_pool_metrics<DB: sqlx::Database>(pool: &Pool<DB>) {
+    let size = pool.size();
+    let idle = u32::try_from(pool.num_idle()).unwrap_or(size);
+
+    metrics::gauge!("db_pool_connections", "state" => "idle").set(idle);
+    metrics::gauge!("db_pool_connections", "state" => "active").set(size.saturating_sub(idle));
+    metrics::gauge!("db_pool_max_connections").set(pool.options().get_max_connections());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DatabaseBackend;
+
+    #[test]
+    fn test_backend_is_told_by_the_scheme() {
+        for (url, backend) in [
+            (
+                "postgres://localhost/ferris",
+                Some(DatabaseBackend::Postgres),
+            ),
+            (
+                "postgresql://localhost/ferris",
+                Some(DatabaseBackend::Postgres),
+            ),
+            ("sqlite:///var/lib/ferris.db", Some(DatabaseBackend::Sqlite)),
+            ("sqlite::memory:", Some(DatabaseBackend::Sqlite)),
+            ("memory:", Some(DatabaseBackend::Memory)),
+            ("mysql://localhost/ferris", None),
+            ("ferris.db", None),
+        ] {
+            assert_eq!(DatabaseBackend::from_url(url), backend, "{}", url);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::domain::{
+    audit::models::audit::AuditEvent,
+    health::{models::health::HealthCheckError, ports::health::DatabaseHealthCheck},
+    tenant::models::{api_key::ApiKeyInfo, tenant::TenantRow},
+    token::models::refresh_token::RefreshTokenRow,
+};
+
+/// Storage kept in the memory of the process and lost when it exits, for the
+/// tests and the demo mode. It needs neither a database server nor the
+/// `DATABASE_URL` the query macros are checked against.
+#[derive(Debug, Clone, Default)]
+pub struct InMemory {
+    tables: Arc<Mutex<Tables>>,
+}
+
+/// The rows of each table, the repositories enforcing the constraints the
+/// databases do.
+#[derive(Debug, Default)]
+pub(crate) struct Tables {
+    pub tenants: HashMap<uuid::Uuid, TenantRow>,
+    /// By the hash of the key.
+    pub api_keys: HashMap<String, ApiKeyInfo>,
+    /// By tenant and serial number, which keeps them ordered for listing.
+    pub refresh_tokens: BTreeMap<(uuid::Uuid, String), StoredRefreshToken>,
+    /// In the order they were recorded.
+    pub audit_events: Vec<AuditEvent>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StoredRefreshToken {
+    pub row: RefreshTokenRow,
+    pub idempotency_key: Option<String>,
+}
+
+impl InMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Locks every table, so that each operation of a repository is atomic.
+    pub(crate) fn tables(&self) -> MutexGuard<'_, Tables> {
+        // A panic while holding the lock leaves complete rows behind, as the
+        // repositories only insert or remove whole ones.
+        self.tables
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl DatabaseHealthCheck for InMemory {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn ping(&self) -> Result<(), HealthCheckError> {
+        Ok(())
+    }
+}
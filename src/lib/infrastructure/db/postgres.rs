@@ -1,9 +1,19 @@
 use anyhow::{Context, Ok, Result};
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{migrate::Migrator, postgres::PgPoolOptions, PgPool};
 use std::sync::Arc;
 use tracing::info;
 
-use crate::env::Env;
+use super::MigrationStatus;
+use crate::{
+    config::DatabaseConfig,
+    domain::health::{models::health::HealthCheckError, ports::health::DatabaseHealthCheck},
+};
+
+#[cfg(test)]
+pub(crate) mod testing;
+
+/// The migrations of `migrations/`, embedded in the binary.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
 #[derive(Debug, Clone)]
 pub struct Postgres {
@@ -11,10 +21,12 @@ pub struct Postgres {
 }
 
 impl Postgres {
-    pub async fn new(env: Arc<Env>) -> Result<Self> {
+    pub async fn new(config: &DatabaseConfig) -> Result<Self> {
         let pool = PgPoolOptions::new()
-            .max_connections(5)
-            .connect(&env.database_url)
+            .max_connections(config.max_connections)
+            .min_connections(config.min_connections)
+            .acquire_timeout(config.acquire_timeout)
+            .connect(config.url.expose())
             .await
             .context("Failed to connect to the Postgres database")?;
 
@@ -28,4 +40,147 @@ impl Postgres {
     pub fn get_pool(&self) -> Arc<PgPool> {
         Arc::clone(&self.pool)
     }
+
+    /// Publishes the usage of the pool under `db_pool_connections`.
+    pub fn record_pool_metrics(&self) {
+        super::record_pool_metrics(&self.pool);
+    }
+
+    /// Applies the pending migrations. They are run under a Postgres advisory
+    /// lock, so that replicas starting together wait for the first one to
+    /// migrate instead of racing it.
+    pub async fn migrate(&self) -> Result<()> {
+        MIGRATOR
+            .run(&*self.pool)
+            .await
+            .context("Failed to apply the database migrations")?;
+
+        info!("Applied the database migrations");
+        Ok(())
+    }
+
+    /// Reverts the migrations applied after `target`, or the latest one when
+    /// no target is given.
+    pub async fn revert_migrations(&self, target: Option<i64>) -> Result<()> {
+        let target = match target {
+            Some(target) => target,
+            None => super::previous_version(&mut *self.pool.acquire().await?).await?,
+        };
+
+        MIGRATOR
+            .undo(&*self.pool, target)
+            .await
+            .context("Failed to revert the database migrations")?;
+
+        info!("Reverted the database migrations after {}", target);
+        Ok(())
+    }
+
+    /// Lists the embedded migrations, oldest first.
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>> {
+        Ok(super::migration_status(&MIGRATOR, &mut *self.pool.acquire().await?).await?)
+    }
+
+    /// Waits for the checked out connections to be returned, then closes them
+    /// all. Queries issued afterwards fail.
+    pub async fn close(&self) {
+        self.pool.close().await;
+        info!("Closed the Postgres connection pool");
+    }
+}
+
+impl DatabaseHealthCheck for Postgres {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn ping(&self) -> std::result::Result<(), HealthCheckError> {
+        sqlx::query("SELECT 1")
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| HealthCheckError::Failed(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::test_database;
+
+    #[tokio::test]
+    async fn test_migrations_are_applied_and_reverted() {
+        let Some(database) = test_database(false).await else {
+            return;
+        };
+        let postgres = database.postgres();
+        let status = postgres.migration_status().await.unwrap();
+        assert!(!status.is_empty());
+        assert!(status.iter().all(|migration| !migration.applied));
+
+        postgres.migrate().await.unwrap();
+        postgres.migrate().await.unwrap();
+        let status = postgres.migration_status().await.unwrap();
+        assert!(status
+            .iter()
+            .all(|migration| migration.applied && !migration.modified));
+
+        postgres.revert_migrations(None).await.unwrap();
+        let status = postgres.migration_status().await.unwrap();
+        let (latest, others) = status.split_last().unwrap();
+        assert!(!latest.applied);
+        assert!(others.iter().all(|migration| migration.applied));
+
+        postgres.revert_migrations(Some(0)).await.unwrap();
+        let status = postgres.migration_status().await.unwrap();
+        assert!(status.iter().all(|migration| !migration.applied));
+
+        database.remove().await;
+    }
+
+    #[tokio::test]
+    async fn test_tenants_are_not_reverted_over_shared_serial_numbers() {
+        let Some(database) = test_database(true).await else {
+            return;
+        };
+        let postgres = database.postgres();
+        for (tenant, name) in [
+            (uuid::Uuid::new_v4(), "first"),
+            (uuid::Uuid::new_v4(), "second"),
+        ] {
+            sqlx::query("INSERT INTO tenants (id, name, created_at, updated_at) VALUES ($1, $2, NOW(), NOW())")
+                .bind(tenant)
+                .bind(name)
+                .execute(&*postgres.get_pool())
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO refresh_tokens (id, tenant_id, provider, serial_number, token, created_at, updated_at)
+                 VALUES ($1, $2, 'bambulab', '00M09A350100123', 'token', NOW(), NOW())",
+            )
+            .bind(uuid::Uuid::new_v4())
+            .bind(tenant)
+            .execute(&*postgres.get_pool())
+            .await
+            .unwrap();
+        }
+
+        let error = postgres.revert_migrations(Some(0)).await.unwrap_err();
+        assert!(
+            format!("{error:#}").contains("several tenants own a token for the same serial number")
+        );
+        let status = postgres.migration_status().await.unwrap();
+        let tenants = status
+            .iter()
+            .find(|migration| migration.description == "tenants")
+            .unwrap();
+        assert!(tenants.applied);
+        let tokens: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM refresh_tokens WHERE tenant_id IS NOT NULL")
+                .fetch_one(&*postgres.get_pool())
+                .await
+                .unwrap();
+        assert_eq!(tokens, 2);
+
+        database.remove().await;
+    }
 }
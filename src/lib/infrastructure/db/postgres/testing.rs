@@ -0,0 +1,158 @@
+//! Postgres databases private to a test, on the server of `DATABASE_URL` or,
+//! when it is not set, on a local cluster started from a throwaway data
+//! directory. The tests return early, without failing, when neither is
+//! available.
+
+use std::{
+    path::Path,
+    process::Command,
+    str::FromStr,
+    sync::{Arc, OnceLock},
+};
+
+use sqlx::{
+    postgres::{PgConnectOptions, PgPoolOptions},
+    Connection, Executor, PgConnection,
+};
+
+use super::Postgres;
+
+/// Database created for a single test, see [test_database].
+pub(crate) struct TestDatabase {
+    postgres: Arc<Postgres>,
+    name: String,
+    server: PgConnectOptions,
+}
+
+impl TestDatabase {
+    pub fn postgres(&self) -> Arc<Postgres> {
+        Arc::clone(&self.postgres)
+    }
+
+    /// Drops the database. It is left behind when the test panics before,
+    /// to be looked into.
+    pub async fn remove(self) {
+        self.postgres.close().await;
+        let mut conn = PgConnection::connect_with(&self.server).await.unwrap();
+        conn.execute(format!(r#"DROP DATABASE "{}" WITH (FORCE)"#, self.name).as_str())
+            .await
+            .unwrap();
+    }
+}
+
+/// Creates an empty database, migrated when `migrate` is set, or returns
+/// `None` when no Postgres server can be reached nor started.
+pub(crate) async fn test_database(migrate: bool) -> Option<TestDatabase> {
+    let server = server()?.clone();
+    let name = format!("ferrisprinter_test_{}", uuid::Uuid::new_v4().simple());
+
+    let mut conn = PgConnection::connect_with(&server).await.unwrap();
+    conn.execute(format!(r#"CREATE DATABASE "{}""#, name).as_str())
+        .await
+        .unwrap();
+    conn.close().await.unwrap();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect_with(server.clone().database(&name))
+        .await
+        .unwrap();
+    let postgres = Postgres {
+        pool: Arc::new(pool),
+    };
+    if migrate {
+        postgres.migrate().await.unwrap();
+    }
+
+    Some(TestDatabase {
+        postgres: Arc::new(postgres),
+        name,
+        server,
+    })
+}
+
+/// The server the test databases are created on, found once per process.
+fn server() -> Option<&'static PgConnectOptions> {
+    static SERVER: OnceLock<Option<PgConnectOptions>> = OnceLock::new();
+
+    SERVER
+        .get_or_init(|| {
+            if let Ok(url) = std::env::var("DATABASE_URL") {
+                if url.starts_with("postgres") {
+                    return Some(PgConnectOptions::from_str(&url).unwrap());
+                }
+            }
+
+            let data_dir = std::env::temp_dir().join("ferrisprinter-test-postgres");
+            match start_local_cluster(&data_dir) {
+                Ok(()) => Some(
+                    PgConnectOptions::new()
+                        .socket(&data_dir)
+                        .username("postgres")
+                        .database("postgres"),
+                ),
+                Err(reason) => {
+                    eprintln!(
+                        "skipping the Postgres tests, set DATABASE_URL or install initdb and pg_ctl: {}",
+                        reason
+                    );
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// Initializes a cluster in `data_dir` unless done by a previous run, then
+/// starts it unless already running. It only listens on a socket in
+/// `data_dir`, and is left running for the next runs to reuse; stop it with
+/// `pg_ctl -D <data_dir> stop`.
+fn start_local_cluster(data_dir: &Path) -> Result<(), String> {
+    if !data_dir.join("PG_VERSION").exists() {
+        run(Command::new("initdb").arg("--pgdata").arg(data_dir).args([
+            "--username=postgres",
+            "--auth=trust",
+            "--encoding=UTF8",
+            "--no-sync",
+        ]))?;
+    }
+
+    let running = Command::new("pg_ctl")
+        .arg("status")
+        .arg("--pgdata")
+        .arg(data_dir)
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if !running {
+        // Durability is of no use for throwaway data.
+        let options = format!(
+            "-k {} -c listen_addresses='' -c fsync=off -c synchronous_commit=off",
+            data_dir.display()
+        );
+        run(Command::new("pg_ctl")
+            .arg("start")
+            .arg("--pgdata")
+            .arg(data_dir)
+            .arg("--log")
+            .arg(data_dir.join("postgres.log"))
+            .args(["--wait", "-o", &options]))?;
+    }
+
+    Ok(())
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
+        .output()
+        .map_err(|e| format!("could not run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use anyhow::{Context, Ok, Result};
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    SqlitePool,
+};
+use std::{str::FromStr, sync::Arc};
+use tracing::info;
+
+use super::MigrationStatus;
+use crate::{
+    config::DatabaseConfig,
+    domain::health::{models::health::HealthCheckError, ports::health::DatabaseHealthCheck},
+};
+
+/// The migrations of `migrations/sqlite/`, embedded in the binary.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+#[derive(Debug, Clone)]
+pub struct Sqlite {
+    pub pool: Arc<SqlitePool>,
+}
+
+impl Sqlite {
+    /// Opens the database file, creating it when it does not exist yet.
+    pub async fn new(config: &DatabaseConfig) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(config.url.expose())
+            .context("Invalid SQLite database URL")?
+            .create_if_missing(true);
+
+        let mut pool_options = SqlitePoolOptions::new()
+            .max_connections(config.max_connections)
+            .min_connections(config.min_connections)
+            .acquire_timeout(config.acquire_timeout);
+        // An in-memory database only lives as long as a connection to it, and
+        // its connections lock each other out rather than wait, so a single
+        // one is kept open.
+        if options
+            .get_filename()
+            .to_string_lossy()
+            .contains("in-memory")
+        {
+            pool_options = pool_options
+                .max_connections(1)
+                .min_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None);
+        }
+
+        let pool = pool_options
+            .connect_with(options)
+            .await
+            .context("Failed to open the SQLite database")?;
+
+        info!("Opened the SQLite database");
+
+        Ok(Self {
+            pool: Arc::new(pool),
+        })
+    }
+
+    pub fn get_pool(&self) -> Arc<SqlitePool> {
+        Arc::clone(&self.pool)
+    }
+
+    /// Publishes the usage of the pool under `db_pool_connections`.
+    pub fn record_pool_metrics(&self) {
+        super::record_pool_metrics(&self.pool);
+    }
+
+    /// Applies the pending migrations. Unlike on Postgres they are not locked,
+    /// a SQLite database being served by a single node.
+    pub async fn migrate(&self) -> Result<()> {
+        MIGRATOR
+            .run(&*self.pool)
+            .await
+            .context("Failed to apply the database migrations")?;
+
+        info!("Applied the database migrations");
+        Ok(())
+    }
+
+    /// Reverts the migrations applied after `target`, or the latest one when
+    /// no target is given.
+    pub async fn revert_migrations(&self, target: Option<i64>) -> Result<()> {
+        let target = match target {
+            Some(target) => target,
+            None => super::previous_version(&mut *self.pool.acquire().await?).await?,
+        };
+
+        MIGRATOR
+            .undo(&*self.pool, target)
+            .await
+            .context("Failed to revert the database migrations")?;
+
+        info!("Reverted the database migrations after {}", target);
+        Ok(())
+    }
+
+    /// Lists the embedded migrations, oldest first.
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>> {
+        Ok(super::migration_status(&MIGRATOR, &mut *self.pool.acquire().await?).await?)
+    }
+
+    /// Waits for the checked out connections to be returned, then closes them
+    /// all. Queries issued afterwards fail.
+    pub async fn close(&self) {
+        self.pool.close().await;
+        info!("Closed the SQLite connection pool");
+    }
+}
+
+impl DatabaseHealthCheck for Sqlite {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn ping(&self) -> std::result::Result<(), HealthCheckError> {
+        sqlx::query("SELECT 1")
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| HealthCheckError::Failed(e.to_string()))
+    }
+}
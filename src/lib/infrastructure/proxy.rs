@@ -0,0 +1 @@
+pub mod bambulab_api_client;
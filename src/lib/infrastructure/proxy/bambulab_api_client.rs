@@ -0,0 +1,88 @@
+use futures_util::TryStreamExt;
+use http::Method;
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+
+use crate::{
+    application::telemetry,
+    domain::{
+        proxy::{
+            models::proxy::{ProxyAllowlist, ProxyError, ProxyRequest, ProxyResponse, ProxyRule},
+            ports::proxy::ProviderApiClient,
+        },
+        token::models::token::Token,
+    },
+    infrastructure::resilience::{ResiliencePolicy, ResilientClient},
+};
+
+#[derive(Debug, Clone)]
+pub struct BambuLabApiClient {
+    http_client: ResilientClient,
+    api_url: String,
+}
+
+impl BambuLabApiClient {
+    pub fn new(api_url: String) -> reqwest::Result<Self> {
+        Ok(Self {
+            http_client: ResilientClient::new("bambulab", ResiliencePolicy::default())?,
+            api_url,
+        })
+    }
+
+    pub fn with_http_client(mut self, http_client: ResilientClient) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Read-only endpoints exposing task history, bound devices and their
+    /// print status, and account messages.
+    pub fn default_allowlist() -> ProxyAllowlist {
+        ProxyAllowlist::new(vec![
+            ProxyRule::new(Method::GET, "/v1/user-service/my/tasks"),
+            ProxyRule::new(Method::GET, "/v1/user-service/my/messages"),
+            ProxyRule::new(Method::GET, "/v1/iot-service/api/user/bind"),
+            ProxyRule::new(Method::GET, "/v1/iot-service/api/user/print"),
+        ])
+    }
+}
+
+impl ProviderApiClient for BambuLabApiClient {
+    async fn send(
+        &self,
+        access_token: &Token,
+        request: &ProxyRequest,
+    ) -> Result<ProxyResponse, ProxyError> {
+        let mut uri = format!("{}{}", self.api_url, request.path.as_str());
+        if let Some(query) = &request.query {
+            uri = format!("{}?{}", uri, query);
+        }
+
+        let mut upstream_request = self
+            .http_client
+            .request(request.method.clone(), &uri)
+            .header(AUTHORIZATION, format!("Bearer {}", access_token.expose()))
+            .headers(telemetry::trace_context_headers())
+            .body(request.body.clone());
+        if let Some(content_type) = &request.content_type {
+            upstream_request = upstream_request.header(CONTENT_TYPE, content_type);
+        }
+
+        let response = self
+            .http_client
+            .send(upstream_request, request.method.is_idempotent())
+            .await
+            .map_err(|e| ProxyError::Upstream(e.to_string()))?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        Ok(ProxyResponse {
+            status: response.status(),
+            content_type,
+            body: Box::pin(response.bytes_stream().map_err(std::io::Error::other)),
+            token_renewed: false,
+        })
+    }
+}
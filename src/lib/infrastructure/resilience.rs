@@ -0,0 +1,571 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+use reqwest::{
+    header::RETRY_AFTER, Client, ClientBuilder, Method, RequestBuilder, Response, StatusCode,
+};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::domain::health::models::health::CircuitState;
+
+/// Timeouts, retries and circuit breaking of the calls made to a provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResiliencePolicy {
+    pub connect_timeout: Duration,
+    /// Longest wait for the next chunk of a response, so that a slow but
+    /// steady stream through the proxy is not cut.
+    pub read_timeout: Duration,
+    /// Retries of an idempotent call after a network error, a server error or
+    /// `429 Too Many Requests`.
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    /// Cap of the backoff between retries. A `Retry-After` asking for longer
+    /// is not waited for, the response being returned as is.
+    pub max_backoff: Duration,
+    /// Consecutive failed calls opening the circuit.
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a call probes the provider.
+    pub open_duration: Duration,
+}
+
+impl Default for ResiliencePolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(5),
+            read_timeout: Duration::from_secs(15),
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ResilienceError {
+    #[error("the circuit of {provider} is open, retry in {}s", retry_after.as_secs().max(1))]
+    CircuitOpen {
+        provider: &'static str,
+        retry_after: Duration,
+    },
+    #[error("{0}")]
+    Transport(reqwest::Error),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BreakerState {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// Only one call probes the provider at a time.
+    HalfOpen {
+        probing: bool,
+    },
+}
+
+/// Fails the calls to a provider fast once it failed `failure_threshold` times
+/// in a row, until a probe after `open_duration` succeeds.
+#[derive(Debug)]
+struct CircuitBreaker {
+    failure_threshold: u32,
+    open_duration: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    fn new(policy: &ResiliencePolicy) -> Self {
+        Self {
+            failure_threshold: policy.failure_threshold.max(1),
+            open_duration: policy.open_duration,
+            state: Mutex::new(BreakerState::Closed { failures: 0 }),
+        }
+    }
+
+    fn state(&self, now: Instant) -> CircuitState {
+        match *self.state.lock().unwrap() {
+            BreakerState::Closed { .. } => CircuitState::Closed,
+            BreakerState::Open { until } if until > now => CircuitState::Open,
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Lets a call through, or returns how long until the circuit lets one.
+    fn acquire(&self, now: Instant) -> Result<Permit<'_>, Duration> {
+        let mut state = self.state.lock().unwrap();
+
+        let probe = match *state {
+            BreakerState::Closed { .. } => false,
+            BreakerState::Open { until } if until > now => return Err(until - now),
+            BreakerState::Open { .. } | BreakerState::HalfOpen { probing: false } => {
+                *state = BreakerState::HalfOpen { probing: true };
+                true
+            }
+            BreakerState::HalfOpen { probing: true } => return Err(Duration::ZERO),
+        };
+
+        Ok(Permit {
+            breaker: self,
+            probe,
+            recorded: false,
+        })
+    }
+
+    /// Returns whether the call opened the circuit.
+    fn record(&self, success: bool, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        let (next, opened) = match (*state, success) {
+            (_, true) => (BreakerState::Closed { failures: 0 }, false),
+            (BreakerState::Closed { failures }, false) if failures + 1 < self.failure_threshold => {
+                (
+                    BreakerState::Closed {
+                        failures: failures + 1,
+                    },
+                    false,
+                )
+            }
+            (BreakerState::Open { until }, false) => (BreakerState::Open { until }, false),
+            (_, false) => (
+                BreakerState::Open {
+                    until: now + self.open_duration,
+                },
+                true,
+            ),
+        };
+        *state = next;
+
+        opened
+    }
+}
+
+/// A call let through by a [CircuitBreaker], whose outcome is given to
+/// [Permit::record].
+///
+/// A probe dropped before recording its outcome, such as when the caller
+/// timed out or the client of the proxy went away, lets the next call probe
+/// rather than leaving the circuit waiting for it forever.
+struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    recorded: bool,
+}
+
+impl Permit<'_> {
+    /// Returns whether the call opened the circuit.
+    fn record(mut self, success: bool, now: Instant) -> bool {
+        self.recorded = true;
+        self.breaker.record(success, now)
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if self.probe && !self.recorded {
+            let mut state = self.breaker.state.lock().unwrap();
+            if let BreakerState::HalfOpen { probing: true } = *state {
+                *state = BreakerState::HalfOpen { probing: false };
+            }
+        }
+    }
+}
+
+/// HTTP client of a provider applying a [ResiliencePolicy]. Clones share their
+/// circuit breaker, so the token service and the API client of a provider
+/// should be given clones of the same client.
+#[derive(Debug, Clone)]
+pub struct ResilientClient {
+    provider: &'static str,
+    client: Client,
+    policy: ResiliencePolicy,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl ResilientClient {
+    pub const DEFAULT_USER_AGENT: &'static str = "ferris-printer";
+
+    /// Fails like [Self::build], when the TLS backend cannot be initialized.
+    pub fn new(provider: &'static str, policy: ResiliencePolicy) -> reqwest::Result<Self> {
+        Self::build(
+            provider,
+            Client::builder().user_agent(Self::DEFAULT_USER_AGENT),
+            policy,
+        )
+    }
+
+    /// Builds the client from `builder`, which carries the settings unrelated
+    /// to resilience such as the user agent or a proxy.
+    pub fn build(
+        provider: &'static str,
+        builder: ClientBuilder,
+        policy: ResiliencePolicy,
+    ) -> reqwest::Result<Self> {
+        let client = builder
+            .connect_timeout(policy.connect_timeout)
+            .read_timeout(policy.read_timeout)
+            .build()?;
+
+        Ok(Self {
+            provider,
+            client,
+            policy,
+            breaker: Arc::new(CircuitBreaker::new(&policy)),
+        })
+    }
+
+    pub fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    pub fn circuit_state(&self) -> CircuitState {
+        self.breaker.state(Instant::now())
+    }
+
+    /// Sends `request` once, around the circuit breaker: health checks must
+    /// neither be refused by an open circuit, which would hide a recovery,
+    /// nor count towards opening it for the calls made on behalf of tenants.
+    pub async fn probe(&self, request: RequestBuilder) -> Result<Response, ResilienceError> {
+        request
+            .send()
+            .await
+            .map_err(|e| ResilienceError::Transport(e.without_url()))
+    }
+
+    /// Sends `request`, retrying it with jittered exponential backoff when
+    /// `idempotent`. The last response is returned whatever its status, for
+    /// the caller to interpret.
+    pub async fn send(
+        &self,
+        mut request: RequestBuilder,
+        idempotent: bool,
+    ) -> Result<Response, ResilienceError> {
+        let mut attempt = 0;
+
+        loop {
+            let permit = self
+                .breaker
+                .acquire(Instant::now())
+                .map_err(|retry_after| ResilienceError::CircuitOpen {
+                    provider: self.provider,
+                    retry_after,
+                })?;
+
+            // Bodies streamed from the client cannot be sent twice.
+            let retry = request.try_clone().filter(|_| idempotent);
+            let result = request
+                .send()
+                .await
+                .map_err(|e| ResilienceError::Transport(e.without_url()));
+
+            let failed = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(_) => true,
+            };
+            if permit.record(!failed, Instant::now()) {
+                warn!(
+                    provider = self.provider,
+                    "opening the circuit for {:?}", self.policy.open_duration
+                );
+            }
+
+            let retryable = match &result {
+                Ok(response) => failed || response.status() == StatusCode::TOO_MANY_REQUESTS,
+                Err(ResilienceError::Transport(e)) => !e.is_builder(),
+                Err(_) => false,
+            };
+
+            let next = match retry {
+                Some(next) if retryable && attempt < self.policy.max_retries => next,
+                _ => return result,
+            };
+
+            let delay = match result.as_ref().ok().and_then(retry_after) {
+                Some(delay) if delay > self.policy.max_backoff => return result,
+                Some(delay) => delay,
+                None => self.backoff(attempt),
+            };
+
+            metrics::counter!("provider_http_retries_total", "provider" => self.provider)
+                .increment(1);
+            tokio::time::sleep(delay).await;
+
+            attempt += 1;
+            request = next;
+        }
+    }
+
+    /// Full jitter: a random delay up to the exponential backoff of the attempt.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .policy
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.policy.max_backoff);
+
+        ceiling.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+    }
+}
+
+/// The delay of a `Retry-After` header given in seconds. HTTP dates are not
+/// used by the providers and fall back to the backoff.
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use httpmock::MockServer;
+    use reqwest::{Method, StatusCode};
+
+    use super::{ResilienceError, ResiliencePolicy, ResilientClient};
+    use crate::domain::health::models::health::CircuitState;
+
+    fn policy() -> ResiliencePolicy {
+        ResiliencePolicy {
+            connect_timeout: Duration::from_secs(1),
+            read_timeout: Duration::from_millis(200),
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(2),
+            failure_threshold: 10,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_calls_are_retried_on_server_errors() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.path("/status");
+            then.status(503);
+        });
+        let client = ResilientClient::new("test", policy()).unwrap();
+
+        let response = client
+            .send(client.request(Method::GET, &server.url("/status")), true)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        mock.assert_hits(3);
+    }
+
+    #[tokio::test]
+    async fn test_other_calls_are_sent_once() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.path("/status");
+            then.status(503);
+        });
+        let client = ResilientClient::new("test", policy()).unwrap();
+
+        client
+            .send(client.request(Method::POST, &server.url("/status")), false)
+            .await
+            .unwrap();
+
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.path("/status");
+            then.status(401);
+        });
+        let client = ResilientClient::new("test", policy()).unwrap();
+
+        client
+            .send(client.request(Method::GET, &server.url("/status")), true)
+            .await
+            .unwrap();
+
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_is_honored() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.path("/status");
+            then.status(429).header("retry-after", "1");
+        });
+        let client = ResilientClient::new(
+            "test",
+            ResiliencePolicy {
+                max_retries: 1,
+                ..policy()
+            },
+        )
+        .unwrap();
+
+        let started_at = Instant::now();
+        client
+            .send(client.request(Method::GET, &server.url("/status")), true)
+            .await
+            .unwrap();
+
+        assert!(started_at.elapsed() >= Duration::from_secs(1));
+        mock.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_beyond_the_backoff_cap_is_not_waited_for() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.path("/status");
+            then.status(429).header("retry-after", "120");
+        });
+        let client = ResilientClient::new("test", policy()).unwrap();
+
+        let response = client
+            .send(client.request(Method::GET, &server.url("/status")), true)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_responses_time_out() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.path("/status");
+            then.status(200).delay(Duration::from_millis(500));
+        });
+        let client = ResilientClient::new(
+            "test",
+            ResiliencePolicy {
+                max_retries: 1,
+                ..policy()
+            },
+        )
+        .unwrap();
+
+        let result = client
+            .send(client.request(Method::GET, &server.url("/status")), true)
+            .await;
+
+        assert!(matches!(result, Err(ResilienceError::Transport(e)) if e.is_timeout()));
+        mock.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_after_consecutive_failures_and_probes_once_open() {
+        let server = MockServer::start();
+        let mut failing = server.mock(|when, then| {
+            when.path("/status");
+            then.status(500);
+        });
+        let client = ResilientClient::new(
+            "test",
+            ResiliencePolicy {
+                max_retries: 0,
+                failure_threshold: 2,
+                open_duration: Duration::from_millis(200),
+                ..policy()
+            },
+        )
+        .unwrap();
+        let url = server.url("/status");
+
+        for _ in 0..2 {
+            client
+                .send(client.request(Method::GET, &url), true)
+                .await
+                .unwrap();
+        }
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+
+        let result = client.send(client.request(Method::GET, &url), true).await;
+        assert!(matches!(result, Err(ResilienceError::CircuitOpen { .. })));
+        failing.assert_hits(2);
+
+        failing.delete();
+        let healthy = server.mock(|when, then| {
+            when.path("/status");
+            then.status(200);
+        });
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(client.circuit_state(), CircuitState::HalfOpen);
+
+        let response = client
+            .send(client.request(Method::GET, &url), true)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(client.circuit_state(), CircuitState::Closed);
+        healthy.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_recovers_from_a_cancelled_probe() {
+        let server = MockServer::start();
+        let mut failing = server.mock(|when, then| {
+            when.path("/status");
+            then.status(500);
+        });
+        let client = ResilientClient::new(
+            "test",
+            ResiliencePolicy {
+                max_retries: 0,
+                failure_threshold: 1,
+                open_duration: Duration::from_millis(100),
+                ..policy()
+            },
+        )
+        .unwrap();
+        let url = server.url("/status");
+
+        client
+            .send(client.request(Method::GET, &url), true)
+            .await
+            .unwrap();
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+
+        failing.delete();
+        let mut slow = server.mock(|when, then| {
+            when.path("/status");
+            then.status(200).delay(Duration::from_millis(150));
+        });
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        // Such as a health check giving up before the provider answers.
+        let probe = tokio::time::timeout(
+            Duration::from_millis(50),
+            client.send(client.request(Method::GET, &url), true),
+        )
+        .await;
+        assert!(probe.is_err());
+        assert_eq!(client.circuit_state(), CircuitState::HalfOpen);
+
+        slow.delete();
+        let healthy = server.mock(|when, then| {
+            when.path("/status");
+            then.status(200);
+        });
+        let response = client
+            .send(client.request(Method::GET, &url), true)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(client.circuit_state(), CircuitState::Closed);
+        healthy.assert_hits(1);
+    }
+}
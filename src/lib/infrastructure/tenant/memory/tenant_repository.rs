@@ -0,0 +1,145 @@
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::{
+    domain::tenant::{
+        models::{
+            api_key::{ApiKeyInfo, AuthenticateTenantError, Scope},
+            tenant::{
+                CreateTenantError, FindTenantError, ListTenantsError, Tenant, TenantName, TenantRow,
+            },
+        },
+        ports::tenant::TenantRepository,
+    },
+    infrastructure::db::memory::InMemory,
+};
+
+#[derive(Debug, Clone)]
+pub struct InMemoryTenantRepository {
+    memory: Arc<InMemory>,
+}
+
+impl InMemoryTenantRepository {
+    pub fn new(memory: Arc<InMemory>) -> Self {
+        Self { memory }
+    }
+}
+
+impl TenantRepository for InMemoryTenantRepository {
+    #[instrument(name = "tenants.create", skip_all, fields(db.system = "memory"))]
+    async fn create_tenant(
+        &self,
+        name: &str,
+        api_key_hash: &str,
+    ) -> Result<Tenant, CreateTenantError> {
+        let name = TenantName::new(name).unwrap();
+        let now = OffsetDateTime::now_utc();
+        let row = TenantRow {
+            id: uuid::Uuid::new_v4(),
+            name: name.as_str().to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut tables = self.memory.tables();
+        if tables
+            .tenants
+            .values()
+            .any(|tenant| tenant.name == row.name)
+        {
+            return Err(CreateTenantError::Duplicate { name });
+        }
+
+        tables.api_keys.insert(
+            api_key_hash.to_string(),
+            ApiKeyInfo::new(uuid::Uuid::new_v4(), row.id, Vec::new(), now),
+        );
+        tables.tenants.insert(row.id, row.clone());
+
+        info!("Creation of the tenant: {}", name);
+
+        Ok(row.into())
+    }
+
+    #[instrument(name = "tenants.list", skip_all, fields(db.system = "memory"))]
+    async fn list_tenants(&self) -> Result<Vec<Tenant>, ListTenantsError> {
+        let mut rows: Vec<TenantRow> = self.memory.tables().tenants.values().cloned().collect();
+        rows.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(rows.into_iter().map(Tenant::from).collect())
+    }
+
+    #[instrument(name = "tenants.find", skip_all, fields(db.system = "memory", tenant_id = %tenant_id))]
+    async fn find_tenant(&self, tenant_id: &uuid::Uuid) -> Result<Tenant, FindTenantError> {
+        self.memory
+            .tables()
+            .tenants
+            .get(tenant_id)
+            .cloned()
+            .map(Tenant::from)
+            .ok_or(FindTenantError::NotFound { id: *tenant_id })
+    }
+
+    #[instrument(name = "tenants.delete", skip_all, fields(db.system = "memory", tenant_id = %tenant_id))]
+    async fn delete_tenant(&self, tenant_id: &uuid::Uuid) -> Result<(), FindTenantError> {
+        let mut tables = self.memory.tables();
+        if tables.tenants.remove(tenant_id).is_none() {
+            return Err(FindTenantError::NotFound { id: *tenant_id });
+        }
+        // As the foreign keys of the databases cascade.
+        tables
+            .api_keys
+            .retain(|_, api_key| api_key.tenant_id != *tenant_id);
+        tables
+            .refresh_tokens
+            .retain(|(owner, _), _| owner != tenant_id);
+
+        info!("Deletion of the tenant: {}", tenant_id);
+
+        Ok(())
+    }
+
+    #[instrument(name = "api_keys.create", skip_all, fields(db.system = "memory", tenant_id = %tenant_id))]
+    async fn create_api_key(
+        &self,
+        tenant_id: &uuid::Uuid,
+        api_key_hash: &str,
+        scopes: &[Scope],
+    ) -> Result<ApiKeyInfo, FindTenantError> {
+        let info = ApiKeyInfo::new(
+            uuid::Uuid::new_v4(),
+            *tenant_id,
+            scopes.to_vec(),
+            OffsetDateTime::now_utc(),
+        );
+
+        let mut tables = self.memory.tables();
+        if !tables.tenants.contains_key(tenant_id) {
+            return Err(FindTenantError::NotFound { id: *tenant_id });
+        }
+        tables
+            .api_keys
+            .insert(api_key_hash.to_string(), info.clone());
+
+        Ok(info)
+    }
+
+    #[instrument(name = "api_keys.authenticate", skip_all, fields(db.system = "memory"))]
+    async fn find_by_api_key_hash(
+        &self,
+        api_key_hash: &str,
+    ) -> Result<(Tenant, ApiKeyInfo), AuthenticateTenantError> {
+        let tables = self.memory.tables();
+        let api_key = tables
+            .api_keys
+            .get(api_key_hash)
+            .ok_or(AuthenticateTenantError::InvalidApiKey)?;
+        let tenant = tables
+            .tenants
+            .get(&api_key.tenant_id)
+            .ok_or(AuthenticateTenantError::InvalidApiKey)?;
+
+        Ok((tenant.clone().into(), api_key.clone()))
+    }
+}
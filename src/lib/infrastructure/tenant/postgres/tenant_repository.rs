@@ -0,0 +1,212 @@
+use std::{str::FromStr, sync::Arc};
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::{
+    domain::tenant::{
+        models::{
+            api_key::{ApiKeyInfo, AuthenticateTenantError, Scope},
+            tenant::{
+                CreateTenantError, FindTenantError, ListTenantsError, Tenant, TenantName, TenantRow,
+            },
+        },
+        ports::tenant::TenantRepository,
+    },
+    infrastructure::db::postgres::Postgres,
+};
+
+#[derive(Debug, Clone)]
+pub struct PostgresTenantRepository {
+    postgres: Arc<Postgres>,
+}
+
+impl PostgresTenantRepository {
+    pub fn new(postgres: Arc<Postgres>) -> Self {
+        Self { postgres }
+    }
+}
+
+impl From<TenantRow> for Tenant {
+    fn from(row: TenantRow) -> Self {
+        Tenant::new(
+            row.id,
+            TenantName::new(&row.name).unwrap(),
+            row.created_at,
+            row.updated_at,
+        )
+    }
+}
+
+fn parse_scopes(scopes: Vec<String>) -> Vec<Scope> {
+    scopes
+        .iter()
+        .filter_map(|scope| Scope::from_str(scope).ok())
+        .collect()
+}
+
+fn format_scopes(scopes: &[Scope]) -> Vec<String> {
+    scopes
+        .iter()
+        .map(|scope| scope.as_str().to_string())
+        .collect()
+}
+
+impl TenantRepository for PostgresTenantRepository {
+    #[instrument(name = "tenants.create", skip_all, fields(db.system = "postgresql"))]
+    async fn create_tenant(
+        &self,
+        name: &str,
+        api_key_hash: &str,
+    ) -> Result<Tenant, CreateTenantError> {
+        let now = OffsetDateTime::now_utc();
+        let tenant = Tenant::new(
+            uuid::Uuid::new_v4(),
+            TenantName::new(name).unwrap(),
+            now,
+            now,
+        );
+
+        let mut transaction = self.postgres.get_pool().begin().await?;
+
+        sqlx::query!(
+            r#"INSERT INTO tenants (id, name, created_at, updated_at) VALUES ($1, $2, $3, $4)"#,
+            tenant.id,
+            tenant.name.as_str(),
+            tenant.created_at,
+            tenant.updated_at,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                CreateTenantError::Duplicate {
+                    name: tenant.name.clone(),
+                }
+            }
+            e => CreateTenantError::DatabaseError(e),
+        })?;
+
+        sqlx::query!(
+            r#"INSERT INTO api_keys (id, tenant_id, key_hash, created_at) VALUES ($1, $2, $3, $4)"#,
+            uuid::Uuid::new_v4(),
+            tenant.id,
+            api_key_hash,
+            now,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        info!("Creation of the tenant: {}", tenant.name);
+
+        Ok(tenant)
+    }
+
+    #[instrument(name = "tenants.list", skip_all, fields(db.system = "postgresql"))]
+    async fn list_tenants(&self) -> Result<Vec<Tenant>, ListTenantsError> {
+        let rows = sqlx::query_as!(
+            TenantRow,
+            r#"SELECT id, name, created_at, updated_at FROM tenants ORDER BY name"#,
+        )
+        .fetch_all(&*self.postgres.get_pool())
+        .await?;
+
+        Ok(rows.into_iter().map(Tenant::from).collect())
+    }
+
+    #[instrument(name = "tenants.find", skip_all, fields(db.system = "postgresql", tenant_id = %tenant_id))]
+    async fn find_tenant(&self, tenant_id: &uuid::Uuid) -> Result<Tenant, FindTenantError> {
+        sqlx::query_as!(
+            TenantRow,
+            r#"SELECT id, name, created_at, updated_at FROM tenants WHERE id=$1"#,
+            tenant_id,
+        )
+        .fetch_optional(&*self.postgres.get_pool())
+        .await?
+        .map(Tenant::from)
+        .ok_or(FindTenantError::NotFound { id: *tenant_id })
+    }
+
+    #[instrument(name = "tenants.delete", skip_all, fields(db.system = "postgresql", tenant_id = %tenant_id))]
+    async fn delete_tenant(&self, tenant_id: &uuid::Uuid) -> Result<(), FindTenantError> {
+        let result = sqlx::query!(r#"DELETE FROM tenants WHERE id=$1"#, tenant_id)
+            .execute(&*self.postgres.get_pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(FindTenantError::NotFound { id: *tenant_id });
+        }
+
+        info!("Deletion of the tenant: {}", tenant_id);
+
+        Ok(())
+    }
+
+    #[instrument(name = "api_keys.create", skip_all, fields(db.system = "postgresql", tenant_id = %tenant_id))]
+    async fn create_api_key(
+        &self,
+        tenant_id: &uuid::Uuid,
+        api_key_hash: &str,
+        scopes: &[Scope],
+    ) -> Result<ApiKeyInfo, FindTenantError> {
+        let info = ApiKeyInfo::new(
+            uuid::Uuid::new_v4(),
+            *tenant_id,
+            scopes.to_vec(),
+            OffsetDateTime::now_utc(),
+        );
+
+        sqlx::query!(
+            r#"INSERT INTO api_keys (id, tenant_id, key_hash, scopes, created_at) VALUES ($1, $2, $3, $4, $5)"#,
+            info.id,
+            info.tenant_id,
+            api_key_hash,
+            &format_scopes(&info.scopes),
+            info.created_at,
+        )
+        .execute(&*self.postgres.get_pool())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_error) if db_error.is_foreign_key_violation() => {
+                FindTenantError::NotFound { id: *tenant_id }
+            }
+            e => FindTenantError::DatabaseError(e),
+        })?;
+
+        Ok(info)
+    }
+
+    #[instrument(name = "api_keys.authenticate", skip_all, fields(db.system = "postgresql"))]
+    async fn find_by_api_key_hash(
+        &self,
+        api_key_hash: &str,
+    ) -> Result<(Tenant, ApiKeyInfo), AuthenticateTenantError> {
+        let row = sqlx::query!(
+            r#"SELECT t.id, t.name, t.created_at, t.updated_at,
+                      k.id AS key_id, k.scopes, k.created_at AS key_created_at
+               FROM tenants t
+               INNER JOIN api_keys k ON k.tenant_id = t.id
+               WHERE k.key_hash=$1"#,
+            api_key_hash,
+        )
+        .fetch_optional(&*self.postgres.get_pool())
+        .await?
+        .ok_or(AuthenticateTenantError::InvalidApiKey)?;
+
+        let api_key = ApiKeyInfo::new(
+            row.key_id,
+            row.id,
+            parse_scopes(row.scopes),
+            row.key_created_at,
+        );
+        let tenant = Tenant::from(TenantRow {
+            id: row.id,
+            name: row.name,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        });
+
+        Ok((tenant, api_key))
+    }
+}
@@ -0,0 +1,210 @@
+use std::{str::FromStr, sync::Arc};
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::{
+    domain::tenant::{
+        models::{
+            api_key::{ApiKeyInfo, AuthenticateTenantError, Scope},
+            tenant::{CreateTenantError, FindTenantError, ListTenantsError, Tenant, TenantRow},
+        },
+        ports::tenant::TenantRepository,
+    },
+    infrastructure::db::sqlite::Sqlite,
+};
+
+/// The queries are checked at runtime, the macros of sqlx only checking them
+/// against the Postgres database of `DATABASE_URL`.
+#[derive(Debug, Clone)]
+pub struct SqliteTenantRepository {
+    sqlite: Arc<Sqlite>,
+}
+
+impl SqliteTenantRepository {
+    pub fn new(sqlite: Arc<Sqlite>) -> Self {
+        Self { sqlite }
+    }
+}
+
+/// SQLite has no arrays, the scopes are stored as a JSON array.
+fn parse_scopes(scopes: &str) -> Vec<Scope> {
+    serde_json::from_str::<Vec<String>>(scopes)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|scope| Scope::from_str(scope).ok())
+        .collect()
+}
+
+fn format_scopes(scopes: &[Scope]) -> String {
+    serde_json::to_string(&scopes.iter().map(Scope::as_str).collect::<Vec<_>>()).unwrap()
+}
+
+#[derive(sqlx::FromRow)]
+struct ApiKeyRow {
+    id: uuid::Uuid,
+    name: String,
+    created_at: OffsetDateTime,
+    updated_at: OffsetDateTime,
+    key_id: uuid::Uuid,
+    scopes: String,
+    key_created_at: OffsetDateTime,
+}
+
+impl TenantRepository for SqliteTenantRepository {
+    #[instrument(name = "tenants.create", skip_all, fields(db.system = "sqlite"))]
+    async fn create_tenant(
+        &self,
+        name: &str,
+        api_key_hash: &str,
+    ) -> Result<Tenant, CreateTenantError> {
+        let now = OffsetDateTime::now_utc();
+        let tenant = Tenant::from(TenantRow {
+            id: uuid::Uuid::new_v4(),
+            name: name.to_string(),
+            created_at: now,
+            updated_at: now,
+        });
+
+        let mut transaction = self.sqlite.get_pool().begin().await?;
+
+        sqlx::query(
+            r#"INSERT INTO tenants (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)"#,
+        )
+        .bind(tenant.id)
+        .bind(tenant.name.as_str())
+        .bind(tenant.created_at)
+        .bind(tenant.updated_at)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                CreateTenantError::Duplicate {
+                    name: tenant.name.clone(),
+                }
+            }
+            e => CreateTenantError::DatabaseError(e),
+        })?;
+
+        sqlx::query(
+            r#"INSERT INTO api_keys (id, tenant_id, key_hash, created_at) VALUES (?1, ?2, ?3, ?4)"#,
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(tenant.id)
+        .bind(api_key_hash)
+        .bind(now)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        info!("Creation of the tenant: {}", tenant.name);
+
+        Ok(tenant)
+    }
+
+    #[instrument(name = "tenants.list", skip_all, fields(db.system = "sqlite"))]
+    async fn list_tenants(&self) -> Result<Vec<Tenant>, ListTenantsError> {
+        let rows = sqlx::query_as::<_, TenantRow>(
+            r#"SELECT id, name, created_at, updated_at FROM tenants ORDER BY name"#,
+        )
+        .fetch_all(&*self.sqlite.get_pool())
+        .await?;
+
+        Ok(rows.into_iter().map(Tenant::from).collect())
+    }
+
+    #[instrument(name = "tenants.find", skip_all, fields(db.system = "sqlite", tenant_id = %tenant_id))]
+    async fn find_tenant(&self, tenant_id: &uuid::Uuid) -> Result<Tenant, FindTenantError> {
+        sqlx::query_as::<_, TenantRow>(
+            r#"SELECT id, name, created_at, updated_at FROM tenants WHERE id=?1"#,
+        )
+        .bind(tenant_id)
+        .fetch_optional(&*self.sqlite.get_pool())
+        .await?
+        .map(Tenant::from)
+        .ok_or(FindTenantError::NotFound { id: *tenant_id })
+    }
+
+    #[instrument(name = "tenants.delete", skip_all, fields(db.system = "sqlite", tenant_id = %tenant_id))]
+    async fn delete_tenant(&self, tenant_id: &uuid::Uuid) -> Result<(), FindTenantError> {
+        let result = sqlx::query(r#"DELETE FROM tenants WHERE id=?1"#)
+            .bind(tenant_id)
+            .execute(&*self.sqlite.get_pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(FindTenantError::NotFound { id: *tenant_id });
+        }
+
+        info!("Deletion of the tenant: {}", tenant_id);
+
+        Ok(())
+    }
+
+    #[instrument(name = "api_keys.create", skip_all, fields(db.system = "sqlite", tenant_id = %tenant_id))]
+    async fn create_api_key(
+        &self,
+        tenant_id: &uuid::Uuid,
+        api_key_hash: &str,
+        scopes: &[Scope],
+    ) -> Result<ApiKeyInfo, FindTenantError> {
+        let info = ApiKeyInfo::new(
+            uuid::Uuid::new_v4(),
+            *tenant_id,
+            scopes.to_vec(),
+            OffsetDateTime::now_utc(),
+        );
+
+        sqlx::query(
+            r#"INSERT INTO api_keys (id, tenant_id, key_hash, scopes, created_at) VALUES (?1, ?2, ?3, ?4, ?5)"#,
+        )
+        .bind(info.id)
+        .bind(info.tenant_id)
+        .bind(api_key_hash)
+        .bind(format_scopes(&info.scopes))
+        .bind(info.created_at)
+        .execute(&*self.sqlite.get_pool())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_error) if db_error.is_foreign_key_violation() => {
+                FindTenantError::NotFound { id: *tenant_id }
+            }
+            e => FindTenantError::DatabaseError(e),
+        })?;
+
+        Ok(info)
+    }
+
+    #[instrument(name = "api_keys.authenticate", skip_all, fields(db.system = "sqlite"))]
+    async fn find_by_api_key_hash(
+        &self,
+        api_key_hash: &str,
+    ) -> Result<(Tenant, ApiKeyInfo), AuthenticateTenantError> {
+        let row = sqlx::query_as::<_, ApiKeyRow>(
+            r#"SELECT t.id, t.name, t.created_at, t.updated_at,
+                      k.id AS key_id, k.scopes, k.created_at AS key_created_at
+               FROM tenants t
+               INNER JOIN api_keys k ON k.tenant_id = t.id
+               WHERE k.key_hash=?1"#,
+        )
+        .bind(api_key_hash)
+        .fetch_optional(&*self.sqlite.get_pool())
+        .await?
+        .ok_or(AuthenticateTenantError::InvalidApiKey)?;
+
+        let api_key = ApiKeyInfo::new(
+            row.key_id,
+            row.id,
+            parse_scopes(&row.scopes),
+            row.key_created_at,
+        );
+        let tenant = Tenant::from(TenantRow {
+            id: row.id,
+            name: row.name,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        });
+
+        Ok((tenant, api_key))
+    }
+}
@@ -1,2 +1,4 @@
+pub mod memory;
 pub mod postgres;
 pub mod providers;
+pub mod sqlite;
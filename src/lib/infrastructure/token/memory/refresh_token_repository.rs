@@ -0,0 +1,210 @@
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::domain::token::models::refresh_token::{
+    DeleteRefreshTokenError, FindRefreshTokenError, ListRefreshTokensError, ListRefreshTokensQuery,
+    RefreshTokenPage, UpdateRefreshTokenError,
+};
+use crate::{
+    domain::token::{
+        models::{
+            refresh_token::{
+                CreateMode, CreateRefreshTokenError, CreateRefreshTokenOptions,
+                CreatedRefreshToken, IdempotencyKey, RefreshToken, RefreshTokenRow,
+            },
+            token::{SerialNumber, Tokens},
+        },
+        ports::{provider_token_service::ProviderType, refresh_token::RefreshTokenRepository},
+    },
+    infrastructure::db::memory::{InMemory, StoredRefreshToken},
+};
+
+#[derive(Debug, Clone)]
+pub struct InMemoryRefreshTokenRepository {
+    memory: Arc<InMemory>,
+}
+
+impl InMemoryRefreshTokenRepository {
+    pub fn new(memory: Arc<InMemory>) -> Self {
+        Self { memory }
+    }
+}
+
+fn not_found(serial_number: &str) -> FindRefreshTokenError {
+    FindRefreshTokenError::NotFound {
+        serial_number: serial_number.to_string(),
+    }
+}
+
+impl RefreshTokenRepository for InMemoryRefreshTokenRepository {
+    #[instrument(name = "refresh_tokens.create", skip_all, fields(db.system = "memory", tenant_id = %tenant_id, provider = %provider_type, serial_number = %serial_number))]
+    async fn create_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        provider_type: &ProviderType,
+        tokens: &Tokens,
+        serial_number: &SerialNumber,
+        options: &CreateRefreshTokenOptions,
+    ) -> Result<CreatedRefreshToken, CreateRefreshTokenError> {
+        let idempotency_key = options.idempotency_key.as_ref().map(IdempotencyKey::as_str);
+        let now = OffsetDateTime::now_utc();
+
+        let mut tables = self.memory.tables();
+        let key = (*tenant_id, serial_number.as_str().to_string());
+
+        if let Some(stored) = tables.refresh_tokens.get_mut(&key) {
+            let replaceable = options.mode == CreateMode::Replace
+                || (idempotency_key.is_some()
+                    && stored.idempotency_key.as_deref() == idempotency_key);
+            if !replaceable {
+                return Err(CreateRefreshTokenError::Duplicate {
+                    name: serial_number.clone(),
+                });
+            }
+
+            stored.row.provider = provider_type.as_str().to_string();
+            stored.row.token = tokens.refresh_token.expose().to_string();
+            stored.row.access_token = Some(tokens.access_token.expose().to_string());
+            stored.row.updated_at = now;
+            stored.row.expires_at = tokens.refresh_token_expires_at;
+            if let Some(idempotency_key) = idempotency_key {
+                stored.idempotency_key = Some(idempotency_key.to_string());
+            }
+
+            info!(
+                "Replacement of the refresh token for the next serial_number: {}",
+                serial_number
+            );
+            return Ok(CreatedRefreshToken::Replaced(
+                stored.row.clone().try_into()?,
+            ));
+        }
+
+        let row = RefreshTokenRow {
+            id: uuid::Uuid::new_v4(),
+            tenant_id: *tenant_id,
+            provider: provider_type.as_str().to_string(),
+            serial_number: serial_number.as_str().to_string(),
+            token: tokens.refresh_token.expose().to_string(),
+            access_token: Some(tokens.access_token.expose().to_string()),
+            created_at: now,
+            updated_at: now,
+            expires_at: tokens.refresh_token_expires_at,
+        };
+        tables.refresh_tokens.insert(
+            key,
+            StoredRefreshToken {
+                row: row.clone(),
+                idempotency_key: idempotency_key.map(str::to_string),
+            },
+        );
+
+        info!(
+            "Creation of a refresh token for the next serial_number: {}",
+            serial_number
+        );
+        Ok(CreatedRefreshToken::Created(row.try_into()?))
+    }
+
+    #[instrument(name = "refresh_tokens.find", skip_all, fields(db.system = "memory", tenant_id = %tenant_id, serial_number = %serial_number))]
+    async fn find_by_serial_number(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> Result<RefreshToken, FindRefreshTokenError> {
+        let row = self
+            .memory
+            .tables()
+            .refresh_tokens
+            .get(&(*tenant_id, serial_number.to_string()))
+            .map(|stored| stored.row.clone())
+            .ok_or_else(|| not_found(serial_number))?;
+
+        Ok(row.try_into()?)
+    }
+
+    #[instrument(name = "refresh_tokens.update", skip_all, fields(db.system = "memory", tenant_id = %tenant_id, serial_number = %serial_number))]
+    async fn update_tokens(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+        tokens: &Tokens,
+    ) -> Result<RefreshToken, UpdateRefreshTokenError> {
+        let mut tables = self.memory.tables();
+        let stored = tables
+            .refresh_tokens
+            .get_mut(&(*tenant_id, serial_number.to_string()))
+            .ok_or_else(|| not_found(serial_number))?;
+
+        stored.row.token = tokens.refresh_token.expose().to_string();
+        stored.row.access_token = Some(tokens.access_token.expose().to_string());
+        stored.row.expires_at = tokens.refresh_token_expires_at;
+        stored.row.updated_at = OffsetDateTime::now_utc();
+
+        info!(
+            "Renewal of the refresh token for the next serial_number: {}",
+            serial_number
+        );
+
+        Ok(stored.row.clone().try_into()?)
+    }
+
+    #[instrument(name = "refresh_tokens.list", skip_all, fields(db.system = "memory", tenant_id = %tenant_id))]
+    async fn list_refresh_tokens(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListRefreshTokensQuery,
+    ) -> Result<RefreshTokenPage, ListRefreshTokensError> {
+        let tables = self.memory.tables();
+        let matching: Vec<&RefreshTokenRow> = tables
+            .refresh_tokens
+            .range((*tenant_id, String::new())..)
+            .take_while(|((owner, _), _)| owner == tenant_id)
+            .map(|(_, stored)| &stored.row)
+            .filter(|row| {
+                query
+                    .provider
+                    .as_ref()
+                    .is_none_or(|provider| row.provider == provider.as_str())
+            })
+            .filter(|row| {
+                query
+                    .serial_number
+                    .as_ref()
+                    .is_none_or(|serial_number| row.serial_number == serial_number.as_str())
+            })
+            .collect();
+
+        Ok(RefreshTokenPage {
+            total: matching.len() as u64,
+            items: matching
+                .into_iter()
+                .skip(query.offset as usize)
+                .take(query.limit as usize)
+                .map(|row| row.clone().try_into())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    #[instrument(name = "refresh_tokens.delete", skip_all, fields(db.system = "memory", tenant_id = %tenant_id, serial_number = %serial_number))]
+    async fn delete_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> Result<RefreshToken, DeleteRefreshTokenError> {
+        let stored = self
+            .memory
+            .tables()
+            .refresh_tokens
+            .remove(&(*tenant_id, serial_number.to_string()))
+            .ok_or_else(|| not_found(serial_number))?;
+
+        info!(
+            "Deletion of the refresh token for the next serial_number: {}",
+            serial_number
+        );
+
+        Ok(stored.row.try_into()?)
+    }
+}
@@ -1,15 +1,21 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 use time::OffsetDateTime;
-use tracing::info;
+use tracing::{info, instrument};
 
-use crate::domain::token::models::refresh_token::FindRefreshTokenError;
+use crate::domain::token::models::refresh_token::{
+    DeleteRefreshTokenError, FindRefreshTokenError, ListRefreshTokensError, ListRefreshTokensQuery,
+    RefreshTokenPage, UpdateRefreshTokenError,
+};
 use crate::{
     domain::token::{
         models::{
-            refresh_token::{CreateRefreshTokenError, RefreshToken, RefreshTokenRow},
-            token::{SerialNumber, Token},
+            refresh_token::{
+                CreateMode, CreateRefreshTokenError, CreateRefreshTokenOptions,
+                CreatedRefreshToken, IdempotencyKey, RefreshToken, RefreshTokenRow,
+            },
+            token::{SerialNumber, Token, Tokens},
         },
-        ports::refresh_token::RefreshTokenRepository,
+        ports::{provider_token_service::ProviderType, refresh_token::RefreshTokenRepository},
     },
     infrastructure::db::postgres::Postgres,
 };
@@ -25,62 +31,229 @@ impl PostgresRefreshTokenRepository {
     }
 }
 
+/// Shared by every backend. A row that no longer makes a valid token, such as
+/// one edited by hand, fails to decode rather than panicking the request.
+impl TryFrom<RefreshTokenRow> for RefreshToken {
+    type Error = sqlx::Error;
+
+    fn try_from(row: RefreshTokenRow) -> Result<Self, Self::Error> {
+        let provider =
+            ProviderType::from_str(&row.provider).map_err(|e| sqlx::Error::Decode(e.into()))?;
+        let token = Token::new(&row.token).map_err(|e| sqlx::Error::Decode(e.into()))?;
+
+        Ok(RefreshToken::new(
+            row.id,
+            row.tenant_id,
+            provider,
+            SerialNumber::from_stored(&row.serial_number),
+            token,
+            row.created_at,
+            row.updated_at,
+        )
+        .with_access_token(row.access_token.and_then(|token| Token::new(&token).ok()))
+        .with_expires_at(row.expires_at))
+    }
+}
+
 impl RefreshTokenRepository for PostgresRefreshTokenRepository {
+    #[instrument(name = "refresh_tokens.create", skip_all, fields(db.system = "postgresql", tenant_id = %tenant_id, provider = %provider_type, serial_number = %serial_number))]
     async fn create_refresh_token(
         &self,
-        token: &str,
+        tenant_id: &uuid::Uuid,
+        provider_type: &ProviderType,
+        tokens: &Tokens,
+        serial_number: &SerialNumber,
+        options: &CreateRefreshTokenOptions,
+    ) -> Result<CreatedRefreshToken, CreateRefreshTokenError> {
+        let now = OffsetDateTime::now_utc();
+
+        // A single statement, so the existing token is either replaced as a whole
+        // or left untouched. The conflicting row is only updated when replacing is
+        // allowed; otherwise nothing is returned and the creation is a duplicate.
+        let row = sqlx::query!(
+            r#"INSERT INTO refresh_tokens (id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at, idempotency_key)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $7, $8, $9)
+               ON CONFLICT (tenant_id, serial_number) DO UPDATE
+               SET provider=EXCLUDED.provider, token=EXCLUDED.token, access_token=EXCLUDED.access_token,
+                   updated_at=EXCLUDED.updated_at, expires_at=EXCLUDED.expires_at,
+                   idempotency_key=COALESCE(EXCLUDED.idempotency_key, refresh_tokens.idempotency_key)
+               WHERE $10 OR refresh_tokens.idempotency_key = EXCLUDED.idempotency_key
+               RETURNING id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at, (xmax = 0) AS "inserted!""#,
+            uuid::Uuid::new_v4(),
+            tenant_id,
+            provider_type.as_str(),
+            serial_number.as_str(),
+            tokens.refresh_token.expose(),
+            tokens.access_token.expose(),
+            now,
+            tokens.refresh_token_expires_at,
+            options.idempotency_key.as_ref().map(IdempotencyKey::as_str),
+            options.mode == CreateMode::Replace,
+        )
+        .fetch_optional(&*self.postgres.get_pool())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                CreateRefreshTokenError::Duplicate {
+                    name: serial_number.clone(),
+                }
+            }
+            e => CreateRefreshTokenError::DatabaseError(e),
+        })?
+        .ok_or_else(|| CreateRefreshTokenError::Duplicate {
+            name: serial_number.clone(),
+        })?;
+
+        let refresh_token: RefreshToken = RefreshTokenRow {
+            id: row.id,
+            tenant_id: row.tenant_id,
+            provider: row.provider,
+            serial_number: row.serial_number,
+            token: row.token,
+            access_token: row.access_token,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            expires_at: row.expires_at,
+        }
+        .try_into()?;
+
+        if row.inserted {
+            info!(
+                "Creation of a refresh token for the next serial_number: {}",
+                serial_number
+            );
+            Ok(CreatedRefreshToken::Created(refresh_token))
+        } else {
+            info!(
+                "Replacement of the refresh token for the next serial_number: {}",
+                serial_number
+            );
+            Ok(CreatedRefreshToken::Replaced(refresh_token))
+        }
+    }
+
+    #[instrument(name = "refresh_tokens.find", skip_all, fields(db.system = "postgresql", tenant_id = %tenant_id, serial_number = %serial_number))]
+    async fn find_by_serial_number(
+        &self,
+        tenant_id: &uuid::Uuid,
         serial_number: &str,
-    ) -> Result<RefreshToken, CreateRefreshTokenError> {
-        let uuid: uuid::Uuid = uuid::Uuid::new_v4();
+    ) -> Result<RefreshToken, FindRefreshTokenError> {
+        let row = sqlx::query_as!(
+            RefreshTokenRow,
+            r#"SELECT id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at FROM refresh_tokens WHERE tenant_id=$1 AND serial_number=$2"#,
+            tenant_id,
+            serial_number,
+        )
+        .fetch_optional(&*self.postgres.get_pool())
+        .await?
+        .ok_or_else(|| FindRefreshTokenError::NotFound {
+            serial_number: serial_number.to_string(),
+        })?;
 
-        let refresh_token = RefreshToken::new(
-            uuid,
-            SerialNumber::new(serial_number).unwrap(),
-            Token::new(token).unwrap(),
-            OffsetDateTime::now_utc(),
-            OffsetDateTime::now_utc(),
-        );
+        Ok(row.try_into()?)
+    }
 
-        sqlx::query_as!(
-            RefreshToken,
-            r#"INSERT INTO refresh_tokens (id, serial_number, token, created_at, updated_at) VALUES ($1, $2, $3, $4, $5)"#,
-            refresh_token.id,
-            refresh_token.serial_number.as_str(),
-            refresh_token.token.as_str(),
-            refresh_token.created_at,
-            refresh_token.updated_at,
-        ).execute(&*self.postgres.get_pool())
-        .await?;
+    #[instrument(name = "refresh_tokens.update", skip_all, fields(db.system = "postgresql", tenant_id = %tenant_id, serial_number = %serial_number))]
+    async fn update_tokens(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+        tokens: &Tokens,
+    ) -> Result<RefreshToken, UpdateRefreshTokenError> {
+        let row = sqlx::query_as!(
+            RefreshTokenRow,
+            r#"UPDATE refresh_tokens SET token=$3, access_token=$4, expires_at=$5, updated_at=$6 WHERE tenant_id=$1 AND serial_number=$2
+               RETURNING id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at"#,
+            tenant_id,
+            serial_number,
+            tokens.refresh_token.expose(),
+            tokens.access_token.expose(),
+            tokens.refresh_token_expires_at,
+            OffsetDateTime::now_utc(),
+        )
+        .fetch_optional(&*self.postgres.get_pool())
+        .await?
+        .ok_or_else(|| FindRefreshTokenError::NotFound {
+            serial_number: serial_number.to_string(),
+        })?;
 
         info!(
-            "Creation of a refresh token for the next serial_number: {}",
+            "Renewal of the refresh token for the next serial_number: {}",
             serial_number
         );
 
-        Ok(refresh_token)
+        Ok(row.try_into()?)
     }
 
-    async fn find_by_serial_number(
+    #[instrument(name = "refresh_tokens.list", skip_all, fields(db.system = "postgresql", tenant_id = %tenant_id))]
+    async fn list_refresh_tokens(
         &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListRefreshTokensQuery,
+    ) -> Result<RefreshTokenPage, ListRefreshTokensError> {
+        let provider = query.provider.as_ref().map(|provider| provider.as_str());
+        let serial_number = query
+            .serial_number
+            .as_ref()
+            .map(|serial_number| serial_number.as_str());
+
+        let rows = sqlx::query_as!(
+            RefreshTokenRow,
+            r#"SELECT id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at FROM refresh_tokens
+               WHERE tenant_id=$1 AND ($2::TEXT IS NULL OR provider=$2) AND ($3::TEXT IS NULL OR serial_number=$3)
+               ORDER BY serial_number LIMIT $4 OFFSET $5"#,
+            tenant_id,
+            provider,
+            serial_number,
+            i64::from(query.limit),
+            i64::from(query.offset),
+        )
+        .fetch_all(&*self.postgres.get_pool())
+        .await?;
+
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "total!" FROM refresh_tokens
+               WHERE tenant_id=$1 AND ($2::TEXT IS NULL OR provider=$2) AND ($3::TEXT IS NULL OR serial_number=$3)"#,
+            tenant_id,
+            provider,
+            serial_number,
+        )
+        .fetch_one(&*self.postgres.get_pool())
+        .await?;
+
+        Ok(RefreshTokenPage {
+            items: rows
+                .into_iter()
+                .map(RefreshToken::try_from)
+                .collect::<Result<_, _>>()?,
+            total: total as u64,
+        })
+    }
+
+    #[instrument(name = "refresh_tokens.delete", skip_all, fields(db.system = "postgresql", tenant_id = %tenant_id, serial_number = %serial_number))]
+    async fn delete_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
         serial_number: &str,
-    ) -> Result<RefreshToken, FindRefreshTokenError> {
+    ) -> Result<RefreshToken, DeleteRefreshTokenError> {
         let row = sqlx::query_as!(
             RefreshTokenRow,
-            r#"SELECT id, serial_number, token, created_at, updated_at FROM refresh_tokens WHERE serial_number=$1"#,
+            r#"DELETE FROM refresh_tokens WHERE tenant_id=$1 AND serial_number=$2
+               RETURNING id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at"#,
+            tenant_id,
             serial_number,
-        ).fetch_one(&*self.postgres.get_pool()).await
-            .map_err(|_| FindRefreshTokenError::NotFound { serial_number: SerialNumber::new(serial_number).unwrap() });
+        )
+        .fetch_optional(&*self.postgres.get_pool())
+        .await?
+        .ok_or_else(|| FindRefreshTokenError::NotFound {
+            serial_number: serial_number.to_string(),
+        })?;
 
-        let row = row.unwrap();
-
-        let refresh_token = RefreshToken::new(
-            row.id,
-            SerialNumber::new(&row.serial_number).unwrap(),
-            Token::new(&row.token).unwrap(),
-            row.created_at,
-            row.updated_at,
+        info!(
+            "Deletion of the refresh token for the next serial_number: {}",
+            serial_number
         );
 
-        Ok(refresh_token)
+        Ok(row.try_into()?)
     }
 }
@@ -1 +1,2 @@
 pub mod bambulab_provider;
+pub mod fake_provider;
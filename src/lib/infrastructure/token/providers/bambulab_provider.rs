@@ -1,32 +1,44 @@
-use reqwest::{
-    header::{CONTENT_TYPE, USER_AGENT},
-    Client,
-};
+use reqwest::{header::CONTENT_TYPE, Method, StatusCode};
 use serde::{Deserialize, Serialize};
-
-use crate::domain::token::{
-    models::{
-        refresh_token,
-        token::{CreateTokensError, Token, Tokens},
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    application::telemetry,
+    domain::health::models::health::CircuitState,
+    domain::secret::serialize_exposed,
+    domain::token::{
+        models::token::{CreateTokensError, Password, Token, Tokens},
+        ports::provider_token_service::ProviderTokenService,
     },
-    ports::provider_token_service::ProviderTokenService,
+    infrastructure::resilience::{ResilienceError, ResiliencePolicy, ResilientClient},
 };
 
 #[derive(Debug, Clone)]
 pub struct BambuLabProviderTokenService {
-    http_client: Client,
-    #[allow(dead_code)]
+    http_client: ResilientClient,
     api_url: String,
     login_url: String,
 }
 
 impl BambuLabProviderTokenService {
-    pub fn new(api_url: String, login_url: String) -> Self {
-        Self {
-            http_client: Client::new(),
+    pub fn new(api_url: String, login_url: String) -> reqwest::Result<Self> {
+        Ok(Self {
+            http_client: ResilientClient::new("bambulab", ResiliencePolicy::default())?,
             login_url,
             api_url,
-        }
+        })
+    }
+
+    /// Replaces the default client, typically by one shared with the
+    /// [crate::infrastructure::proxy::bambulab_api_client::BambuLabApiClient]
+    /// so that both trip the same circuit.
+    pub fn with_http_client(mut self, http_client: ResilientClient) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    fn unavailable(e: ResilienceError) -> CreateTokensError {
+        CreateTokensError::Unavailable(e.to_string())
     }
 
     fn extract_token_from_cookie(
@@ -41,17 +53,73 @@ impl BambuLabProviderTokenService {
             .find(|cookie| cookie.name() == cookie_name)
             .map(|cookie| cookie.value().to_string())
     }
+
+    fn extract_cookie_expiry(
+        headers: &reqwest::header::HeaderMap,
+        cookie_name: &str,
+    ) -> Option<OffsetDateTime> {
+        headers
+            .get_all(reqwest::header::SET_COOKIE)
+            .iter()
+            .filter_map(|header| header.to_str().ok())
+            .filter_map(|cookie_str| cookie::Cookie::parse(cookie_str).ok())
+            .find(|cookie| cookie.name() == cookie_name)
+            .and_then(|cookie| {
+                cookie
+                    .max_age()
+                    .map(|max_age| OffsetDateTime::now_utc() + max_age)
+                    .or_else(|| cookie.expires_datetime())
+            })
+    }
+
+    /// Maps the error statuses shared by the provider's endpoints, `rejected`
+    /// being the error for requests refused because of what they carry.
+    fn check_status(
+        status: StatusCode,
+        rejected: CreateTokensError,
+    ) -> Result<(), CreateTokensError> {
+        match status {
+            status if status.is_success() => Ok(()),
+            StatusCode::TOO_MANY_REQUESTS => Err(CreateTokensError::RateLimited),
+            status if status.is_server_error() => Err(CreateTokensError::Unavailable(format!(
+                "the provider answered with {}",
+                status
+            ))),
+            StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(rejected)
+            }
+            status => Err(CreateTokensError::ProviderError(format!(
+                "unexpected status {}",
+                status
+            ))),
+        }
+    }
+
+    fn parse_token(value: &str) -> Result<Token, CreateTokensError> {
+        Token::new(value).map_err(|_| {
+            CreateTokensError::ProviderError("the provider returned an empty token".into())
+        })
+    }
 }
 
 #[derive(Serialize)]
-struct AuthPayload {
+struct AuthPayload<'a> {
     account: String,
-    password: String,
+    #[serde(serialize_with = "serialize_exposed")]
+    password: &'a Password,
 }
 
 #[derive(Serialize)]
-struct RefreshTokenRequestPayload {
-    refresh_token: String,
+struct RefreshTokenRequestPayload<'a> {
+    #[serde(serialize_with = "serialize_exposed")]
+    refresh_token: &'a Token,
+}
+
+/// Body of a sign-in that did not issue tokens; `loginType` tells why.
+#[derive(Debug, Default, Deserialize)]
+struct SignInResponse {
+    #[serde(rename = "loginType", default)]
+    login_type: String,
 }
 
 #[derive(Serialize, Debug, Deserialize)]
@@ -60,75 +128,123 @@ struct RefreshTokenResponse {
     refresh_token: String,
     #[serde(rename = "accessToken")]
     access_token: String,
+    #[serde(rename = "refreshExpiresIn", default)]
+    refresh_expires_in: Option<i64>,
 }
 
 impl ProviderTokenService for BambuLabProviderTokenService {
     async fn authenticate(
         &self,
         username: String,
-        password: String,
+        password: Password,
     ) -> Result<Tokens, CreateTokensError> {
         let payload = AuthPayload {
             account: username,
-            password,
+            password: &password,
         };
 
-        let response = self
+        let request = self
             .http_client
-            .post(&self.login_url)
-            .header(USER_AGENT, "ferris-printer")
+            .request(Method::POST, &self.login_url)
             .header(CONTENT_TYPE, "application/json")
-            .json(&payload)
-            .send()
+            .headers(telemetry::trace_context_headers())
+            .json(&payload);
+        // Not retried: a sign-in whose response was lost may still have
+        // counted against the account's lockout or sent a verification code.
+        let response = self
+            .http_client
+            .send(request, false)
             .await
-            .map_err(|_| CreateTokensError::ProviderError)?;
+            .map_err(Self::unavailable)?;
+        Self::check_status(response.status(), CreateTokensError::InvalidCredentials)?;
 
         let headers = response.headers();
+        let refresh_token_expires_at =
+            BambuLabProviderTokenService::extract_cookie_expiry(headers, "refreshToken");
         let refresh_token =
-            BambuLabProviderTokenService::extract_token_from_cookie(headers, "refreshToken")
-                .ok_or(CreateTokensError::ProviderError)?;
+            BambuLabProviderTokenService::extract_token_from_cookie(headers, "refreshToken");
         let access_token =
-            BambuLabProviderTokenService::extract_token_from_cookie(headers, "token")
-                .ok_or(CreateTokensError::ProviderError)?;
-
-        let refresh_token =
-            Token::new(&refresh_token).map_err(|_| CreateTokensError::InvalidToken)?;
-        let access_token =
-            Token::new(&access_token).map_err(|_| CreateTokensError::InvalidToken)?;
+            BambuLabProviderTokenService::extract_token_from_cookie(headers, "token");
+
+        let (Some(refresh_token), Some(access_token)) = (refresh_token, access_token) else {
+            // Accounts with email verification or two-factor authentication
+            // enabled get a successful response without any token.
+            let body: SignInResponse = response.json().await.unwrap_or_default();
+            return Err(match body.login_type.as_str() {
+                "verifyCode" | "tfa" => CreateTokensError::VerificationRequired,
+                _ => CreateTokensError::ProviderError(
+                    "the sign-in response carried no tokens".into(),
+                ),
+            });
+        };
 
         Ok(Tokens {
-            access_token,
-            refresh_token,
+            access_token: Self::parse_token(&access_token)?,
+            refresh_token: Self::parse_token(&refresh_token)?,
+            refresh_token_expires_at,
         })
     }
 
-    async fn renew_tokens(&self, refresh_token: String) -> Result<Tokens, CreateTokensError> {
+    async fn renew_tokens(&self, refresh_token: &Token) -> Result<Tokens, CreateTokensError> {
         let uri = format!("{}/v1/user-service/user/refreshtoken", self.api_url);
 
         let payload = RefreshTokenRequestPayload { refresh_token };
 
-        let response = self
+        let request = self
             .http_client
-            .post(&uri)
-            .header(USER_AGENT, "ferris-printer")
+            .request(Method::POST, &uri)
             .header(CONTENT_TYPE, "application/json")
-            .json(&payload)
-            .send()
+            .headers(telemetry::trace_context_headers())
+            .json(&payload);
+        // Not retried: the provider may have rotated the refresh token even
+        // though its response was lost.
+        let response = self
+            .http_client
+            .send(request, false)
             .await
-            .map_err(|_| CreateTokensError::InvalidToken)?;
+            .map_err(Self::unavailable)?;
+        Self::check_status(response.status(), CreateTokensError::InvalidToken)?;
 
-        let response_result: RefreshTokenResponse = response.json().await.unwrap();
+        let response_result: RefreshTokenResponse = response
+            .json()
+            .await
+            .map_err(|e| CreateTokensError::ProviderError(e.without_url().to_string()))?;
 
-        let refresh_token = Token::new(&response_result.refresh_token)
-            .map_err(|_| CreateTokensError::InvalidToken)?;
-        let access_token = Token::new(&response_result.access_token)
-            .map_err(|_| CreateTokensError::InvalidToken)?;
+        let refresh_token = Self::parse_token(&response_result.refresh_token)?;
+        let access_token = Self::parse_token(&response_result.access_token)?;
+        let refresh_token_expires_at = response_result
+            .refresh_expires_in
+            .map(|seconds| OffsetDateTime::now_utc() + Duration::seconds(seconds));
 
         Ok(Tokens {
             access_token,
             refresh_token,
+            refresh_token_expires_at,
         })
     }
+
+    async fn check_reachability(&self) -> Result<(), CreateTokensError> {
+        let request = self.http_client.request(Method::GET, &self.api_url);
+        let response = self
+            .http_client
+            .probe(request)
+            .await
+            .map_err(Self::unavailable)?;
+
+        // Any answer but a server error means the API is up, the root path
+        // itself not being a documented endpoint.
+        match response.status() {
+            status if status.is_server_error() => Err(CreateTokensError::Unavailable(format!(
+                "the provider answered with {}",
+                status
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn circuit_state(&self) -> Option<CircuitState> {
+        Some(self.http_client.circuit_state())
+    }
 }
 
 #[cfg(test)]
@@ -137,7 +253,10 @@ mod tests {
     use reqwest::header::{HeaderValue, SET_COOKIE};
 
     use super::BambuLabProviderTokenService;
-    use crate::domain::token::ports::provider_token_service::ProviderTokenService;
+    use crate::domain::token::{
+        models::token::{CreateTokensError, Password, Token},
+        ports::provider_token_service::ProviderTokenService,
+    };
 
     fn mock_headers_with_cookies() -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
@@ -160,7 +279,6 @@ mod tests {
     #[tokio::test]
     async fn test_extract_token_from_cookie_success() {
         let headers = mock_headers_with_cookies();
-        println!("Headers: {:?}", headers);
 
         let refresh_token =
             BambuLabProviderTokenService::extract_token_from_cookie(&headers, "refreshToken");
@@ -196,22 +314,108 @@ mod tests {
                 .header("Content-Type", "application/json");
 
             then.status(200)
-                .header("set-cookie", "refreshToken=mock_refresh_token; HttpOnly")
+                .header(
+                    "set-cookie",
+                    "refreshToken=mock_refresh_token; Max-Age=7776000; HttpOnly",
+                )
                 .header("set-cookie", "token=mock_access_token; HttpOnly")
                 .json_body("{}"); // Simulate empty JSON body
         });
         let service =
-            BambuLabProviderTokenService::new(server.url("/"), server.url("/api/sign-in/form"));
+            BambuLabProviderTokenService::new(server.url("/"), server.url("/api/sign-in/form"))
+                .unwrap();
 
         let result = service
-            .authenticate("test".to_string(), "test".to_string())
+            .authenticate("test".to_string(), Password::new("test").unwrap())
             .await;
 
         assert!(result.is_ok());
         let tokens = result.unwrap();
-        assert_eq!(tokens.refresh_token.as_str(), "mock_refresh_token");
-        assert_eq!(tokens.access_token.as_str(), "mock_access_token");
+        assert_eq!(tokens.refresh_token.expose(), "mock_refresh_token");
+        assert_eq!(tokens.access_token.expose(), "mock_access_token");
+        assert!(tokens.refresh_token_expires_at.is_some());
 
         mock.assert();
     }
+
+    #[tokio::test]
+    async fn test_authenticate_requires_verification() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method("POST").path("/api/sign-in/form");
+            then.status(200)
+                .json_body(serde_json::json!({ "loginType": "verifyCode" }));
+        });
+        let service =
+            BambuLabProviderTokenService::new(server.url(""), server.url("/api/sign-in/form"))
+                .unwrap();
+
+        let result = service
+            .authenticate("test".to_string(), Password::new("test").unwrap())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateTokensError::VerificationRequired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_renew_tokens_maps_provider_statuses() {
+        let server = MockServer::start();
+        let mut mock = server.mock(|when, then| {
+            when.method("POST")
+                .path("/v1/user-service/user/refreshtoken");
+            then.status(429);
+        });
+        let service =
+            BambuLabProviderTokenService::new(server.url(""), server.url("/api/sign-in/form"))
+                .unwrap();
+
+        let result = service
+            .renew_tokens(&Token::new("refresh_token").unwrap())
+            .await;
+        assert!(matches!(result, Err(CreateTokensError::RateLimited)));
+
+        mock.delete();
+        server.mock(|when, then| {
+            when.method("POST")
+                .path("/v1/user-service/user/refreshtoken");
+            then.status(401);
+        });
+
+        let result = service
+            .renew_tokens(&Token::new("refresh_token").unwrap())
+            .await;
+        assert!(matches!(result, Err(CreateTokensError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_and_renewal_are_not_retried() {
+        let server = MockServer::start();
+        let sign_in = server.mock(|when, then| {
+            when.method("POST").path("/api/sign-in/form");
+            then.status(502);
+        });
+        let renewal = server.mock(|when, then| {
+            when.method("POST")
+                .path("/v1/user-service/user/refreshtoken");
+            then.status(502);
+        });
+        let service =
+            BambuLabProviderTokenService::new(server.url(""), server.url("/api/sign-in/form"))
+                .unwrap();
+
+        let result = service
+            .authenticate("test".to_string(), Password::new("test").unwrap())
+            .await;
+        assert!(matches!(result, Err(CreateTokensError::Unavailable(_))));
+        sign_in.assert_hits(1);
+
+        let result = service
+            .renew_tokens(&Token::new("refresh_token").unwrap())
+            .await;
+        assert!(matches!(result, Err(CreateTokensError::Unavailable(_))));
+        renewal.assert_hits(1);
+    }
 }
@@ -0,0 +1,189 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use time::{Duration, OffsetDateTime};
+
+use crate::domain::token::{
+    models::token::{CreateTokensError, Password, Token, Tokens},
+    ports::provider_token_service::ProviderTokenService,
+};
+
+/// A provider issuing made-up tokens without calling anything, for the tests
+/// and the demo mode. It accepts any credentials unless configured otherwise,
+/// and only renews the refresh tokens it issued and did not revoke.
+///
+/// Clones share the tokens issued and the calls counted.
+#[derive(Debug, Clone, Default)]
+pub struct FakeProviderTokenService {
+    credentials: Option<(String, String)>,
+    error: Option<CreateTokensError>,
+    refresh_token_lifetime: Option<Duration>,
+    state: Arc<FakeProviderState>,
+}
+
+#[derive(Debug, Default)]
+struct FakeProviderState {
+    issued: AtomicU64,
+    logins: AtomicU64,
+    renewals: AtomicU64,
+    revoked: Mutex<HashSet<String>>,
+}
+
+impl FakeProviderTokenService {
+    const REFRESH_TOKEN_PREFIX: &'static str = "fake-refresh-token-";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only these credentials are accepted, the others being rejected with
+    /// [CreateTokensError::InvalidCredentials].
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    /// Every login and renewal fails with `error`, such as
+    /// [CreateTokensError::Unavailable] to act as an outage.
+    pub fn failing_with(mut self, error: CreateTokensError) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    /// The refresh tokens issued expire after `lifetime`, they never do by
+    /// default.
+    pub fn with_refresh_token_lifetime(mut self, lifetime: Duration) -> Self {
+        self.refresh_token_lifetime = Some(lifetime);
+        self
+    }
+
+    /// Number of logins attempted, rejected ones included.
+    pub fn logins(&self) -> u64 {
+        self.state.logins.load(Ordering::Relaxed)
+    }
+
+    /// Number of renewals attempted, rejected ones included.
+    pub fn renewals(&self) -> u64 {
+        self.state.renewals.load(Ordering::Relaxed)
+    }
+
+    fn issue(&self) -> Tokens {
+        let n = self.state.issued.fetch_add(1, Ordering::Relaxed) + 1;
+
+        Tokens {
+            access_token: Token::new(&format!("fake-access-token-{}", n)).unwrap(),
+            refresh_token: Token::new(&format!("{}{}", Self::REFRESH_TOKEN_PREFIX, n)).unwrap(),
+            refresh_token_expires_at: self
+                .refresh_token_lifetime
+                .map(|lifetime| OffsetDateTime::now_utc() + lifetime),
+        }
+    }
+
+    fn revoked(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.state
+            .revoked
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl ProviderTokenService for FakeProviderTokenService {
+    async fn authenticate(
+        &self,
+        username: String,
+        password: Password,
+    ) -> Result<Tokens, CreateTokensError> {
+        self.state.logins.fetch_add(1, Ordering::Relaxed);
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        if let Some((expected_username, expected_password)) = &self.credentials {
+            if username != *expected_username || password.expose() != expected_password {
+                return Err(CreateTokensError::InvalidCredentials);
+            }
+        }
+
+        Ok(self.issue())
+    }
+
+    async fn renew_tokens(&self, refresh_token: &Token) -> Result<Tokens, CreateTokensError> {
+        self.state.renewals.fetch_add(1, Ordering::Relaxed);
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        let refresh_token = refresh_token.expose();
+        if !refresh_token.starts_with(Self::REFRESH_TOKEN_PREFIX)
+            || self.revoked().contains(refresh_token)
+        {
+            return Err(CreateTokensError::InvalidToken);
+        }
+
+        Ok(self.issue())
+    }
+
+    async fn revoke_tokens(&self, refresh_token: &Token) -> Result<(), CreateTokensError> {
+        self.revoked().insert(refresh_token.expose().to_string());
+        Ok(())
+    }
+
+    async fn check_reachability(&self) -> Result<(), CreateTokensError> {
+        match &self.error {
+            Some(error) => Err(error.clone()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FakeProviderTokenService;
+    use crate::domain::token::{
+        models::token::{CreateTokensError, Password},
+        ports::provider_token_service::ProviderTokenService,
+    };
+
+    #[tokio::test]
+    async fn test_only_the_configured_credentials_are_accepted() {
+        let provider = FakeProviderTokenService::new().with_credentials("maker", "secret");
+
+        let error = provider
+            .authenticate("maker".to_string(), Password::new("wrong").unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, CreateTokensError::InvalidCredentials));
+
+        let tokens = provider
+            .authenticate("maker".to_string(), Password::new("secret").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(tokens.refresh_token.expose(), "fake-refresh-token-1");
+        assert_eq!(provider.clone().logins(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_tokens_are_not_renewed() {
+        let provider = FakeProviderTokenService::new();
+        let tokens = provider
+            .authenticate("maker".to_string(), Password::new("secret").unwrap())
+            .await
+            .unwrap();
+
+        let renewed = provider.renew_tokens(&tokens.refresh_token).await.unwrap();
+        assert_ne!(renewed.refresh_token, tokens.refresh_token);
+
+        provider
+            .revoke_tokens(&renewed.refresh_token)
+            .await
+            .unwrap();
+        let error = provider
+            .renew_tokens(&renewed.refresh_token)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, CreateTokensError::InvalidToken));
+    }
+}
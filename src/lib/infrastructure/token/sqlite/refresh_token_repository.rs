@@ -0,0 +1,223 @@
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::domain::token::models::refresh_token::{
+    DeleteRefreshTokenError, FindRefreshTokenError, ListRefreshTokensError, ListRefreshTokensQuery,
+    RefreshTokenPage, UpdateRefreshTokenError,
+};
+use crate::{
+    domain::token::{
+        models::{
+            refresh_token::{
+                CreateMode, CreateRefreshTokenError, CreateRefreshTokenOptions,
+                CreatedRefreshToken, IdempotencyKey, RefreshToken, RefreshTokenRow,
+            },
+            token::{SerialNumber, Tokens},
+        },
+        ports::{provider_token_service::ProviderType, refresh_token::RefreshTokenRepository},
+    },
+    infrastructure::db::sqlite::Sqlite,
+};
+
+/// The queries are checked at runtime, the macros of sqlx only checking them
+/// against the Postgres database of `DATABASE_URL`.
+#[derive(Debug, Clone)]
+pub struct SqliteRefreshTokenRepository {
+    sqlite: Arc<Sqlite>,
+}
+
+impl SqliteRefreshTokenRepository {
+    pub fn new(sqlite: Arc<Sqlite>) -> Self {
+        Self { sqlite }
+    }
+}
+
+impl RefreshTokenRepository for SqliteRefreshTokenRepository {
+    #[instrument(name = "refresh_tokens.create", skip_all, fields(db.system = "sqlite", tenant_id = %tenant_id, provider = %provider_type, serial_number = %serial_number))]
+    async fn create_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        provider_type: &ProviderType,
+        tokens: &Tokens,
+        serial_number: &SerialNumber,
+        options: &CreateRefreshTokenOptions,
+    ) -> Result<CreatedRefreshToken, CreateRefreshTokenError> {
+        let id = uuid::Uuid::new_v4();
+        let now = OffsetDateTime::now_utc();
+
+        // A single statement as on Postgres. A replaced row keeps its id, which
+        // tells it apart from an inserted one.
+        let row = sqlx::query_as::<_, RefreshTokenRow>(
+            r#"INSERT INTO refresh_tokens (id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at, idempotency_key)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7, ?8, ?9)
+               ON CONFLICT (tenant_id, serial_number) DO UPDATE
+               SET provider=excluded.provider, token=excluded.token, access_token=excluded.access_token,
+                   updated_at=excluded.updated_at, expires_at=excluded.expires_at,
+                   idempotency_key=COALESCE(excluded.idempotency_key, refresh_tokens.idempotency_key)
+               WHERE ?10 OR refresh_tokens.idempotency_key = excluded.idempotency_key
+               RETURNING id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at"#,
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .bind(provider_type.as_str())
+        .bind(serial_number.as_str())
+        .bind(tokens.refresh_token.expose())
+        .bind(tokens.access_token.expose())
+        .bind(now)
+        .bind(tokens.refresh_token_expires_at)
+        .bind(options.idempotency_key.as_ref().map(IdempotencyKey::as_str))
+        .bind(options.mode == CreateMode::Replace)
+        .fetch_optional(&*self.sqlite.get_pool())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                CreateRefreshTokenError::Duplicate {
+                    name: serial_number.clone(),
+                }
+            }
+            e => CreateRefreshTokenError::DatabaseError(e),
+        })?
+        .ok_or_else(|| CreateRefreshTokenError::Duplicate {
+            name: serial_number.clone(),
+        })?;
+
+        let inserted = row.id == id;
+        let refresh_token = RefreshToken::try_from(row)?;
+
+        if inserted {
+            info!(
+                "Creation of a refresh token for the next serial_number: {}",
+                serial_number
+            );
+            Ok(CreatedRefreshToken::Created(refresh_token))
+        } else {
+            info!(
+                "Replacement of the refresh token for the next serial_number: {}",
+                serial_number
+            );
+            Ok(CreatedRefreshToken::Replaced(refresh_token))
+        }
+    }
+
+    #[instrument(name = "refresh_tokens.find", skip_all, fields(db.system = "sqlite", tenant_id = %tenant_id, serial_number = %serial_number))]
+    async fn find_by_serial_number(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> Result<RefreshToken, FindRefreshTokenError> {
+        let row = sqlx::query_as::<_, RefreshTokenRow>(
+            r#"SELECT id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at FROM refresh_tokens WHERE tenant_id=?1 AND serial_number=?2"#,
+        )
+        .bind(tenant_id)
+        .bind(serial_number)
+        .fetch_optional(&*self.sqlite.get_pool())
+        .await?
+        .ok_or_else(|| FindRefreshTokenError::NotFound {
+            serial_number: serial_number.to_string(),
+        })?;
+
+        Ok(row.try_into()?)
+    }
+
+    #[instrument(name = "refresh_tokens.update", skip_all, fields(db.system = "sqlite", tenant_id = %tenant_id, serial_number = %serial_number))]
+    async fn update_tokens(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+        tokens: &Tokens,
+    ) -> Result<RefreshToken, UpdateRefreshTokenError> {
+        let row = sqlx::query_as::<_, RefreshTokenRow>(
+            r#"UPDATE refresh_tokens SET token=?3, access_token=?4, expires_at=?5, updated_at=?6 WHERE tenant_id=?1 AND serial_number=?2
+               RETURNING id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at"#,
+        )
+        .bind(tenant_id)
+        .bind(serial_number)
+        .bind(tokens.refresh_token.expose())
+        .bind(tokens.access_token.expose())
+        .bind(tokens.refresh_token_expires_at)
+        .bind(OffsetDateTime::now_utc())
+        .fetch_optional(&*self.sqlite.get_pool())
+        .await?
+        .ok_or_else(|| FindRefreshTokenError::NotFound {
+            serial_number: serial_number.to_string(),
+        })?;
+
+        info!(
+            "Renewal of the refresh token for the next serial_number: {}",
+            serial_number
+        );
+
+        Ok(row.try_into()?)
+    }
+
+    #[instrument(name = "refresh_tokens.list", skip_all, fields(db.system = "sqlite", tenant_id = %tenant_id))]
+    async fn list_refresh_tokens(
+        &self,
+        tenant_id: &uuid::Uuid,
+        query: &ListRefreshTokensQuery,
+    ) -> Result<RefreshTokenPage, ListRefreshTokensError> {
+        let provider = query.provider.as_ref().map(|provider| provider.as_str());
+        let serial_number = query
+            .serial_number
+            .as_ref()
+            .map(|serial_number| serial_number.as_str());
+
+        let rows = sqlx::query_as::<_, RefreshTokenRow>(
+            r#"SELECT id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at FROM refresh_tokens
+               WHERE tenant_id=?1 AND (?2 IS NULL OR provider=?2) AND (?3 IS NULL OR serial_number=?3)
+               ORDER BY serial_number LIMIT ?4 OFFSET ?5"#,
+        )
+        .bind(tenant_id)
+        .bind(provider)
+        .bind(serial_number)
+        .bind(i64::from(query.limit))
+        .bind(i64::from(query.offset))
+        .fetch_all(&*self.sqlite.get_pool())
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM refresh_tokens
+               WHERE tenant_id=?1 AND (?2 IS NULL OR provider=?2) AND (?3 IS NULL OR serial_number=?3)"#,
+        )
+        .bind(tenant_id)
+        .bind(provider)
+        .bind(serial_number)
+        .fetch_one(&*self.sqlite.get_pool())
+        .await?;
+
+        Ok(RefreshTokenPage {
+            items: rows
+                .into_iter()
+                .map(RefreshToken::try_from)
+                .collect::<Result<_, _>>()?,
+            total: total as u64,
+        })
+    }
+
+    #[instrument(name = "refresh_tokens.delete", skip_all, fields(db.system = "sqlite", tenant_id = %tenant_id, serial_number = %serial_number))]
+    async fn delete_refresh_token(
+        &self,
+        tenant_id: &uuid::Uuid,
+        serial_number: &str,
+    ) -> Result<RefreshToken, DeleteRefreshTokenError> {
+        let row = sqlx::query_as::<_, RefreshTokenRow>(
+            r#"DELETE FROM refresh_tokens WHERE tenant_id=?1 AND serial_number=?2
+               RETURNING id, tenant_id, provider, serial_number, token, access_token, created_at, updated_at, expires_at"#,
+        )
+        .bind(tenant_id)
+        .bind(serial_number)
+        .fetch_optional(&*self.sqlite.get_pool())
+        .await?
+        .ok_or_else(|| FindRefreshTokenError::NotFound {
+            serial_number: serial_number.to_string(),
+        })?;
+
+        info!(
+            "Deletion of the refresh token for the next serial_number: {}",
+            serial_number
+        );
+
+        Ok(row.try_into()?)
+    }
+}
@@ -1,4 +1,5 @@
 pub mod application;
+pub mod config;
 pub mod domain;
 pub mod env;
 pub mod infrastructure;